@@ -1,7 +1,11 @@
 #![allow(dead_code)]
-use maths::comb_optimization::OptimalSolution;
+use maths::comb_optimization::{birkhoff_decomposition, new_solver, solve_gap, solve_qap, solve_sparse, solve_transportation, verify_assignment, AssignmentError, Cost, KBestAssignments};
+use maths::number_theory::Rational;
 use rocket::serde::json::Json;
-use web::inputs::{parse, InputAssPblm};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt::Display;
+use web::{parse, parse_birkhoff, parse_gap, parse_k_best, parse_qap, parse_sparse, parse_transportation, CostType, EntryType, InputAssPblm, InputBirkhoff, InputGap, InputQap, InputTransportation};
 
 mod web;
 
@@ -20,16 +24,156 @@ extern crate rocket;
 
 #[post("/", format = "json", data = "<ass_pblm_input>")]
 fn solve_assignment_problem(ass_pblm_input: Json<InputAssPblm>) -> String {
-        match parse::<i32>(ass_pblm_input) {
-                Ok((u_invec, v_invec, c)) => {
-                        let mut ass_pblm_soln = OptimalSolution::new();
-                        ass_pblm_soln.init(u_invec, v_invec, c);
-                        let ass_pblm_soln = ass_pblm_soln.find_optimum_cost();
-                        return ass_pblm_soln;
-                }
-                Err(err) => println!("{:?}", err),
+        match ass_pblm_input.cost_type {
+                CostType::I32 => solve::<i32>(ass_pblm_input),
+                CostType::I64 => solve::<i64>(ass_pblm_input),
+                CostType::U64 => solve::<u64>(ass_pblm_input),
+                CostType::F64 => solve::<f64>(ass_pblm_input),
         }
-       "".to_string()
+}
+
+fn solve<N: Cost + DeserializeOwned + 'static>(ass_pblm_input: Json<InputAssPblm>) -> String {
+        let objective = ass_pblm_input.objective;
+        let repair_duals = ass_pblm_input.repair_duals;
+        let solver = ass_pblm_input.solver.unwrap_or_default();
+        let trace = ass_pblm_input.trace;
+        let (row_names, col_names) = (ass_pblm_input.row_names.clone(), ass_pblm_input.col_names.clone());
+        // An edge list is solved without building the dense matrix.
+        if ass_pblm_input.edges.is_some() {
+                let response = parse_sparse::<N>(ass_pblm_input).and_then(|c| solve_sparse(&c, objective)).map(|solution| to_pretty_json(&solution));
+                return response.unwrap_or_else(error_response);
+        }
+        // The k best assignments, best first.
+        if ass_pblm_input.k.is_some() {
+                let response = parse_k_best::<N>(ass_pblm_input).and_then(|(k, c)| KBestAssignments::new(c, objective, solver).take(k).collect::<Result<Vec<_>, _>>()).map(|solutions| to_pretty_json(&solutions));
+                return response.unwrap_or_else(error_response);
+        }
+        parse::<N>(ass_pblm_input)
+                .and_then(|(u_invec, v_invec, c)| {
+                        let mut ass_pblm_soln = new_solver::<N>(solver);
+                        ass_pblm_soln.set_objective(objective);
+                        ass_pblm_soln.set_repair_duals(repair_duals);
+                        ass_pblm_soln.set_trace(trace);
+                        ass_pblm_soln.set_names(row_names, col_names);
+                        ass_pblm_soln.init_with_forbidden(u_invec, v_invec, c);
+                        ass_pblm_soln.find_optimum_cost()
+                })
+                .map(|solution| to_pretty_json(&solution))
+                .unwrap_or_else(error_response)
+}
+
+#[post("/", format = "json", data = "<ass_pblm_input>")]
+fn verify_assignment_problem(ass_pblm_input: Json<InputAssPblm>) -> String {
+        match ass_pblm_input.cost_type {
+                CostType::I32 => verify::<i32>(ass_pblm_input),
+                CostType::I64 => verify::<i64>(ass_pblm_input),
+                CostType::U64 => verify::<u64>(ass_pblm_input),
+                CostType::F64 => verify::<f64>(ass_pblm_input),
+        }
+}
+
+// Checks a given assignment and duals, e.g. from another tool, against `c`.
+fn verify<N: Cost + DeserializeOwned>(ass_pblm_input: Json<InputAssPblm>) -> String {
+        let objective = ass_pblm_input.objective;
+        let assignment = ass_pblm_input.assignment.clone().unwrap_or_default();
+        parse::<N>(ass_pblm_input)
+                .and_then(|(u_invec, v_invec, c)| verify_assignment(&c, &assignment, &u_invec, &v_invec, objective))
+                .map(|report| to_pretty_json(&report))
+                .unwrap_or_else(error_response)
+}
+
+#[post("/", format = "json", data = "<transportation_input>")]
+fn solve_transportation_problem(transportation_input: Json<InputTransportation>) -> String {
+        match transportation_input.cost_type {
+                CostType::I32 => solve_transportation_json::<i32>(transportation_input),
+                CostType::I64 => solve_transportation_json::<i64>(transportation_input),
+                CostType::U64 => solve_transportation_json::<u64>(transportation_input),
+                CostType::F64 => solve_transportation_json::<f64>(transportation_input),
+        }
+}
+
+fn solve_transportation_json<N: Cost + DeserializeOwned>(transportation_input: Json<InputTransportation>) -> String {
+        let method = transportation_input.method;
+        parse_transportation::<N>(transportation_input)
+                .and_then(|(supply, demand, c)| solve_transportation(&supply, &demand, &c, method))
+                .map(|solution| to_pretty_json(&solution))
+                .unwrap_or_else(|err| error_json(&err))
+}
+
+#[post("/", format = "json", data = "<gap_input>")]
+fn solve_gap_problem(gap_input: Json<InputGap>) -> String {
+        match gap_input.cost_type {
+                CostType::I32 => solve_gap_json::<i32>(gap_input),
+                CostType::I64 => solve_gap_json::<i64>(gap_input),
+                CostType::U64 => solve_gap_json::<u64>(gap_input),
+                CostType::F64 => solve_gap_json::<f64>(gap_input),
+        }
+}
+
+fn solve_gap_json<N: Cost + DeserializeOwned>(gap_input: Json<InputGap>) -> String {
+        let (objective, method) = (gap_input.objective, gap_input.method);
+        parse_gap::<N>(gap_input)
+                .and_then(|(c, sizes, capacities)| solve_gap(&c, &sizes, &capacities, objective, method))
+                .map(|solution| to_pretty_json(&solution))
+                .unwrap_or_else(|err| error_json(&err))
+}
+
+#[post("/", format = "json", data = "<qap_input>")]
+fn solve_qap_problem(qap_input: Json<InputQap>) -> String {
+        match qap_input.cost_type {
+                CostType::I32 => solve_qap_json::<i32>(qap_input),
+                CostType::I64 => solve_qap_json::<i64>(qap_input),
+                CostType::U64 => solve_qap_json::<u64>(qap_input),
+                CostType::F64 => solve_qap_json::<f64>(qap_input),
+        }
+}
+
+fn solve_qap_json<N: Cost + DeserializeOwned>(qap_input: Json<InputQap>) -> String {
+        let method = qap_input.method;
+        parse_qap::<N>(qap_input)
+                .and_then(|(flow, distance)| solve_qap(&flow, &distance, method))
+                .map(|solution| to_pretty_json(&solution))
+                .unwrap_or_else(|err| error_json(&err))
+}
+
+#[post("/", format = "json", data = "<birkhoff_input>")]
+fn decompose_birkhoff(birkhoff_input: Json<InputBirkhoff>) -> String {
+        match birkhoff_input.entry_type {
+                EntryType::I32 => decompose_birkhoff_json::<i32>(birkhoff_input),
+                EntryType::I64 => decompose_birkhoff_json::<i64>(birkhoff_input),
+                EntryType::U64 => decompose_birkhoff_json::<u64>(birkhoff_input),
+                EntryType::F64 => decompose_birkhoff_json::<f64>(birkhoff_input),
+                EntryType::Rational => decompose_birkhoff_json::<Rational>(birkhoff_input),
+        }
+}
+
+fn decompose_birkhoff_json<N: Cost + DeserializeOwned>(birkhoff_input: Json<InputBirkhoff>) -> String {
+        parse_birkhoff::<N>(birkhoff_input)
+                .and_then(|x| birkhoff_decomposition(&x))
+                .map(|decomposition| to_pretty_json(&decomposition))
+                .unwrap_or_else(|err| error_json(&err))
+}
+
+fn error_response(err: AssignmentError) -> String {
+        match err {
+                AssignmentError::NoPerfectMatching { hall_violator } => serde_json::json!({ "infeasible": hall_violator }).to_string(),
+                err => error_json(&err),
+        }
+}
+
+// The tagged error with its message.
+fn error_json<E: Serialize + Display>(err: &E) -> String {
+        let mut response = serde_json::to_value(err).unwrap();
+        response["message"] = serde_json::Value::String(err.to_string());
+        response.to_string()
+}
+
+fn to_pretty_json<T: Serialize>(value: &T) -> String {
+        let buf = Vec::new();
+        let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
+        let mut ser = serde_json::Serializer::with_formatter(buf, formatter);
+        value.serialize(&mut ser).unwrap();
+        String::from_utf8(ser.into_inner()).unwrap()
 }
 
 use rocket::response::stream::{Event, EventStream};
@@ -42,14 +186,18 @@ fn events() -> EventStream![] {
                 .event("hello")
                 .id("1");
         let raw = stream::iter(vec![Event::data("a"), Event::data("b"), event]);
-        let stream = EventStream::from(raw);
-        stream
+        EventStream::from(raw)
 }
 
 #[rocket::main]
 async fn main() {
         if let Err(err) = rocket::build()
                 .mount("/co/assignment_problem/solve", routes![solve_assignment_problem])
+                .mount("/co/assignment_problem/verify", routes![verify_assignment_problem])
+                .mount("/co/transportation/solve", routes![solve_transportation_problem])
+                .mount("/co/gap/solve", routes![solve_gap_problem])
+                .mount("/co/qap/solve", routes![solve_qap_problem])
+                .mount("/co/birkhoff/decompose", routes![decompose_birkhoff])
                 .mount("/", routes![events])
         .launch().await {
                 println!("Rocket Rust couldn't take off successfully!");
@@ -0,0 +1,145 @@
+use crate::comb_optimization::assignment_problem::assignment_problem::generate_equality_graph;
+use crate::comb_optimization::assignment_problem::solution::AssignmentSolution;
+use crate::comb_optimization::cost::Cost;
+use petgraph::visit::EdgeRef;
+
+/// Every optimal assignment of the problem that `solution` solves, each
+/// ordered by row, at most `limit` of them.
+///
+/// With optimal duals an assignment is optimal exactly when it only uses
+/// cells with a zero reduced cost and leaves only rows or columns with a zero
+/// dual unassigned (complementary slackness). So the optimal assignments are
+/// the perfect matchings of the equality graph of the final reduced costs,
+/// `generate_equality_graph(c)` of the last state, that cover every vertex
+/// of the larger side whose dual is not zero. They are enumerated by
+/// backtracking over the smaller side, dropping a branch as soon as the
+/// rest of the graph has no such matching, so every branch ends in a
+/// solution.
+pub fn optimal_assignments<N: Cost>(solution: &AssignmentSolution<N>, limit: Option<usize>) -> Vec<Vec<(usize, usize)>> {
+        let mut assignments = Vec::new();
+        enumerate(solution, limit, |assignment| assignments.push(assignment.to_vec()));
+        assignments
+}
+
+/// Number of optimal assignments of the problem that `solution` solves,
+/// counting up to `limit`, without keeping them. See [`optimal_assignments`].
+pub fn count_optimal_assignments<N: Cost>(solution: &AssignmentSolution<N>, limit: Option<usize>) -> usize {
+        let mut count = 0;
+        enumerate(solution, limit, |_| count += 1);
+        count
+}
+
+fn enumerate<N: Cost>(solution: &AssignmentSolution<N>, limit: Option<usize>, mut found: impl FnMut(&[(usize, usize)])) {
+        let final_state = match solution.states.last() {
+                Some(final_state) => final_state,
+                None => return,
+        };
+        let (nrows, ncols) = final_state.c.shape();
+        let g = generate_equality_graph(&final_state.c, &[], &[]);
+
+        // The search runs over the smaller side, the "left" one.
+        let transposed = nrows > ncols;
+        let (left, right) = if transposed { (ncols, nrows) } else { (nrows, ncols) };
+        let mut adjacent: Vec<Vec<usize>> = vec![Vec::new(); left];
+        for edge in g.edge_references() {
+                let (row, col) = (edge.source().index().min(edge.target().index()), edge.source().index().max(edge.target().index()) - nrows);
+                if transposed {
+                        adjacent[col].push(row);
+                } else {
+                        adjacent[row].push(col);
+                }
+        }
+        for partners in adjacent.iter_mut() {
+                partners.sort_unstable();
+        }
+        let right_duals = if transposed { &solution.u } else { &solution.v };
+        let must_match: Vec<bool> = (0..right).map(|k| right_duals.get(k).is_some_and(|d| !d.is_zero())).collect();
+
+        let mut search = Search {
+                adjacent,
+                must_match,
+                partner: vec![None; right],
+                limit: limit.unwrap_or(usize::MAX),
+                count: 0,
+        };
+        if search.feasible(0) {
+                search.extend(0, &mut |partner: &[Option<usize>]| {
+                        let mut assignment: Vec<(usize, usize)> = partner.iter().enumerate().filter_map(|(k, l)| l.map(|l| if transposed { (k, l) } else { (l, k) })).collect();
+                        assignment.sort_unstable();
+                        found(&assignment);
+                });
+        }
+}
+
+struct Search {
+        adjacent: Vec<Vec<usize>>,
+        must_match: Vec<bool>,
+        // Left vertex matched to each right vertex so far.
+        partner: Vec<Option<usize>>,
+        limit: usize,
+        count: usize,
+}
+
+impl Search {
+        // Matches the left vertices from `next` on in every possible way.
+        fn extend(&mut self, next: usize, found: &mut impl FnMut(&[Option<usize>])) {
+                if self.count >= self.limit {
+                        return;
+                }
+                if next == self.adjacent.len() {
+                        self.count += 1;
+                        found(&self.partner);
+                        return;
+                }
+                for k in 0..self.adjacent[next].len() {
+                        let r = self.adjacent[next][k];
+                        if self.partner[r].is_some() {
+                                continue;
+                        }
+                        self.partner[r] = Some(next);
+                        if self.feasible(next + 1) {
+                                self.extend(next + 1, found);
+                        }
+                        self.partner[r] = None;
+                }
+        }
+
+        // Whether the left vertices from `next` on can be matched to free right
+        // vertices and, separately, the free right vertices that must be matched
+        // can be. By the Mendelsohn–Dulmage theorem one matching then does both.
+        fn feasible(&self, next: usize) -> bool {
+                let free: Vec<bool> = self.partner.iter().map(Option::is_none).collect();
+                let left: Vec<usize> = (next..self.adjacent.len()).collect();
+                let mut cover_left = vec![None; free.len()];
+                if !left.iter().all(|&l| augment(&self.adjacent, &free, &mut cover_left, l, &mut vec![false; free.len()])) {
+                        return false;
+                }
+
+                // The same search from the right: left vertices adjacent to each right vertex.
+                let mut reverse: Vec<Vec<usize>> = vec![Vec::new(); free.len()];
+                for &l in &left {
+                        for &r in &self.adjacent[l] {
+                                reverse[r].push(l);
+                        }
+                }
+                let free_left: Vec<bool> = (0..self.adjacent.len()).map(|l| l >= next).collect();
+                let mut cover_right = vec![None; self.adjacent.len()];
+                (0..free.len()).filter(|&r| free[r] && self.must_match[r]).all(|r| augment(&reverse, &free_left, &mut cover_right, r, &mut vec![false; self.adjacent.len()]))
+        }
+}
+
+// Kuhn's augmenting path search from `from` to the `allowed` vertices on the
+// other side, with `matched[to]` the vertex matched to `to`.
+fn augment(adjacent: &[Vec<usize>], allowed: &[bool], matched: &mut [Option<usize>], from: usize, visited: &mut [bool]) -> bool {
+        for &to in &adjacent[from] {
+                if !allowed[to] || visited[to] {
+                        continue;
+                }
+                visited[to] = true;
+                if matched[to].is_none_or(|other| augment(adjacent, allowed, matched, other, visited)) {
+                        matched[to] = Some(from);
+                        return true;
+                }
+        }
+        false
+}
@@ -3,158 +3,265 @@
 extern crate nalgebra;
 extern crate petgraph;
 
-use crate::comb_optimization::hungarian::hungarian_maximum_matching;
-use crate::graph_theory::bipartite::bipartite_undirected;
+use crate::comb_optimization::assignment_problem::auction::Bid;
+use crate::comb_optimization::assignment_problem::error::{checked, AssignmentError};
+use crate::comb_optimization::assignment_problem::sensitivity::cost_ranging;
+use crate::comb_optimization::assignment_problem::solution::AssignmentSolution;
+use crate::comb_optimization::cost::{min_cost, Cost};
+use crate::comb_optimization::hungarian::{hungarian_maximum_matching, HungarianStep};
+use crate::graph_theory::bipartite::BipartiteGraph;
+use crate::graph_theory::hopcroft_karp::hopcroft_karp;
 use nalgebra::DMatrix;
-use petgraph::algo::maximum_matching;
 use petgraph::graph::{Graph, NodeIndex};
 use petgraph::Undirected;
 use rocket::{
         self,
-        serde::{json::Json, Deserialize, Serialize},
+        serde::{Deserialize, Serialize},
 };
-use std::collections::HashMap;
 use std::collections::HashSet;
-use std::iter;
 
-#[derive(Serialize)]
-struct ProblemState {
+/// Which side of the problem a [`HallViolator`] is taken from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+        Rows,
+        Columns,
+}
+
+/// Certificate that no assignment covering the smaller side exists when some
+/// cells are forbidden: the `members` (rows or columns of `c`) have fewer
+/// allowed partners, `neighbours`, than there are members (Hall's theorem).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HallViolator {
+        pub side: Side,
+        pub members: Vec<usize>,
+        pub neighbours: Vec<usize>,
+}
+
+/// Whether the total of the assigned entries of `c` is minimised (costs) or
+/// maximised (profits, preferences).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Objective {
+        #[default]
+        #[serde(alias = "min")]
+        Minimize,
+        #[serde(alias = "max")]
+        Maximize,
+}
+
+/// One step of the Hungarian method: the duals, the reduced cost matrix and
+/// its equality graph. The first state holds the input, the last one the
+/// optimal matching.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProblemState<N: Cost> {
         // u = (u_1, u_2, ..., u_n)
-        u: Vec<i32>,
+        pub u: Vec<N>,
 
         // v = (v_1, v_2, ..., v_n)
-        v: Vec<i32>,
+        pub v: Vec<N>,
 
-        // c = cost matrix.
+        // c = cost matrix, `None` marks a forbidden cell.
         // c_i,j >= 0, u_i + v_j <= c_i,j
-        c: DMatrix<i32>,
+        pub c: DMatrix<Option<N>>,
         // Equality graph (bipartite)
-        g: Option<Graph<String, String, Undirected>>,
+        pub g: Option<Graph<String, String, Undirected>>,
 
         // Maximum matching (perfect matching gives the solution)
-        m: Option<Vec<(NodeIndex, NodeIndex)>>,
+        pub m: Option<Vec<(NodeIndex, NodeIndex)>>,
+
+        // Cells (i, j) at which the supplied duals were infeasible, set on the state
+        // whose duals replace them with row and column minima.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub repaired_cells: Option<Vec<(usize, usize)>>,
+
+        // The bid that led to this state, in the trace of the auction solver.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub bid: Option<Bid<N>>,
+
+        // The steps of the Hungarian maximum matching on `g` that gave the cover
+        // from which ϵ was chosen, on the states of the Hungarian method.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub hungarian: Option<Vec<HungarianStep<NodeIndex>>>,
 }
 
+/// Solves the assignment problem for a cost matrix with entries of any
+/// [`Cost`] type, e.g. `i64` for large integer costs or `f64` for distances.
+///
+/// A maximisation problem is solved as the minimisation of `M - c_ij`, where
+/// `M` is the largest entry of `c`. An m × n problem with m != n is solved
+/// on a square matrix padded with zero cost dummy rows (or columns), so that
+/// every row (or column) of the smaller side is assigned. The states are
+/// stored for that square minimisation problem and translated back when they
+/// are reported, so the trace always shows the original m × n matrix `c`.
+///
+/// Forbidden cells (`None`) never enter the equality graph and are skipped when
+/// ϵ is chosen. If no allowed cell is left to choose ϵ from, the rows (or
+/// columns) of the smaller side cannot all be assigned and the Hungarian tree
+/// gives a [`HallViolator`].
+///
+/// Malformed input (an empty `c`, duals of the wrong length or violating the
+/// dual constraints) and arithmetic overflow are returned as an
+/// [`AssignmentError`], the solver never panics on them. With
+/// `set_repair_duals(true)` infeasible duals are replaced by the row and column
+/// minima of `c` instead, see [`dual_violations`].
 #[derive(Serialize)]
-pub struct OptimalSolution {
-        states: Vec<ProblemState>,
+pub struct OptimalSolution<N: Cost> {
+        states: Vec<ProblemState<N>>,
+        objective: Objective,
+        repair_duals: bool,
+
+        // `M` while a maximisation problem is being solved as a minimisation.
+        #[serde(skip)]
+        offset: Option<N>,
+
+        // Shape of `c` before padding.
+        #[serde(skip)]
+        nrows: usize,
+        #[serde(skip)]
+        ncols: usize,
+
+        // Labels of the rows and columns in the equality graphs, if given.
+        #[serde(skip)]
+        row_names: Vec<String>,
+        #[serde(skip)]
+        col_names: Vec<String>,
+}
+
+impl<N: Cost> Default for OptimalSolution<N> {
+        fn default() -> Self {
+                Self::new()
+        }
 }
 
-impl OptimalSolution {
+impl<N: Cost> OptimalSolution<N> {
         pub fn new() -> Self {
-                Self { states: Vec::new() }
+                Self {
+                        states: Vec::new(),
+                        objective: Objective::Minimize,
+                        repair_duals: false,
+                        offset: None,
+                        nrows: 0,
+                        ncols: 0,
+                        row_names: Vec::new(),
+                        col_names: Vec::new(),
+                }
+        }
+
+        pub fn set_objective(&mut self, objective: Objective) {
+                self.objective = objective;
         }
 
-        pub fn init(&mut self, u_invec: Vec<i32>, v_invec: Vec<i32>, c: DMatrix<i32>) {
+        /// Replace infeasible supplied duals by row and column minima instead of
+        /// failing with [`AssignmentError::InfeasibleDuals`].
+        pub fn set_repair_duals(&mut self, repair_duals: bool) {
+                self.repair_duals = repair_duals;
+        }
+
+        /// Names of the rows and columns of `c`, e.g. workers and jobs, that label
+        /// the nodes of the equality graphs and the steps of the trace, and give
+        /// `named_assignment` in the solution. Either may be empty; unnamed rows
+        /// are labelled s1, s2, ... and unnamed columns t1, t2, ...
+        pub fn set_names(&mut self, row_names: Vec<String>, col_names: Vec<String>) {
+                self.row_names = row_names;
+                self.col_names = col_names;
+        }
+
+        pub fn init(&mut self, u_invec: Vec<N>, v_invec: Vec<N>, c: DMatrix<N>) {
+                self.init_with_forbidden(u_invec, v_invec, c.map(Some));
+        }
+
+        /// Like `init`, for a cost matrix in which `None` marks a forbidden cell.
+        pub fn init_with_forbidden(&mut self, u_invec: Vec<N>, v_invec: Vec<N>, c: DMatrix<Option<N>>) {
+                self.nrows = c.nrows();
+                self.ncols = c.ncols();
                 self.states.push(ProblemState {
-                        u: u_invec.clone(),
-                        v: v_invec.clone(),
-                        c: c.clone(),
+                        u: u_invec,
+                        v: v_invec,
+                        c,
                         g: None,
                         m: None,
+                        repaired_cells: None,
+                        bid: None,
+                        hungarian: None,
                 });
         }
 
-        pub fn find_optimum_cost(&mut self) -> String {
-                loop {
-                        let u: DMatrix<i32>;
-                        let v: DMatrix<i32>;
+        pub fn find_optimum_cost(&mut self) -> Result<AssignmentSolution<N>, AssignmentError> {
+                self.prepare()?;
 
-                        let last_saved_state = self.states.last().unwrap();
-                        let first_saved_state = self.states.get(0).unwrap();
+                let mut iterations = 0;
+                loop {
+                        iterations += 1;
+                        let last_saved_state = self.states.last().ok_or(AssignmentError::EmptyMatrix)?;
+                        let first_saved_state = self.states.first().ok_or(AssignmentError::EmptyMatrix)?;
 
                         let prev_c = &first_saved_state.c;
                         let prev_u_invec = &last_saved_state.u;
                         let prev_v_invec = &last_saved_state.v;
-                        let prev_g = &last_saved_state.g;
                         // let prev_m = &last_saved_state.m;
 
-                        // println!("{:?}", prev_g);
-
-                        u = generate_u_matrix(prev_c, prev_u_invec);
+                        let u = generate_u_matrix(prev_c, prev_u_invec);
                         // println!("u = {}", &u);
-                        let c_ij_intermediary = prev_c - &u;
+                        let c_ij_intermediary = subtract_matrix(prev_c, &u)?;
 
-                        v = generate_v_matrix(&c_ij_intermediary, prev_v_invec);
+                        let v = generate_v_matrix(&c_ij_intermediary, prev_v_invec);
                         // println!("v = {}", v);
-                        let c_ij = c_ij_intermediary - &v;
-                        println!("c_ij = {}", c_ij);
-
-                        let g = generate_equality_graph(&c_ij);
-
-                        println!("g = {:?}", g);
-                        let nrows = c_ij.nrows();
-                        let ncols = c_ij.ncols();
-
-                        let g_vertex_u_v_sets = bipartite_undirected(&g).unwrap();
-                        // println!("nodes_u = {:?}", g_vertex_u_v_sets.nodes_u);
-                        // println!("nodes_v = {:?}", g_vertex_u_v_sets.nodes_v);
-                        let max_matching = maximum_matching(&g);
-                        let mut matched_edges: HashSet<(NodeIndex, NodeIndex)> = HashSet::new();
-
-                        for edge in max_matching.edges() {
-                                // println!("m edge = {:?}", edge);
-                                matched_edges.insert(edge);
-                        }
-
-                        // let covered_nodes: HashSet<NodeIndex> = max_matching.nodes().into_iter().collect();
-                        // println!("covered_nodes = {:?}", covered_nodes);
-
-                        // ###########################
-                        // check is_perfect is working properly
-                        let perfect = max_matching.is_perfect();
-                        // println!("perfect = {:?}", &perfect);
-
-                        if perfect == true {
-                                let mut cost = 0;
-                                for edge in max_matching.edges() {
-                                        println!("edge = {:?}", &edge);
-                                        let node1 = edge.0;
-                                        let node2 = edge.1;
-                                        let node1_index = node1.index();
-                                        let node2_index = node2.index() - ncols;
-                                        if let Some(elem) = prev_c.get((node1_index % nrows) + (node2_index * ncols)) {
-                                                cost = cost + elem;
-                                        }
-                                }
-                                // the matching is optimal perfect matching if matching's cost equals ∑u + ∑v
-                                println!("cost = {:?}", cost);
-                                let u_sum: i32 = prev_u_invec.iter().sum();
-                                let v_sum: i32 = prev_v_invec.iter().sum();
-                                println!("uv_sum = {:?}", u_sum + v_sum);
-
-                                let buf = Vec::new();
-                                let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
-                                let mut ser = serde_json::Serializer::with_formatter(buf, formatter);
-                                self.states.serialize(&mut ser).unwrap();
-                                return String::from_utf8(ser.into_inner()).unwrap();
-                        }
-
-                        let mut node_index_weight_map: HashMap<usize, String> = HashMap::new();
+                        let c_ij = subtract_matrix(&c_ij_intermediary, &v)?;
+
+                        let g = generate_equality_graph(&c_ij, &self.row_names, &self.col_names);
+
+                        // After padding `c_ij` is square, rows are the nodes 0..n and columns the nodes n..2n.
+                        let n = c_ij.nrows();
+
+                        // The sides of the equality graph are known, so they are not recovered with
+                        // `bipartite_undirected`, which leaves out rows and columns without a zero.
+                        let g_vertex_u_v_sets = BipartiteGraph {
+                                nodes_u: (0..n).map(NodeIndex::new).collect(),
+                                nodes_v: (n..2 * n).map(NodeIndex::new).collect(),
+                        };
+                        // The sides are known, so the matching is found by Hopcroft–Karp.
+                        let max_matching = hopcroft_karp(&g, &g_vertex_u_v_sets).matching;
+                        let matched_edges: HashSet<(NodeIndex, NodeIndex)> = max_matching.iter().copied().collect();
+
+                        let perfect = max_matching.len() == n;
+
+                        if perfect {
+                                let m = max_matching;
+                                let u_invec = u.column(0).iter().copied().collect::<Vec<_>>();
+                                let v_invec = v.row(0).iter().copied().collect::<Vec<_>>();
+                                let final_state = ProblemState {
+                                        u: u_invec,
+                                        v: v_invec,
+                                        c: c_ij,
+                                        g: Some(g),
+                                        m: Some(m),
+                                        repaired_cells: None,
+                                        bid: None,
+                                        hungarian: None,
+                                };
+                                self.states.push(final_state);
 
-                        for node in g.node_indices() {
-                                node_index_weight_map.insert(node.index(), String::from(g.node_weight(node).unwrap()));
+                                return self.finish(iterations);
                         }
 
-                        if let Some(hungarian_output) = hungarian_maximum_matching(&g, node_index_weight_map, matched_edges, &g_vertex_u_v_sets) {
-                                let min_cover = hungarian_output.0;
-                                // println!("min_cover = {:?}", min_cover);
-                                // max_matching = hungarian_output.1;
+                        if let Some(hungarian_output) = hungarian_maximum_matching(&g, |node| g[node].clone(), matched_edges, &g_vertex_u_v_sets) {
+                                let (min_cover, _, hungarian_steps) = hungarian_output;
 
                                 // min_cover has the set of colored vertices from hungarian algm output. We need to find the
                                 // row index and column index of c_ij from the node indices. If the node is a member of the set U,
                                 // then node indices suffice as row index. If the node is a member of the set V, then we
-                                // need to subtract `ncols` from node index. Then we choose all the nodes in set V which are
+                                // need to subtract `n` from node index. Then we choose all the nodes in set V which are
                                 // not in the min_cover to get the correct column index.
                                 // we choose ϵ to be the minimum of c_ij with i and j calculated from node indices as above.
                                 let mut min_cover_row_indices: Vec<usize> = g_vertex_u_v_sets.nodes_u.iter().map(|x| x.index()).collect();
                                 let mut min_cover_red_indices: Vec<usize> = Vec::new();
-                                let mut min_cover_col_indices: Vec<usize> = g_vertex_u_v_sets.nodes_v.iter().map(|x| (x.index() - ncols)).collect();
+                                let mut min_cover_col_indices: Vec<usize> = g_vertex_u_v_sets.nodes_v.iter().map(|x| x.index() - n).collect();
 
                                 // min_cover contains uncolored nodes from U and colored nodes from V. We need colored node indices from
                                 // set U  and uncolored node indices from V to calculate epsilon.
                                 for node in &min_cover {
-                                        if g_vertex_u_v_sets.nodes_u.contains(&node) {
+                                        if g_vertex_u_v_sets.nodes_u.contains(node) {
                                                 // 'min_cover_row_indices' has all the node indices represnting each row of
                                                 // the c_ij matrix. We remove the elements corresponding to the uncolored vertices
                                                 // in min_cover
@@ -163,48 +270,49 @@ impl OptimalSolution {
                                                 // 'min_cover_col_indices' has all the node indices represnting each column of
                                                 // the c_ij matrix. We remove the elements corresponding to the red color vertices
                                                 // in our min_cover
-                                                min_cover_col_indices.retain(|value| *value != (node.index() - ncols));
-                                                min_cover_red_indices.push(node.index() - ncols);
+                                                min_cover_col_indices.retain(|value| *value != (node.index() - n));
+                                                min_cover_red_indices.push(node.index() - n);
                                         }
                                 }
 
                                 let min_cover_blue_indices = min_cover_row_indices.clone();
 
-                                // println!("min_cover_row_indices = {:?}", min_cover_row_indices);
-                                // println!("min_cover_col_indices = {:?}", min_cover_col_indices);
-                                // println!("min_cover_red_indices = {:?}", min_cover_red_indices);
-
                                 //  ϵ calculation
-                                let mut min_cover_matrix_elements: Vec<i32> = Vec::new();
+                                let mut min_cover_matrix_elements: Vec<N> = Vec::new();
                                 for i in &min_cover_row_indices {
                                         for j in &min_cover_col_indices {
-                                                if let Some(elem) = c_ij.get((i % nrows) + (j * ncols)) {
-                                                        min_cover_matrix_elements.push(*elem);
+                                                if let Some(elem) = c_ij[(*i, *j)] {
+                                                        min_cover_matrix_elements.push(elem);
                                                 }
                                         }
                                 }
 
                                 // println!("min_cover_matrix_elements = {:?}", min_cover_matrix_elements);
-                                let epsilon = min_cover_matrix_elements.iter().min().unwrap();
+                                // Without an allowed cell between the blue rows and the uncolored columns, the
+                                // blue rows have only the red columns as partners and there are fewer of those.
+                                let epsilon = match min_cost(min_cover_matrix_elements) {
+                                        Some(epsilon) => epsilon,
+                                        None => return Err(self.hall_violator(&min_cover_blue_indices, &min_cover_red_indices)?.into()),
+                                };
                                 // println!("epsilon = {:?}", epsilon);
                                 //
                                 // increase u vec elements and decrese v vector elements by epsilon
                                 //
                                 let u_invec_col = u.column(0);
-                                let mut u_invec = u_invec_col.into_iter().map(|x| *x).collect::<Vec<_>>();
+                                let mut u_invec = u_invec_col.iter().copied().collect::<Vec<_>>();
 
                                 let v_invec_col = v.row(0);
-                                let mut v_invec = v_invec_col.into_iter().map(|x| *x).collect::<Vec<_>>();
+                                let mut v_invec = v_invec_col.iter().copied().collect::<Vec<_>>();
 
                                 for (i, x) in u_invec.iter_mut().enumerate() {
                                         if min_cover_blue_indices.contains(&i) {
-                                                *x = *x + epsilon;
+                                                *x = checked(x.checked_add(epsilon), "u_i + ϵ")?;
                                         }
                                 }
 
                                 for (i, x) in v_invec.iter_mut().enumerate() {
                                         if min_cover_red_indices.contains(&i) {
-                                                *x = *x - epsilon;
+                                                *x = checked(x.checked_sub(epsilon), "v_j - ϵ")?;
                                         }
                                 }
 
@@ -216,6 +324,9 @@ impl OptimalSolution {
                                         c: c_ij,
                                         g: Some(g),
                                         m: None,
+                                        repaired_cells: None,
+                                        bid: None,
+                                        hungarian: Some(hungarian_steps),
                                 };
 
                                 self.states.push(new_state);
@@ -224,6 +335,300 @@ impl OptimalSolution {
         }
 }
 
+impl<N: Cost> OptimalSolution<N> {
+        // Turns the initial state into a square minimisation problem with feasible
+        // (or empty) duals, on the first call only. Every solver backend starts from
+        // the prepared problem.
+        pub(super) fn prepare(&mut self) -> Result<(), AssignmentError> {
+                self.prepare_keeping_violations(false).map(|_| ())
+        }
+
+        // Like `prepare`, but with `keep_violations` infeasible supplied duals are
+        // neither rejected nor repaired: the cells at which they are infeasible are
+        // returned and the caller has to repair them.
+        pub(super) fn prepare_keeping_violations(&mut self, keep_violations: bool) -> Result<Vec<(usize, usize)>, AssignmentError> {
+                let mut violations = Vec::new();
+                if self.states.len() == 1 {
+                        violations = self.check_input()?;
+                        if !violations.is_empty() && !self.repair_duals && !keep_violations {
+                                return Err(AssignmentError::InfeasibleDuals { cells: violations });
+                        }
+                        if self.objective == Objective::Maximize {
+                                self.convert_to_minimization()?;
+                        }
+                        self.pad_to_square()?;
+                        if !violations.is_empty() && !keep_violations {
+                                self.repair_duals(violations.clone())?;
+                        }
+                }
+                Ok(violations)
+        }
+
+        // Replaces the duals of the initial state, before the problem is prepared.
+        pub(super) fn set_initial_duals(&mut self, u_invec: Vec<N>, v_invec: Vec<N>) {
+                if let Some(first_state) = self.states.first_mut() {
+                        first_state.u = u_invec;
+                        first_state.v = v_invec;
+                }
+        }
+
+        // The prepared problem: the square cost matrix and the latest duals.
+        #[allow(clippy::type_complexity)]
+        pub(super) fn prepared(&self) -> Result<(&DMatrix<Option<N>>, &[N], &[N]), AssignmentError> {
+                let first_state = self.states.first().ok_or(AssignmentError::EmptyMatrix)?;
+                let last_state = self.states.last().ok_or(AssignmentError::EmptyMatrix)?;
+                Ok((&first_state.c, &last_state.u, &last_state.v))
+        }
+
+        pub(super) fn push_state(&mut self, state: ProblemState<N>) {
+                self.states.push(state);
+        }
+
+        // Reports the recorded states, the last of which holds a perfect matching of
+        // the prepared problem, in terms of the original problem. The cost ranging is
+        // taken from the reduced costs of the prepared problem, where the dummy cells
+        // stand for leaving a row or column unassigned.
+        pub(super) fn finish(&self, iterations: usize) -> Result<AssignmentSolution<N>, AssignmentError> {
+                let final_state = self.states.last().ok_or(AssignmentError::EmptyMatrix)?;
+                let ranging = match &final_state.m {
+                        Some(m) => cost_ranging(&final_state.c, m)?.slice((0, 0), (self.nrows, self.ncols)).into_owned(),
+                        None => DMatrix::from_element(self.nrows, self.ncols, None),
+                };
+                let states = self.states.iter().enumerate().map(|(i, state)| self.original_state(i, state)).collect::<Result<Vec<_>, _>>()?;
+                let solution = AssignmentSolution::from_states(states, iterations, ranging)?;
+                if self.row_names.is_empty() && self.col_names.is_empty() {
+                        return Ok(solution);
+                }
+                Ok(solution.with_names(&self.row_names, &self.col_names))
+        }
+
+        // Rejects an empty cost matrix and supplied duals that do not fit its shape,
+        // and returns the cells at which the supplied duals are infeasible.
+        fn check_input(&self) -> Result<Vec<(usize, usize)>, AssignmentError> {
+                let first_state = self.states.first().ok_or(AssignmentError::EmptyMatrix)?;
+                if self.nrows == 0 || self.ncols == 0 {
+                        return Err(AssignmentError::EmptyMatrix);
+                }
+                if !first_state.u.is_empty() && first_state.u.len() != self.nrows {
+                        return Err(AssignmentError::DualLengthMismatch {
+                                side: Side::Rows,
+                                expected: self.nrows,
+                                found: first_state.u.len(),
+                        });
+                }
+                if !first_state.v.is_empty() && first_state.v.len() != self.ncols {
+                        return Err(AssignmentError::DualLengthMismatch {
+                                side: Side::Columns,
+                                expected: self.ncols,
+                                found: first_state.v.len(),
+                        });
+                }
+                for (side, names, expected) in [(Side::Rows, &self.row_names, self.nrows), (Side::Columns, &self.col_names, self.ncols)] {
+                        if !names.is_empty() && names.len() != expected {
+                                return Err(AssignmentError::NameLengthMismatch { side, expected, found: names.len() });
+                        }
+                }
+                dual_violations(&first_state.u, &first_state.v, &first_state.c, self.objective)
+        }
+
+        // Records the cells at which the supplied duals were infeasible and continues
+        // from u_i = min_j c_i,j and v_j = min_i (c_i,j - u_i) of the prepared
+        // (square, minimisation) problem, which are always feasible.
+        fn repair_duals(&mut self, violations: Vec<(usize, usize)>) -> Result<(), AssignmentError> {
+                let c = &self.states.first().ok_or(AssignmentError::EmptyMatrix)?.c;
+                let u = generate_u_matrix(c, &[]);
+                let c_ij_intermediary = subtract_matrix(c, &u)?;
+                let v = generate_v_matrix(&c_ij_intermediary, &[]);
+                let c_ij = subtract_matrix(&c_ij_intermediary, &v)?;
+
+                self.states.push(ProblemState {
+                        u: u.column(0).iter().copied().collect(),
+                        v: v.row(0).iter().copied().collect(),
+                        c: c_ij,
+                        g: None,
+                        m: None,
+                        repaired_cells: Some(violations),
+                        bid: None,
+                        hungarian: None,
+                });
+                Ok(())
+        }
+
+        // Replaces the initial state of a maximisation problem by the equivalent
+        // minimisation problem c'_i,j = M - c_i,j. Duals supplied for the original
+        // problem (u_i + v_j >= c_i,j) are mapped as in `original_duals`.
+        fn convert_to_minimization(&mut self) -> Result<(), AssignmentError> {
+                let rows_get_offset = self.nrows <= self.ncols;
+                let first_state = self.states.first_mut().ok_or(AssignmentError::EmptyMatrix)?;
+                let offset = first_state.c.iter().flatten().fold(N::zero(), |m, &x| if x > m { x } else { m });
+
+                first_state.c = offset_matrix(&first_state.c, offset)?;
+                first_state.u = negate_duals(&first_state.u, offset, rows_get_offset)?;
+                first_state.v = negate_duals(&first_state.v, offset, !rows_get_offset)?;
+                self.offset = Some(offset);
+                Ok(())
+        }
+
+        // Pads `c` with zero cost dummy rows or columns to a square matrix. Supplied
+        // duals are extended so that they stay feasible for the dummy entries.
+        fn pad_to_square(&mut self) -> Result<(), AssignmentError> {
+                let (nrows, ncols) = (self.nrows, self.ncols);
+                let n = nrows.max(ncols);
+                let first_state = self.states.first_mut().ok_or(AssignmentError::EmptyMatrix)?;
+
+                let c = &first_state.c;
+                first_state.c = DMatrix::from_fn(n, n, |i, j| if i < nrows && j < ncols { c[(i, j)] } else { Some(N::zero()) });
+
+                // A dummy row needs u_d <= 0 - v_j for every column j, a dummy column v_d <= 0 - u_i.
+                if !first_state.u.is_empty() && nrows < n {
+                        let negated_v = negate_duals(&first_state.v, N::zero(), false)?;
+                        first_state.u.resize(n, min_cost(negated_v).unwrap_or_else(N::zero));
+                }
+                if !first_state.v.is_empty() && ncols < n {
+                        let negated_u = negate_duals(&first_state.u, N::zero(), false)?;
+                        first_state.v.resize(n, min_cost(negated_u).unwrap_or_else(N::zero));
+                }
+                Ok(())
+        }
+
+        // Inverse of `pad_to_square` and `convert_to_minimization` for a recorded state.
+        //
+        // The dummy rows (or columns) are dropped. Their duals are moved onto the real
+        // duals first: with t = max u_d, u_i - t and v_j + t keep every reduced cost of a
+        // real entry and give v_j <= 0, the dual condition for a column that may stay
+        // unassigned. ∑u + ∑v is then the cost of the assignment at the optimum.
+        //
+        // The reduced costs u_i + v_j - c_i,j of a maximisation problem equal the reduced
+        // costs of the minimisation problem, so besides the duals only the first state's
+        // `c` changes.
+        fn original_state(&self, index: usize, state: &ProblemState<N>) -> Result<ProblemState<N>, AssignmentError> {
+                let (nrows, ncols) = (self.nrows, self.ncols);
+                let mut state = state.clone();
+
+                if !state.u.is_empty() && !state.v.is_empty() && nrows != ncols {
+                        if nrows < ncols {
+                                let t = state.u[nrows..].iter().fold(state.u[nrows], |m, &x| if x > m { x } else { m });
+                                state.u = state.u.iter().map(|&x| checked(x.checked_sub(t), "u_i - t")).collect::<Result<_, _>>()?;
+                                state.v = state.v.iter().map(|&x| checked(x.checked_add(t), "v_j + t")).collect::<Result<_, _>>()?;
+                        } else {
+                                let t = state.v[ncols..].iter().fold(state.v[ncols], |m, &x| if x > m { x } else { m });
+                                state.u = state.u.iter().map(|&x| checked(x.checked_add(t), "u_i + t")).collect::<Result<_, _>>()?;
+                                state.v = state.v.iter().map(|&x| checked(x.checked_sub(t), "v_j - t")).collect::<Result<_, _>>()?;
+                        }
+                }
+                state.u.truncate(nrows);
+                state.v.truncate(ncols);
+                state.c = state.c.slice((0, 0), (nrows, ncols)).into_owned();
+
+                if let Some(offset) = self.offset {
+                        let rows_get_offset = nrows <= ncols;
+                        state.u = negate_duals(&state.u, offset, rows_get_offset)?;
+                        state.v = negate_duals(&state.v, offset, !rows_get_offset)?;
+                        if index == 0 {
+                                state.c = offset_matrix(&state.c, offset)?;
+                        }
+                }
+
+                if state.g.is_some() {
+                        state.g = Some(generate_equality_graph(&state.c, &self.row_names, &self.col_names));
+                }
+
+                // The nodes of the padded graph are the rows 0..n and the columns n..2n.
+                let n = nrows.max(ncols);
+                if let Some(steps) = &state.hungarian {
+                        let original_node = |node: NodeIndex| match node.index() {
+                                i if i < n => (i < nrows).then(|| NodeIndex::new(i)),
+                                j => (j - n < ncols).then(|| NodeIndex::new(nrows + j - n)),
+                        };
+                        state.hungarian = Some(steps.iter().filter_map(|step| step.filter_map_nodes(original_node)).collect());
+                }
+
+                if let Some(m) = &state.m {
+                        let pairs = m.iter().map(|(row_node, col_node)| (row_node.index(), col_node.index() - n));
+                        let real_pairs = pairs.filter(|&(i, j)| i < nrows && j < ncols);
+                        state.m = Some(real_pairs.map(|(i, j)| (NodeIndex::new(i), NodeIndex::new(nrows + j))).collect());
+                }
+
+                Ok(state)
+        }
+}
+
+impl<N: Cost> OptimalSolution<N> {
+        // Turns the blue rows and red columns of a Hungarian tree that cannot grow any
+        // further into a Hall violator of the original problem.
+        //
+        // When m <= n the blue rows are all real rows and their allowed partners are all
+        // red. When m > n the dummy columns are all red (they are allowed for every row),
+        // so the real columns that are not red have only the rows that are not blue as
+        // partners, and there are fewer of those. Fails before the problem is prepared.
+        pub(super) fn hall_violator(&self, blue_rows: &[usize], red_cols: &[usize]) -> Result<HallViolator, AssignmentError> {
+                let (nrows, ncols) = (self.nrows, self.ncols);
+                let (c, _, _) = self.prepared()?;
+
+                if nrows <= ncols {
+                        let mut members = blue_rows.to_vec();
+                        members.sort_unstable();
+                        let neighbours = (0..ncols).filter(|&j| members.iter().any(|&i| c[(i, j)].is_some())).collect();
+                        Ok(HallViolator {
+                                side: Side::Rows,
+                                members,
+                                neighbours,
+                        })
+                } else {
+                        let members: Vec<usize> = (0..ncols).filter(|j| !red_cols.contains(j)).collect();
+                        let neighbours = (0..nrows).filter(|&i| members.iter().any(|&j| c[(i, j)].is_some())).collect();
+                        Ok(HallViolator {
+                                side: Side::Columns,
+                                members,
+                                neighbours,
+                        })
+                }
+        }
+}
+
+/// Cells (i, j) of `c` at which the duals violate u_i + v_j <= c_i,j, or
+/// u_i + v_j >= c_i,j for a maximisation problem. Floating point duals may
+/// exceed the bound by `Cost::tolerance()`. Forbidden cells carry no
+/// constraint. If `u` or `v` is empty it is computed by the solver, which
+/// always gives feasible duals, so there is nothing to check.
+///
+/// `u` and `v` are expected to have one entry per row and column of `c`.
+pub fn dual_violations<N: Cost>(u: &[N], v: &[N], c: &DMatrix<Option<N>>, objective: Objective) -> Result<Vec<(usize, usize)>, AssignmentError> {
+        let mut violations = Vec::new();
+        if u.is_empty() || v.is_empty() {
+                return Ok(violations);
+        }
+        for row in 0..c.nrows() {
+                for col in 0..c.ncols() {
+                        if let Some(c_ij) = c[(row, col)] {
+                                let uv = checked(u[row].checked_add(v[col]), "u_i + v_j")?;
+                                let feasible = match objective {
+                                        Objective::Minimize => uv <= c_ij || checked(uv.checked_sub(c_ij), "u_i + v_j - c_ij")?.is_zero(),
+                                        Objective::Maximize => uv >= c_ij || checked(c_ij.checked_sub(uv), "c_ij - u_i - v_j")?.is_zero(),
+                                };
+                                if !feasible {
+                                        violations.push((row, col));
+                                }
+                        }
+                }
+        }
+        Ok(violations)
+}
+
+// Maps duals between a maximisation problem and the minimisation of M - c_i,j.
+// The side that is completely assigned carries M: x' = M - x. The other side is
+// negated: x' = -x. The map is its own inverse.
+fn negate_duals<N: Cost>(duals: &[N], offset: N, with_offset: bool) -> Result<Vec<N>, AssignmentError> {
+        let base = if with_offset { offset } else { N::zero() };
+        duals.iter().map(|&x| checked(base.checked_sub(x), "negated dual")).collect()
+}
+
+// M - c_i,j for every allowed cell.
+fn offset_matrix<N: Cost>(c: &DMatrix<Option<N>>, offset: N) -> Result<DMatrix<Option<N>>, AssignmentError> {
+        let entries = c.iter().map(|x| x.map(|x| checked(offset.checked_sub(x), "M - c_ij")).transpose()).collect::<Result<Vec<_>, _>>()?;
+        Ok(DMatrix::from_vec(c.nrows(), c.ncols(), entries))
+}
+
 // impl Serialize for OptimalSolution {
 //         fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
 //         where
@@ -234,41 +639,41 @@ impl OptimalSolution {
 //         }
 // }
 
-fn generate_u_matrix(c: &DMatrix<i32>, u: &Vec<i32>) -> DMatrix<i32> {
+// Element-wise `a - b`, forbidden cells stay forbidden. Both matrices are expected
+// to have the same shape.
+pub(super) fn subtract_matrix<N: Cost>(a: &DMatrix<Option<N>>, b: &DMatrix<N>) -> Result<DMatrix<Option<N>>, AssignmentError> {
+        let entries = a.iter().zip(b.iter()).map(|(x, &y)| x.map(|x| checked(x.checked_sub(y), "reduced cost")).transpose()).collect::<Result<Vec<_>, _>>()?;
+        Ok(DMatrix::from_vec(a.nrows(), a.ncols(), entries))
+}
+
+pub(super) fn generate_u_matrix<N: Cost>(c: &DMatrix<Option<N>>, u: &[N]) -> DMatrix<N> {
         let nrows = c.nrows();
         let ncols = c.ncols();
-        let mut u = u.clone();
+        let mut u = u.to_vec();
 
-        if u.len() == 0 {
+        // A row without any allowed cell gets u_i = 0; it is reported as infeasible later.
+        if u.is_empty() {
                 for row in c.row_iter() {
-                        u.push(row.min());
+                        u.push(min_cost(row.iter().flatten().copied()).unwrap_or_else(N::zero));
                 }
         }
 
-        let frequencies = vec![ncols; ncols];
         // u_i to be the smallest entry in row i
         // below expression will convert 1-dim u into nrows x ncols sized matrix with the first
         // element of each row repeating in each colums. For ex. if 3 is element at (0,0), then (0,1)
         // (0,2) and (0,3) will be 3.
-        let u_modifier_vals = u
-                .into_iter()
-                .zip(frequencies.into_iter())
-                .map(|(n, t)| iter::repeat(n).take(t))
-                .fold(Vec::new(), |mut acc, x| {
-                        acc.extend(x);
-                        acc
-                });
+        let u_modifier_vals = u.into_iter().flat_map(|n| vec![n; ncols]).collect::<Vec<_>>();
         DMatrix::from_row_slice(nrows, ncols, &u_modifier_vals[..])
 }
 
-fn generate_v_matrix(c: &DMatrix<i32>, v: &Vec<i32>) -> DMatrix<i32> {
+pub(super) fn generate_v_matrix<N: Cost>(c: &DMatrix<Option<N>>, v: &[N]) -> DMatrix<N> {
         let nrows = c.nrows();
         let ncols = c.ncols();
-        let mut v = v.clone();
+        let mut v = v.to_vec();
 
-        if v.len() == 0 {
+        if v.is_empty() {
                 for column in c.column_iter() {
-                        v.push(column.min());
+                        v.push(min_cost(column.iter().flatten().copied()).unwrap_or_else(N::zero));
                 }
         }
 
@@ -277,43 +682,54 @@ fn generate_v_matrix(c: &DMatrix<i32>, v: &Vec<i32>) -> DMatrix<i32> {
         DMatrix::from_row_slice(nrows, ncols, &v_modifier_vals[..])
 }
 
-fn generate_equality_graph(c_ij: &DMatrix<i32>) -> Graph<String, String, petgraph::Undirected> {
+pub(super) fn generate_equality_graph<N: Cost>(c_ij: &DMatrix<Option<N>>, row_names: &[String], col_names: &[String]) -> Graph<String, String, petgraph::Undirected> {
+        // Floating point entries count as zero when they are within `Cost::tolerance()` of it.
+        generate_threshold_graph(c_ij, row_names, col_names, |x| x.is_zero())
+}
+
+// Bipartite graph of the rows and columns of `c_ij` with an edge for every allowed
+// cell whose entry satisfies `is_edge`. The nodes are labelled by `node_label`.
+pub(super) fn generate_threshold_graph<N: Cost>(c_ij: &DMatrix<Option<N>>, row_names: &[String], col_names: &[String], is_edge: impl Fn(N) -> bool) -> Graph<String, String, petgraph::Undirected> {
         let nrows = c_ij.nrows();
         let ncols = c_ij.ncols();
 
         let mut g: Graph<String, String, petgraph::Undirected> = Graph::new_undirected();
         //let mut g: UnGraph<String, String> = UnGraph::new_undirected();
 
-        for i in 0..(nrows as i32) {
-                let weight = "s".to_string() + &(i + 1).to_string();
-                g.add_node(weight);
+        for i in 0..nrows {
+                g.add_node(node_label(row_names, "s", i));
         }
 
-        for j in 0..(ncols as i32) {
-                let weight = "s".to_string() + &(j + 1).to_string();
-                g.add_node(weight);
+        for j in 0..ncols {
+                g.add_node(node_label(col_names, "t", j));
         }
 
         // let is_bipartite = is_bipartite_undirected(&g, NodeIndex::new(0));
         // iterate the elements of the cost matrix in a column major way and calculate the row index
         // and column index backward from the element position ('i' below). Since the sets U and V of the bipartite
-        // graph cannot have the same index numbers, we add numger of columns to column index to get the node
-        // index of the second node of the edge in the graph
+        // graph cannot have the same index numbers, we add number of rows to column index to get the node
+        // index of the second node of the edge in the graph. Forbidden cells never become edges.
         for (i, element) in c_ij.iter().enumerate() {
-                match *element {
-                        0 => {
-                                let row_index = i % nrows;
-                                let col_index = i / nrows;
-
-                                g.add_edge(
-                                        NodeIndex::new(row_index),
-                                        NodeIndex::new(ncols + col_index),
-                                        format!("{} -> {}", row_index, ncols + col_index),
-                                );
-                        }
-                        _ => (),
+                if element.is_some_and(&is_edge) {
+                        let row_index = i % nrows;
+                        let col_index = i / nrows;
+
+                        g.add_edge(
+                                NodeIndex::new(row_index),
+                                NodeIndex::new(nrows + col_index),
+                                format!("{} -> {}", row_index, nrows + col_index),
+                        );
                 }
         }
 
         g
 }
+
+/// The name of row or column `index`, or `prefix` followed by its 1-based
+/// index if it has none, e.g. a dummy row of a padded problem.
+pub(super) fn node_label(names: &[String], prefix: &str, index: usize) -> String {
+        match names.get(index) {
+                Some(name) => name.clone(),
+                None => format!("{}{}", prefix, index + 1),
+        }
+}
@@ -0,0 +1,396 @@
+use crate::comb_optimization::assignment_problem::assignment_problem::{generate_u_matrix, generate_v_matrix, subtract_matrix, Objective, OptimalSolution, ProblemState, Side};
+use crate::comb_optimization::assignment_problem::error::{checked, AssignmentError};
+use crate::comb_optimization::assignment_problem::shortest_path::{augment, matching_of, reduced_costs};
+use crate::comb_optimization::assignment_problem::solution::AssignmentSolution;
+use crate::comb_optimization::assignment_problem::solver::AssignmentSolver;
+use crate::comb_optimization::cost::{min_cost, Cost};
+use nalgebra::DMatrix;
+use rocket::serde::{Deserialize, Serialize};
+
+// ϵ is divided by this factor from one scaling phase to the next.
+const SCALING_FACTOR: usize = 4;
+
+// A phase may take this many bids per row. Beyond that prices are taken to be
+// stuck, e.g. floating point prices that a bid no longer changes, and the
+// assignment is left to the shortest augmenting paths.
+const MAX_BIDS_PER_ROW: usize = 1_000;
+
+/// Who bids in [`AuctionSolver`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuctionDirection {
+        /// Unassigned rows bid for columns.
+        #[default]
+        Forward,
+        /// Unassigned columns bid for rows.
+        Reverse,
+        /// Forward bids until the matching grows, then reverse bids until it
+        /// grows, and so on.
+        ForwardReverse,
+}
+
+/// One bid of the auction, recorded on the state it leads to.
+///
+/// The indices are those of the square minimisation problem the auction runs
+/// on, so a dummy row or column of a rectangular problem bids too.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Bid<N: Cost> {
+        /// `Rows` for a forward bid of row `bidder` for column `target`,
+        /// `Columns` for a reverse bid of column `bidder` for row `target`.
+        pub side: Side,
+        pub bidder: usize,
+        pub target: usize,
+        /// How far the dual of `target` falls: the bidder's margin between its
+        /// best and its second best choice, plus ϵ.
+        pub increment: N,
+        pub epsilon: N,
+}
+
+/// Solves the assignment problem with Bertsekas' auction algorithm and ϵ-scaling.
+///
+/// On the minimisation problem the column duals v_j act as (negated) prices.
+/// An unassigned row i bids for the column j that minimises c_i,j - v_j: v_j
+/// falls by the margin to the row's second best column plus ϵ, and the row
+/// takes j from its previous owner. The duals stay ϵ-feasible,
+/// u_i + v_j <= c_i,j + ϵ, and tight on the assigned cells. A reverse auction
+/// lets unassigned columns bid for rows on u in the same way, see
+/// [`AuctionDirection`].
+///
+/// Each phase starts with no assignment and the prices of the previous one,
+/// with ϵ divided by 4 from a quarter of the spread of the costs down to 1.
+/// For floating point costs the last ϵ is the spread times
+/// `Cost::tolerance()`, but no less than one ulp of the largest |c_i,j|, so
+/// that a bid still moves a price. That leaves the assignment within n ϵ of
+/// the optimum. The duals are then made exact with u_i = min_j (c_i,j - v_j),
+/// the assigned cells that are no longer tight are dropped and their rows are
+/// assigned again by the shortest augmenting paths of
+/// [`ShortestPathSolver`](super::ShortestPathSolver), usually only a few. A
+/// phase that takes more than 1000 bids per row ends the scaling early and
+/// leaves more rows to those paths.
+///
+/// Objective, forbidden cells, rectangular matrices and supplied duals (the
+/// initial prices) are handled as by [`OptimalSolution`]. Only the prepared
+/// input and the final state are recorded, unless `set_trace(true)` records a
+/// state with its [`Bid`] after every bid. `iterations` counts the bids and
+/// the augmenting paths.
+pub struct AuctionSolver<N: Cost> {
+        problem: OptimalSolution<N>,
+        direction: AuctionDirection,
+        trace: bool,
+}
+
+impl<N: Cost> Default for AuctionSolver<N> {
+        fn default() -> Self {
+                Self::new()
+        }
+}
+
+impl<N: Cost> AuctionSolver<N> {
+        pub fn new() -> Self {
+                Self {
+                        problem: OptimalSolution::new(),
+                        direction: AuctionDirection::Forward,
+                        trace: false,
+                }
+        }
+
+        pub fn set_direction(&mut self, direction: AuctionDirection) {
+                self.direction = direction;
+        }
+}
+
+impl<N: Cost> AssignmentSolver<N> for AuctionSolver<N> {
+        fn set_objective(&mut self, objective: Objective) {
+                self.problem.set_objective(objective);
+        }
+
+        fn set_repair_duals(&mut self, repair_duals: bool) {
+                self.problem.set_repair_duals(repair_duals);
+        }
+
+        fn set_names(&mut self, row_names: Vec<String>, col_names: Vec<String>) {
+                self.problem.set_names(row_names, col_names);
+        }
+
+        fn set_trace(&mut self, trace: bool) {
+                self.trace = trace;
+        }
+
+        fn init_with_forbidden(&mut self, u_invec: Vec<N>, v_invec: Vec<N>, c: DMatrix<Option<N>>) {
+                self.problem.init_with_forbidden(u_invec, v_invec, c);
+        }
+
+        fn find_optimum_cost(&mut self) -> Result<AssignmentSolution<N>, AssignmentError> {
+                self.problem.prepare_keeping_violations(false)?;
+                let (c, u, v) = self.problem.prepared()?;
+                let c = c.clone();
+                let n = c.nrows();
+
+                // Bidding never ends without an assignment, so that is checked first.
+                if c.iter().any(|x| x.is_none()) {
+                        let allowed = c.map(|x| x.map(|_| N::zero()));
+                        let (mut u, mut v) = (vec![N::zero(); n], vec![N::zero(); n]);
+                        let mut row_of_col = vec![None; n];
+                        for root in 0..n {
+                                if let Some((blue_rows, red_cols)) = augment(&allowed, &mut u, &mut v, &mut row_of_col, root)? {
+                                        return Err(self.problem.hall_violator(&blue_rows, &red_cols)?.into());
+                                }
+                        }
+                }
+
+                // The supplied duals, or row and column minima, are the initial prices.
+                let u_matrix = generate_u_matrix(&c, u);
+                let v_matrix = generate_v_matrix(&subtract_matrix(&c, &u_matrix)?, v);
+                let mut auction = Auction {
+                        c: &c,
+                        u: u_matrix.column(0).iter().copied().collect(),
+                        v: v_matrix.row(0).iter().copied().collect(),
+                        col_of_row: vec![None; n],
+                        row_of_col: vec![None; n],
+                        assigned: 0,
+                        epsilon: N::zero(),
+                        spread: spread(&c)?,
+                };
+
+                let epsilon_min = epsilon_min(&c, auction.spread)?;
+                let factor = checked(N::from_usize(SCALING_FACTOR), "ϵ")?;
+                let mut epsilon = checked(auction.spread.checked_div(factor), "ϵ")?;
+                let mut bids = 0;
+                loop {
+                        if epsilon < epsilon_min {
+                                epsilon = epsilon_min;
+                        }
+                        auction.start_phase(epsilon)?;
+                        bids += self.run_phase(&mut auction)?;
+                        if epsilon <= epsilon_min || auction.assigned < n {
+                                break;
+                        }
+                        epsilon = checked(epsilon.checked_div(factor), "ϵ")?;
+                }
+
+                // Exact duals, keeping the assigned cells that stay tight.
+                let Auction { mut v, col_of_row, .. } = auction;
+                let mut u = Vec::with_capacity(n);
+                for i in 0..n {
+                        let slack = (0..n).filter_map(|j| c[(i, j)].map(|c_ij| checked(c_ij.checked_sub(v[j]), "c_ij - v_j")));
+                        u.push(min_cost(slack.collect::<Result<Vec<_>, _>>()?).unwrap_or_else(N::zero));
+                }
+                let mut row_of_col = vec![None; n];
+                for (i, j) in col_of_row.iter().enumerate().filter_map(|(i, j)| j.map(|j| (i, j))) {
+                        if let Some(c_ij) = c[(i, j)] {
+                                let reduced = checked(c_ij.checked_sub(u[i]).and_then(|x| x.checked_sub(v[j])), "reduced cost")?;
+                                if reduced.is_zero() {
+                                        row_of_col[j] = Some(i);
+                                }
+                        }
+                }
+                let matched_rows: Vec<usize> = row_of_col.iter().flatten().copied().collect();
+                let mut iterations = bids;
+                for root in (0..n).filter(|i| !matched_rows.contains(i)) {
+                        iterations += 1;
+                        if let Some((blue_rows, red_cols)) = augment(&c, &mut u, &mut v, &mut row_of_col, root)? {
+                                return Err(self.problem.hall_violator(&blue_rows, &red_cols)?.into());
+                        }
+                }
+
+                let c_ij = reduced_costs(&c, &u, &v)?;
+                let m = matching_of(&row_of_col);
+                self.problem.push_state(ProblemState {
+                        u,
+                        v,
+                        c: c_ij,
+                        g: None,
+                        m: Some(m),
+                        repaired_cells: None,
+                        bid: None,
+                        hungarian: None,
+                });
+                self.problem.finish(iterations)
+        }
+}
+
+impl<N: Cost> AuctionSolver<N> {
+        // Bids until every row is assigned, or until MAX_BIDS_PER_ROW * n bids, and
+        // returns the number of bids. In every sweep the rows (columns) that are
+        // free at its start bid once each.
+        fn run_phase(&mut self, auction: &mut Auction<N>) -> Result<usize, AssignmentError> {
+                let n = auction.c.nrows();
+                let mut free_rows: Vec<usize> = (0..n).collect();
+                let mut free_cols: Vec<usize> = (0..n).collect();
+                let mut forward = self.direction != AuctionDirection::Reverse;
+                let mut bids = 0;
+
+                while auction.assigned < n && bids < MAX_BIDS_PER_ROW * n {
+                        let assigned = auction.assigned;
+                        let bidders = if forward { take_free(&mut free_rows, &auction.col_of_row) } else { take_free(&mut free_cols, &auction.row_of_col) };
+                        for k in bidders {
+                                let bid = if forward { auction.forward_bid(k, &mut free_rows)? } else { auction.reverse_bid(k, &mut free_cols)? };
+                                bids += 1;
+                                if self.trace {
+                                        self.problem.push_state(ProblemState {
+                                                u: auction.u.clone(),
+                                                v: auction.v.clone(),
+                                                c: reduced_costs(auction.c, &auction.u, &auction.v)?,
+                                                g: None,
+                                                m: Some(matching_of(&auction.row_of_col)),
+                                                repaired_cells: None,
+                                                bid: Some(bid),
+                                                hungarian: None,
+                                        });
+                                }
+                        }
+                        if auction.assigned > assigned && self.direction == AuctionDirection::ForwardReverse {
+                                forward = !forward;
+                        }
+                }
+                Ok(bids)
+        }
+}
+
+// Prices, profits and the partial assignment of a running auction, on the
+// square minimisation problem `c`.
+struct Auction<'a, N: Cost> {
+        c: &'a DMatrix<Option<N>>,
+        u: Vec<N>,
+        v: Vec<N>,
+        col_of_row: Vec<Option<usize>>,
+        row_of_col: Vec<Option<usize>>,
+        assigned: usize,
+        epsilon: N,
+        // Margin of a bidder with a single allowed partner, which may bid any amount.
+        spread: N,
+}
+
+impl<N: Cost> Auction<'_, N> {
+        // Drops the assignment and sets u_i = min_j (c_i,j - v_j), which makes the
+        // duals feasible for any ϵ.
+        fn start_phase(&mut self, epsilon: N) -> Result<(), AssignmentError> {
+                let n = self.c.nrows();
+                self.epsilon = epsilon;
+                self.col_of_row = vec![None; n];
+                self.row_of_col = vec![None; n];
+                self.assigned = 0;
+                for i in 0..n {
+                        let slack = (0..n).filter_map(|j| self.c[(i, j)].map(|c_ij| checked(c_ij.checked_sub(self.v[j]), "c_ij - v_j")));
+                        if let Some(u_i) = min_cost(slack.collect::<Result<Vec<_>, _>>()?) {
+                                self.u[i] = u_i;
+                        }
+                }
+                Ok(())
+        }
+
+        // Row i takes its best column j: v_j = c_i,j - second best - ϵ and
+        // u_i = second best + ϵ. The previous owner of j becomes free.
+        fn forward_bid(&mut self, i: usize, free_rows: &mut Vec<usize>) -> Result<Bid<N>, AssignmentError> {
+                let n = self.c.nrows();
+                let values = (0..n).filter_map(|j| self.c[(i, j)].map(|c_ij| checked(c_ij.checked_sub(self.v[j]), "c_ij - v_j").map(|x| (x, j))));
+                let (best, j, increment) = self.best_two(values.collect::<Result<Vec<_>, _>>()?)?;
+
+                self.v[j] = checked(self.v[j].checked_sub(increment), "v_j - bid")?;
+                self.u[i] = checked(best.checked_add(increment), "u_i + bid")?;
+                match self.row_of_col[j] {
+                        Some(owner) => {
+                                self.col_of_row[owner] = None;
+                                free_rows.push(owner);
+                        }
+                        None => self.assigned += 1,
+                }
+                self.row_of_col[j] = Some(i);
+                self.col_of_row[i] = Some(j);
+                Ok(Bid {
+                        side: Side::Rows,
+                        bidder: i,
+                        target: j,
+                        increment,
+                        epsilon: self.epsilon,
+                })
+        }
+
+        // Column j takes its best row i in the same way, on u. The previous column
+        // of i becomes free.
+        fn reverse_bid(&mut self, j: usize, free_cols: &mut Vec<usize>) -> Result<Bid<N>, AssignmentError> {
+                let n = self.c.nrows();
+                let values = (0..n).filter_map(|i| self.c[(i, j)].map(|c_ij| checked(c_ij.checked_sub(self.u[i]), "c_ij - u_i").map(|x| (x, i))));
+                let (best, i, increment) = self.best_two(values.collect::<Result<Vec<_>, _>>()?)?;
+
+                self.u[i] = checked(self.u[i].checked_sub(increment), "u_i - bid")?;
+                self.v[j] = checked(best.checked_add(increment), "v_j + bid")?;
+                match self.col_of_row[i] {
+                        Some(col) => {
+                                self.row_of_col[col] = None;
+                                free_cols.push(col);
+                        }
+                        None => self.assigned += 1,
+                }
+                self.col_of_row[i] = Some(j);
+                self.row_of_col[j] = Some(i);
+                Ok(Bid {
+                        side: Side::Columns,
+                        bidder: j,
+                        target: i,
+                        increment,
+                        epsilon: self.epsilon,
+                })
+        }
+
+        // The smallest value, its index and the bid: the margin to the second
+        // smallest value plus ϵ.
+        fn best_two(&self, values: Vec<(N, usize)>) -> Result<(N, usize, N), AssignmentError> {
+                let mut best: Option<(N, usize)> = None;
+                let mut second: Option<N> = None;
+                for (x, k) in values {
+                        if best.is_none_or(|(b, _)| x < b) {
+                                second = best.map(|(b, _)| b);
+                                best = Some((x, k));
+                        } else if second.is_none_or(|s| x < s) {
+                                second = Some(x);
+                        }
+                }
+                // Every row and column has an allowed cell once an assignment exists.
+                let (best, k) = best.ok_or(AssignmentError::EmptyMatrix)?;
+                let margin = match second {
+                        Some(second) => checked(second.checked_sub(best), "bid margin")?,
+                        None => self.spread,
+                };
+                Ok((best, k, checked(margin.checked_add(self.epsilon), "bid")?))
+        }
+}
+
+// The rows (columns) to bid in the next sweep. Those that were freed are
+// collected in `freed`, which may also hold some that were assigned again by
+// a bid of the other side since.
+fn take_free(freed: &mut Vec<usize>, partner: &[Option<usize>]) -> Vec<usize> {
+        let mut free: Vec<usize> = freed.drain(..).filter(|&k| partner[k].is_none()).collect();
+        free.sort_unstable();
+        free.dedup();
+        free
+}
+
+// The last ϵ: 1 for exact costs. For floating point costs the spread times
+// `Cost::tolerance()`, doubled until it changes the largest |c_i,j|, so that
+// large costs are not left with bids that move no price.
+fn epsilon_min<N: Cost>(c: &DMatrix<Option<N>>, spread: N) -> Result<N, AssignmentError> {
+        if N::tolerance() <= N::zero() {
+                return checked(N::from_usize(1), "ϵ");
+        }
+        let largest = c.iter().flatten().fold(N::zero(), |largest, &x| {
+                let magnitude = if x < N::zero() { N::zero().checked_sub(x).unwrap_or(x) } else { x };
+                if magnitude > largest { magnitude } else { largest }
+        });
+        let mut epsilon = checked(spread.checked_mul(N::tolerance()), "ϵ")?;
+        if epsilon <= N::zero() {
+                epsilon = N::tolerance();
+        }
+        while largest.checked_add(epsilon) == Some(largest) {
+                epsilon = checked(epsilon.checked_add(epsilon), "ϵ")?;
+        }
+        Ok(epsilon)
+}
+
+// Largest minus smallest allowed cost.
+fn spread<N: Cost>(c: &DMatrix<Option<N>>) -> Result<N, AssignmentError> {
+        let mut allowed = c.iter().flatten().copied();
+        let first = allowed.next().unwrap_or_else(N::zero);
+        let (lo, hi) = allowed.fold((first, first), |(lo, hi), x| (if x < lo { x } else { lo }, if x > hi { x } else { hi }));
+        checked(hi.checked_sub(lo), "cost spread")
+}
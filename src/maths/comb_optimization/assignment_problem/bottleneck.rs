@@ -0,0 +1,150 @@
+use crate::comb_optimization::assignment_problem::assignment_problem::{generate_threshold_graph, HallViolator, Objective, Side};
+use crate::comb_optimization::assignment_problem::error::AssignmentError;
+use crate::comb_optimization::cost::Cost;
+use crate::comb_optimization::hungarian::hungarian_maximum_matching;
+use crate::graph_theory::bipartite::BipartiteGraph;
+use crate::graph_theory::hopcroft_karp::hopcroft_karp;
+use nalgebra::DMatrix;
+use petgraph::graph::NodeIndex;
+use rocket::serde::Serialize;
+use std::collections::HashSet;
+
+/// One threshold tried by [`solve_bottleneck`]: the size of a maximum matching
+/// on the cells of `c` that are at most (at least, when maximising) `threshold`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BottleneckStep<N: Cost> {
+        pub threshold: N,
+        pub matching_size: usize,
+        pub perfect: bool,
+}
+
+/// Assignment whose worst entry, `bottleneck`, is as good as possible.
+#[derive(Debug, Clone, Serialize)]
+pub struct BottleneckSolution<N: Cost> {
+        pub bottleneck: N,
+        /// Assigned (row, column) pairs, ordered by row.
+        pub assignment: Vec<(usize, usize)>,
+        pub unmatched_rows: Vec<usize>,
+        pub unmatched_cols: Vec<usize>,
+        /// The thresholds in the order they were tried.
+        pub thresholds: Vec<BottleneckStep<N>>,
+}
+
+/// Solves the bottleneck assignment problem: minimise the largest assigned
+/// entry of `c` (`Objective::Minimize`), or maximise the smallest one
+/// (`Objective::Maximize`).
+///
+/// The distinct allowed entries of `c` are the candidate thresholds. For a
+/// threshold t the threshold graph has an edge (i, j) for every allowed cell
+/// with c_i,j <= t (>= t when maximising), built like the equality graph of
+/// the Hungarian method. The smallest t whose graph has a matching covering the
+/// smaller side of `c` is found by binary search, with a maximum matching per
+/// threshold. If even the graph of all allowed cells has no such matching, the
+/// Hungarian tree on it gives a [`HallViolator`].
+pub fn solve_bottleneck<N: Cost>(c: &DMatrix<Option<N>>, objective: Objective) -> Result<BottleneckSolution<N>, AssignmentError> {
+        let (nrows, ncols) = c.shape();
+        if nrows == 0 || ncols == 0 {
+                return Err(AssignmentError::EmptyMatrix);
+        }
+
+        // The rows are the smaller side from here on.
+        if nrows > ncols {
+                return match solve_bottleneck(&c.transpose(), objective) {
+                        Ok(solution) => {
+                                let mut assignment: Vec<(usize, usize)> = solution.assignment.iter().map(|&(j, i)| (i, j)).collect();
+                                assignment.sort_unstable();
+                                Ok(BottleneckSolution {
+                                        bottleneck: solution.bottleneck,
+                                        assignment,
+                                        unmatched_rows: solution.unmatched_cols,
+                                        unmatched_cols: solution.unmatched_rows,
+                                        thresholds: solution.thresholds,
+                                })
+                        }
+                        Err(AssignmentError::NoPerfectMatching { hall_violator }) => Err(HallViolator { side: Side::Columns, ..hall_violator }.into()),
+                        Err(err) => Err(err),
+                };
+        }
+
+        let is_better = |a: N, b: N| match objective {
+                Objective::Minimize => a < b,
+                Objective::Maximize => a > b,
+        };
+        // The distinct entries, best first.
+        let mut thresholds: Vec<N> = c.iter().flatten().copied().collect();
+        thresholds.sort_by(|&a, &b| if is_better(a, b) { std::cmp::Ordering::Less } else if is_better(b, a) { std::cmp::Ordering::Greater } else { std::cmp::Ordering::Equal });
+        thresholds.dedup();
+
+        let sides = BipartiteGraph {
+                nodes_u: (0..nrows).map(NodeIndex::new).collect(),
+                nodes_v: (nrows..nrows + ncols).map(NodeIndex::new).collect(),
+        };
+        let mut steps = Vec::new();
+        let mut try_threshold = |t: N| {
+                let g = generate_threshold_graph(c, &[], &[], |x| !is_better(t, x));
+                let matching: Vec<(NodeIndex, NodeIndex)> = hopcroft_karp(&g, &sides).matching;
+                steps.push(BottleneckStep {
+                        threshold: t,
+                        matching_size: matching.len(),
+                        perfect: matching.len() == nrows,
+                });
+                (g, matching)
+        };
+
+        // The graph of the largest threshold, i.e. of all allowed cells, decides
+        // whether there is an assignment at all.
+        let (mut lo, mut hi) = (0, thresholds.len().saturating_sub(1));
+        let (g, mut best_matching) = match thresholds.last() {
+                Some(&t) => try_threshold(t),
+                None => (generate_threshold_graph(c, &[], &[], |_| false), Vec::new()),
+        };
+        if best_matching.len() < nrows {
+                return Err(hall_violator(&g, &best_matching, nrows, ncols).into());
+        }
+        while lo < hi {
+                let mid = (lo + hi) / 2;
+                let (_, matching) = try_threshold(thresholds[mid]);
+                if matching.len() == nrows {
+                        hi = mid;
+                        best_matching = matching;
+                } else {
+                        lo = mid + 1;
+                }
+        }
+
+        let mut assignment: Vec<(usize, usize)> = best_matching
+                .iter()
+                .map(|&(a, b)| if a.index() < nrows { (a.index(), b.index() - nrows) } else { (b.index(), a.index() - nrows) })
+                .collect();
+        assignment.sort_unstable();
+        let unmatched_cols = (0..ncols).filter(|j| !assignment.iter().any(|p| p.1 == *j)).collect();
+        Ok(BottleneckSolution {
+                bottleneck: thresholds[hi],
+                assignment,
+                unmatched_rows: Vec::new(),
+                unmatched_cols,
+                thresholds: steps,
+        })
+}
+
+// The rows reached by the Hungarian tree from the exposed rows have only the
+// columns of the tree, which are all matched, as partners.
+fn hall_violator(g: &petgraph::Graph<String, String, petgraph::Undirected>, matching: &[(NodeIndex, NodeIndex)], nrows: usize, ncols: usize) -> HallViolator {
+        let g_vertex_u_v_sets = BipartiteGraph {
+                nodes_u: (0..nrows).map(NodeIndex::new).collect(),
+                nodes_v: (nrows..nrows + ncols).map(NodeIndex::new).collect(),
+        };
+        let matched_edges: HashSet<(NodeIndex, NodeIndex)> = matching.iter().copied().collect();
+
+        let mut members = Vec::new();
+        if let Some((min_cover, _, _)) = hungarian_maximum_matching(g, |node| g[node].clone(), matched_edges, &g_vertex_u_v_sets) {
+                // The cover holds the rows outside the tree and the columns in it.
+                members = (0..nrows).filter(|i| !min_cover.contains(&NodeIndex::new(*i))).collect();
+        }
+        let neighbours = (0..ncols).filter(|&j| members.iter().any(|&i| g.contains_edge(NodeIndex::new(i), NodeIndex::new(nrows + j)))).collect();
+        HallViolator {
+                side: Side::Rows,
+                members,
+                neighbours,
+        }
+}
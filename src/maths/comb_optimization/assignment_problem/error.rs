@@ -0,0 +1,92 @@
+use crate::comb_optimization::assignment_problem::assignment_problem::{HallViolator, Side};
+use rocket::serde::Serialize;
+use std::error::Error;
+use std::fmt;
+
+/// Everything that can go wrong between reading an assignment problem and
+/// returning its optimal solution.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "error")]
+pub enum AssignmentError {
+        /// The input is not valid JSON or an entry is not a number of the cost type.
+        InvalidInput { details: String },
+
+        /// `c` has no rows or no columns.
+        EmptyMatrix,
+
+        /// Row `row` of `c` has `found` entries instead of the `expected` entries of row 0.
+        RaggedRows { row: usize, expected: usize, found: usize },
+
+        /// An edge of a sparse cost matrix lies outside of its `nrows` x `ncols` shape.
+        EdgeOutOfRange { row: usize, col: usize, nrows: usize, ncols: usize },
+
+        /// A sparse cost matrix lists the cell (`row`, `col`) more than once.
+        DuplicateEdge { row: usize, col: usize },
+
+        /// `u` (side `Rows`) or `v` (side `Columns`) was given, but its length is not
+        /// the number of rows or columns of `c`.
+        DualLengthMismatch { side: Side, expected: usize, found: usize },
+
+        /// Row (side `Rows`) or column (side `Columns`) names were given, but not
+        /// one for every row or column of `c`.
+        NameLengthMismatch { side: Side, expected: usize, found: usize },
+
+        /// The supplied duals violate u_i + v_j <= c_i,j (>= for a maximisation
+        /// problem) at every (row, column) cell in `cells`.
+        InfeasibleDuals { cells: Vec<(usize, usize)> },
+
+        /// A computation left the range of the cost type, e.g. a dual of an
+        /// unsigned type that would have to become negative.
+        Overflow { operation: &'static str },
+
+        /// The forbidden cells leave no assignment of the smaller side.
+        NoPerfectMatching { hall_violator: HallViolator },
+}
+
+impl fmt::Display for AssignmentError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                        AssignmentError::InvalidInput { details } => write!(f, "invalid input: {}", details),
+                        AssignmentError::EmptyMatrix => write!(f, "the cost matrix is empty"),
+                        AssignmentError::RaggedRows { row, expected, found } => write!(f, "row {} of the cost matrix has {} entries, expected {}", row, found, expected),
+                        AssignmentError::EdgeOutOfRange { row, col, nrows, ncols } => write!(f, "the edge ({}, {}) lies outside of the {} x {} cost matrix", row, col, nrows, ncols),
+                        AssignmentError::DuplicateEdge { row, col } => write!(f, "the cell ({}, {}) is given more than once", row, col),
+                        AssignmentError::DualLengthMismatch { side, expected, found } => {
+                                let name = match side {
+                                        Side::Rows => "u",
+                                        Side::Columns => "v",
+                                };
+                                write!(f, "{} has {} entries, expected {}", name, found, expected)
+                        }
+                        AssignmentError::NameLengthMismatch { side, expected, found } => {
+                                let name = match side {
+                                        Side::Rows => "row",
+                                        Side::Columns => "column",
+                                };
+                                write!(f, "{} {} names were given, expected {}", found, name, expected)
+                        }
+                        AssignmentError::InfeasibleDuals { cells } => write!(f, "the duals u and v are infeasible at the cells {:?}", cells),
+                        AssignmentError::Overflow { operation } => write!(f, "{} overflowed", operation),
+                        AssignmentError::NoPerfectMatching { hall_violator } => write!(f, "no assignment exists, {:?} {:?} have only the partners {:?}", hall_violator.side, hall_violator.members, hall_violator.neighbours),
+                }
+        }
+}
+
+impl Error for AssignmentError {}
+
+impl From<serde_json::Error> for AssignmentError {
+        fn from(err: serde_json::Error) -> Self {
+                AssignmentError::InvalidInput { details: err.to_string() }
+        }
+}
+
+impl From<HallViolator> for AssignmentError {
+        fn from(hall_violator: HallViolator) -> Self {
+                AssignmentError::NoPerfectMatching { hall_violator }
+        }
+}
+
+/// `Some` result of a checked operation, or an [`AssignmentError::Overflow`] naming it.
+pub(crate) fn checked<N>(result: Option<N>, operation: &'static str) -> Result<N, AssignmentError> {
+        result.ok_or(AssignmentError::Overflow { operation })
+}
@@ -1,32 +1,37 @@
+use crate::comb_optimization::assignment_problem::error::AssignmentError;
+use crate::comb_optimization::cost::Cost;
+use nalgebra::DMatrix;
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
-use std::str::FromStr;
-use std::error::Error;
-use nalgebra::{DMatrix, Scalar};
 use std::io::{self, BufRead};
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct UInVec(Option<Vec<i32>>);
+struct UInVec<N>(Option<Vec<N>>);
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct VInVec(Option<Vec<i32>>);
+struct VInVec<N>(Option<Vec<N>>);
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct CInMatrix(Vec<Vec<i32>>);
+struct CInMatrix<N>(Vec<Vec<Option<N>>>);
+
+/// `u`, `v` and the cost matrix `c` read from the input. A `null` entry of `c`
+/// is a forbidden cell and is read as `None`.
+pub type ParsedInput<N> = (Vec<N>, Vec<N>, DMatrix<Option<N>>);
 
 // echo -e "{\"u\":[2,4,3,2],\"v\":[0,0,0,1],\"c\":[[5,2,3,4],[7,8,4,5],[6,3,5,6],[2,2,3,5]]}" | cargo run --
 /// Consumes a `BufRead` of line and json string of u, v and cost matrix, and
-/// produces either a `DMatrix` of the three or an error.
-pub fn parse<N>() -> Result<(Vec<i32>, Vec<i32>, DMatrix<i32>), Box<dyn Error>>
+/// produces either a `DMatrix` of the three or an error. The entries are read
+/// as the cost type `N`, e.g. `parse::<f64>()` for distances.
+pub fn parse<N>() -> Result<ParsedInput<N>, AssignmentError>
 where
-    N: FromStr + Scalar,
-    N::Err: Error,
+    N: Cost + DeserializeOwned,
 {
-    let mut u: Vec<i32> = Vec::new();
-    let mut v: Vec<i32> = Vec::new();
-    let mut c: DMatrix<i32> = DMatrix::zeros(0, 0);
+    let mut u: Vec<N> = Vec::new();
+    let mut v: Vec<N> = Vec::new();
+    let mut c: DMatrix<Option<N>> = DMatrix::from_row_slice(0, 0, &[]);
     let stdin = io::stdin();
     let buffered_stdin = stdin.lock();
 
@@ -34,65 +39,80 @@ where
     for line in buffered_stdin.lines() {
         match line {
             Ok(buffered_stdin) => {
-                let input_json: serde_json::Value =
-                    serde_json::from_str(&buffered_stdin).expect("JSON was not well-formatted");
+                let input_json: serde_json::Value = serde_json::from_str(&buffered_stdin)?;
 
-                u = get_u_vector(&input_json);
-                v = get_v_vector(&input_json);
-                c = get_cost_matrix(&input_json);
+                u = get_u_vector(&input_json)?;
+                v = get_v_vector(&input_json)?;
+                c = get_cost_matrix(&input_json)?;
+            }
+            Err(err) => {
+                return Err(AssignmentError::InvalidInput {
+                    details: err.to_string(),
+                })
             }
-            Err(err) => println!("Input failed!!! {}", err),
         }
     }
 
     Ok((u, v, c))
 }
 
-fn get_u_vector(input_json: &serde_json::Value) -> Vec<i32> {
+fn get_u_vector<N: Cost + DeserializeOwned>(
+    input_json: &serde_json::Value,
+) -> Result<Vec<N>, AssignmentError> {
     let mut u = Vec::new();
 
     if let Some(u_json_arr) = input_json.get("u") {
-        let u_invec: UInVec = serde_json::from_value(u_json_arr.clone()).unwrap();
+        let u_invec: UInVec<N> = serde_json::from_value(u_json_arr.clone())?;
         if let Ok(item) = u_invec.0.ok_or("None") {
             u = item.to_vec();
         }
     }
-    u
+    Ok(u)
     //DMatrix::from_row_slice(u.len(), 1, &u[..])
 }
 
-fn get_v_vector(input_json: &serde_json::Value) -> Vec<i32> {
+fn get_v_vector<N: Cost + DeserializeOwned>(
+    input_json: &serde_json::Value,
+) -> Result<Vec<N>, AssignmentError> {
     let mut v = Vec::new();
 
     if let Some(v_json_arr) = input_json.get("v") {
-        let v_invec: VInVec = serde_json::from_value(v_json_arr.clone()).unwrap();
+        let v_invec: VInVec<N> = serde_json::from_value(v_json_arr.clone())?;
         if let Ok(item) = v_invec.0.ok_or("None") {
             v = item.to_vec();
         }
     }
-    v
+    Ok(v)
     //DMatrix::from_row_slice(v.len(), 1, &v[..])
 }
 
-fn get_cost_matrix(input_json: &serde_json::Value) -> DMatrix<i32> {
-    let mut c = Vec::new();
-    let mut rows = 0;
-    let mut cols = 0;
+fn get_cost_matrix<N: Cost + DeserializeOwned>(
+    input_json: &serde_json::Value,
+) -> Result<DMatrix<Option<N>>, AssignmentError> {
+    let c_json_arr = input_json.get("c").ok_or(AssignmentError::EmptyMatrix)?;
+    let c_inmatrix: CInMatrix<N> = serde_json::from_value(c_json_arr.clone())?;
+    let rows = c_inmatrix.0.len();
+    let cols = check_cost_rows(&c_inmatrix.0)?;
 
-    if let Some(c_json_arr) = input_json.get("c") {
-        let c_inmatrix: CInMatrix = serde_json::from_value(c_json_arr.clone()).unwrap();
-        
-        rows = c_inmatrix.0.len();
+    let c: Vec<Option<N>> = c_inmatrix.0.into_iter().flatten().collect();
+    Ok(DMatrix::from_row_slice(rows, cols, &c[..]))
+}
 
-        for mut row in c_inmatrix.0 {
-            c.append(&mut row);
+/// Number of columns of a cost matrix given as a list of rows. Fails if there
+/// are no rows or no columns, or if a row is shorter or longer than row 0.
+pub fn check_cost_rows<T>(rows: &[Vec<T>]) -> Result<usize, AssignmentError> {
+    let cols = rows.first().map_or(0, |row| row.len());
+    if cols == 0 {
+        return Err(AssignmentError::EmptyMatrix);
+    }
+    for (row, entries) in rows.iter().enumerate() {
+        if entries.len() != cols {
+            return Err(AssignmentError::RaggedRows {
+                row,
+                expected: cols,
+                found: entries.len(),
+            });
         }
-
-        // The number of items divided by the number of rows equals the
-        // number of columns.
-        cols = c.len() / rows;
     }
-
-    DMatrix::from_row_slice(rows, cols, &c[..])
+    Ok(cols)
 }
-
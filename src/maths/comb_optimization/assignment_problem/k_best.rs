@@ -0,0 +1,106 @@
+use crate::comb_optimization::assignment_problem::assignment_problem::Objective;
+use crate::comb_optimization::assignment_problem::error::AssignmentError;
+use crate::comb_optimization::assignment_problem::solution::AssignmentSolution;
+use crate::comb_optimization::assignment_problem::solver::{new_solver, SolverKind};
+use crate::comb_optimization::cost::Cost;
+use nalgebra::DMatrix;
+
+// A solved subproblem that has not been returned yet: the solution, the
+// subproblem's cost matrix and the pairs forced in it.
+type Part<N> = (AssignmentSolution<N>, DMatrix<Option<N>>, Vec<(usize, usize)>);
+
+/// Enumerates the assignments of `c` from the best one on, with Murty's
+/// algorithm: the cost never decreases from one item to the next (the profit
+/// never increases for a maximisation problem).
+///
+/// Every solution found so far stands for a subproblem, a copy of `c` with
+/// some cells forced and some forbidden. When the best pending solution is
+/// returned, its subproblem is split along its assigned pairs (i_1, j_1), ...,
+/// (i_k, j_k): the p-th part forces the pairs before p and forbids (i_p, j_p),
+/// so the parts cover every other assignment of the subproblem exactly once.
+/// Forcing a pair forbids the rest of its row and column. Parts without any
+/// assignment are dropped.
+///
+/// The iterator ends when every assignment has been returned. An error other
+/// than an infeasible part, e.g. an overflow, is returned as an item.
+pub struct KBestAssignments<N: Cost> {
+        objective: Objective,
+        solver: SolverKind,
+        pending: Vec<Part<N>>,
+        errors: Vec<AssignmentError>,
+}
+
+impl<N: Cost + 'static> KBestAssignments<N> {
+        pub fn new(c: DMatrix<Option<N>>, objective: Objective, solver: SolverKind) -> Self {
+                let mut k_best = Self {
+                        objective,
+                        solver,
+                        pending: Vec::new(),
+                        errors: Vec::new(),
+                };
+                k_best.solve_part(c, Vec::new(), true);
+                k_best
+        }
+
+        // Solves a subproblem and keeps its solution. An infeasible part is dropped,
+        // only the whole problem being infeasible is reported.
+        fn solve_part(&mut self, c: DMatrix<Option<N>>, forced: Vec<(usize, usize)>, report_infeasible: bool) {
+                let mut ass_pblm_soln = new_solver::<N>(self.solver);
+                ass_pblm_soln.set_objective(self.objective);
+                ass_pblm_soln.init_with_forbidden(vec![], vec![], c.clone());
+                match ass_pblm_soln.find_optimum_cost() {
+                        Ok(solution) => self.pending.push((solution, c, forced)),
+                        Err(AssignmentError::NoPerfectMatching { .. }) if !report_infeasible => {}
+                        Err(err) => self.errors.push(err),
+                }
+        }
+
+        fn is_better(&self, a: N, b: N) -> bool {
+                match self.objective {
+                        Objective::Minimize => a < b,
+                        Objective::Maximize => a > b,
+                }
+        }
+}
+
+impl<N: Cost + 'static> Iterator for KBestAssignments<N> {
+        type Item = Result<AssignmentSolution<N>, AssignmentError>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+                if let Some(err) = self.errors.pop() {
+                        return Some(Err(err));
+                }
+
+                if self.pending.is_empty() {
+                        return None;
+                }
+                let mut best = 0;
+                for (index, (solution, _, _)) in self.pending.iter().enumerate() {
+                        if self.is_better(solution.cost, self.pending[best].0.cost) {
+                                best = index;
+                        }
+                }
+                let (solution, c, forced) = self.pending.swap_remove(best);
+
+                let mut part = c;
+                let mut part_forced = forced.clone();
+                for &(i, j) in solution.assignment.iter().filter(|pair| !forced.contains(pair)) {
+                        let mut excluded = part.clone();
+                        excluded[(i, j)] = None;
+                        self.solve_part(excluded, part_forced.clone(), false);
+
+                        force(&mut part, i, j);
+                        part_forced.push((i, j));
+                }
+
+                Some(Ok(solution))
+        }
+}
+
+// Forbids every cell of row i and column j but (i, j).
+fn force<N: Cost>(c: &mut DMatrix<Option<N>>, i: usize, j: usize) {
+        let keep = c[(i, j)];
+        c.row_mut(i).fill(None);
+        c.column_mut(j).fill(None);
+        c[(i, j)] = keep;
+}
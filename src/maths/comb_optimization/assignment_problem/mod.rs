@@ -1,5 +1,27 @@
-pub use assignment_problem::OptimalSolution;
+pub use all_optimal::{count_optimal_assignments, optimal_assignments};
+pub use auction::{AuctionDirection, AuctionSolver, Bid};
+pub use assignment_problem::{dual_violations, HallViolator, Objective, OptimalSolution, ProblemState, Side};
+pub use bottleneck::{solve_bottleneck, BottleneckSolution, BottleneckStep};
+pub use error::AssignmentError;
 pub use inputs::parse;
+pub use k_best::KBestAssignments;
+pub use shortest_path::{Change, ShortestPathSolver};
+pub use solution::AssignmentSolution;
+pub use sparse::{solve_sparse, SparseAssignmentSolution, SparseCostMatrix};
+pub use solver::{new_solver, AssignmentSolver, SolverKind};
+pub use verify::{verify_assignment, CertificateViolation, VerificationReport};
 
+pub mod all_optimal;
+#[allow(clippy::module_inception)]
 pub mod assignment_problem;
+pub mod auction;
+pub mod bottleneck;
+pub mod error;
 pub mod inputs;
+pub mod k_best;
+pub mod sensitivity;
+pub mod shortest_path;
+pub mod solution;
+pub mod solver;
+pub mod sparse;
+pub mod verify;
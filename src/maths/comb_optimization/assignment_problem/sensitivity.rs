@@ -0,0 +1,96 @@
+use crate::comb_optimization::assignment_problem::error::{checked, AssignmentError};
+use crate::comb_optimization::cost::Cost;
+use nalgebra::DMatrix;
+use petgraph::graph::NodeIndex;
+
+/// Cost ranging of an optimal assignment, from the reduced costs
+/// c_i,j - u_i - v_j of a square minimisation problem (`reduced`, zero on the
+/// assigned cells) and its perfect matching `m` (rows 0..n, columns n..2n).
+///
+/// Changing the cost of one cell changes the optimal assignment only once an
+/// alternating cycle through that cell stops costing more than the matching
+/// it replaces. With non-negative reduced costs the cheapest such cycles are
+/// shortest paths:
+///
+/// - an assigned cell (i, σ(i)) can rise by the length of the shortest path
+///   from row i to column σ(i) that avoids the cell itself;
+/// - an unassigned cell (i, j) has to drop by its reduced cost plus the length
+///   of the shortest path from the row σ⁻¹(j) to the column σ(i).
+///
+/// A path steps from a row to a column along an allowed cell, paying its
+/// reduced cost, and from a column back to the row assigned to it for free.
+/// One Dijkstra search per row gives every entry, O(n^3) in all.
+///
+/// The entry of a forbidden cell is `None`, as is the entry of a cell for
+/// which no other assignment exists, whatever its cost.
+pub(super) fn cost_ranging<N: Cost>(reduced: &DMatrix<Option<N>>, m: &[(NodeIndex, NodeIndex)]) -> Result<DMatrix<Option<N>>, AssignmentError> {
+        let n = reduced.nrows();
+        let mut col_of_row = vec![0; n];
+        let mut row_of_col = vec![0; n];
+        for &(a, b) in m {
+                let (row_node, col_node) = if a.index() < n { (a, b) } else { (b, a) };
+                col_of_row[row_node.index()] = col_node.index() - n;
+                row_of_col[col_node.index() - n] = row_node.index();
+        }
+
+        let dist: Vec<Vec<Option<N>>> = (0..n).map(|r| alternating_distances(reduced, &col_of_row, &row_of_col, r)).collect::<Result<_, _>>()?;
+
+        let mut ranging = DMatrix::from_element(n, n, None);
+        for i in 0..n {
+                for j in 0..n {
+                        let c_ij = match reduced[(i, j)] {
+                                Some(c_ij) => c_ij,
+                                None => continue,
+                        };
+                        let amount = if col_of_row[i] == j {
+                                dist[i][j]
+                        } else {
+                                match dist[row_of_col[j]][col_of_row[i]] {
+                                        Some(d) => Some(checked(c_ij.checked_add(d), "cost ranging")?),
+                                        None => None,
+                                }
+                        };
+                        // Floating point reduced costs may be a little below zero.
+                        ranging[(i, j)] = amount.map(|x| if x < N::zero() { N::zero() } else { x });
+                }
+        }
+        Ok(ranging)
+}
+
+// Lengths of the shortest alternating paths from row `root` to every column,
+// leaving out the cell of `root` and its assigned column.
+fn alternating_distances<N: Cost>(reduced: &DMatrix<Option<N>>, col_of_row: &[usize], row_of_col: &[usize], root: usize) -> Result<Vec<Option<N>>, AssignmentError> {
+        let n = reduced.nrows();
+        let mut dist: Vec<Option<N>> = (0..n).map(|j| if j == col_of_row[root] { None } else { reduced[(root, j)] }).collect();
+        let mut scanned = vec![false; n];
+
+        loop {
+                let mut next: Option<(usize, N)> = None;
+                for j in (0..n).filter(|&j| !scanned[j]) {
+                        if let Some(d) = dist[j] {
+                                if next.is_none_or(|(_, best)| d < best) {
+                                        next = Some((j, d));
+                                }
+                        }
+                }
+                let (k, d_k) = match next {
+                        Some(next) => next,
+                        None => return Ok(dist),
+                };
+                scanned[k] = true;
+
+                // Reaching the column of `root` closes a cycle, there is no row to go on from.
+                let row = row_of_col[k];
+                if row == root {
+                        continue;
+                }
+                for j in (0..n).filter(|&j| !scanned[j]) {
+                        if let Some(c_rj) = reduced[(row, j)] {
+                                let d = checked(d_k.checked_add(c_rj), "cost ranging")?;
+                                if dist[j].is_none_or(|d_j| d < d_j) {
+                                        dist[j] = Some(d);
+                                }
+                        }
+                }
+        }
+}
@@ -0,0 +1,295 @@
+use crate::comb_optimization::assignment_problem::assignment_problem::{generate_u_matrix, generate_v_matrix, subtract_matrix, Objective, OptimalSolution, ProblemState};
+use crate::comb_optimization::assignment_problem::error::{checked, AssignmentError};
+use crate::comb_optimization::assignment_problem::solution::AssignmentSolution;
+use crate::comb_optimization::assignment_problem::solver::AssignmentSolver;
+use crate::comb_optimization::cost::{min_cost, Cost};
+use nalgebra::DMatrix;
+use petgraph::graph::NodeIndex;
+use rocket::serde::{Deserialize, Serialize};
+
+type WarmStart = (Vec<(usize, usize)>, Vec<Change>);
+
+/// Part of the cost matrix that changed since a previous solution, see
+/// [`AssignmentSolver::warm_start`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Change {
+        Cell(usize, usize),
+        Row(usize),
+        Column(usize),
+}
+
+/// Solves the assignment problem with shortest augmenting paths in the style
+/// of Jonker and Volgenant, in O(n^3) time.
+///
+/// The rows are assigned one after the other. For a free row a Dijkstra search
+/// on the reduced costs c_i,j - u_i - v_j finds the cheapest alternating path
+/// to a free column, the duals of the scanned rows and columns are moved by
+/// the length of that path so that it becomes tight, and the matching is
+/// augmented along it. The duals stay feasible throughout, so at the end
+/// ∑u + ∑v is the cost of the assignment.
+///
+/// Objective, forbidden cells, rectangular matrices and supplied duals are
+/// handled as by [`OptimalSolution`], which also keeps the trace. Only the
+/// prepared input and the final state are recorded; `iterations` counts the
+/// augmenting paths.
+pub struct ShortestPathSolver<N: Cost> {
+        problem: OptimalSolution<N>,
+
+        // Assignment of a previous solution and the changes since, see `warm_start`.
+        warm_start: Option<WarmStart>,
+}
+
+impl<N: Cost> Default for ShortestPathSolver<N> {
+        fn default() -> Self {
+                Self::new()
+        }
+}
+
+impl<N: Cost> ShortestPathSolver<N> {
+        pub fn new() -> Self {
+                Self {
+                        problem: OptimalSolution::new(),
+                        warm_start: None,
+                }
+        }
+}
+
+impl<N: Cost> AssignmentSolver<N> for ShortestPathSolver<N> {
+        fn set_objective(&mut self, objective: Objective) {
+                self.problem.set_objective(objective);
+        }
+
+        fn set_repair_duals(&mut self, repair_duals: bool) {
+                self.problem.set_repair_duals(repair_duals);
+        }
+
+        fn set_names(&mut self, row_names: Vec<String>, col_names: Vec<String>) {
+                self.problem.set_names(row_names, col_names);
+        }
+
+        fn init_with_forbidden(&mut self, u_invec: Vec<N>, v_invec: Vec<N>, c: DMatrix<Option<N>>) {
+                self.problem.init_with_forbidden(u_invec, v_invec, c);
+        }
+
+        /// Re-solves from a `previous` solution after the cost matrix passed to
+        /// `init` was edited in `changes`, instead of starting from scratch. Call
+        /// it after `init`; it replaces the duals given there.
+        ///
+        /// The duals of the changed rows (and of the rows of changed cells) and
+        /// columns are recomputed from the new costs, the other duals are kept.
+        /// Assigned pairs that are not touched by a change and still tight stay
+        /// assigned, so only the rows that lost their partner are augmented again
+        /// and `iterations` counts just those. Edits missing from `changes` are
+        /// still found: a row whose old duals are infeasible somewhere is treated
+        /// as changed, and a pair that is no longer tight is dropped.
+        ///
+        /// `previous` has to solve a problem of the same shape and objective.
+        fn warm_start(&mut self, previous: &AssignmentSolution<N>, changes: &[Change]) {
+                self.problem.set_initial_duals(previous.u.clone(), previous.v.clone());
+                self.warm_start = Some((previous.assignment.clone(), changes.to_vec()));
+        }
+
+        fn find_optimum_cost(&mut self) -> Result<AssignmentSolution<N>, AssignmentError> {
+                let violations = self.problem.prepare_keeping_violations(self.warm_start.is_some())?;
+                let (c, u, v) = self.problem.prepared()?;
+                let c = c.clone();
+                let n = c.nrows();
+
+                // Start from the supplied duals, or from row and column minima.
+                let u_matrix = generate_u_matrix(&c, u);
+                let c_ij_intermediary = subtract_matrix(&c, &u_matrix)?;
+                let v_matrix = generate_v_matrix(&c_ij_intermediary, v);
+                let mut u: Vec<N> = u_matrix.column(0).iter().copied().collect();
+                let mut v: Vec<N> = v_matrix.row(0).iter().copied().collect();
+
+                let mut row_of_col: Vec<Option<usize>> = vec![None; n];
+                if let Some((assignment, changes)) = self.warm_start.take() {
+                        let mut stale_rows = vec![false; n];
+                        let mut stale_cols = vec![false; n];
+                        for change in &changes {
+                                match *change {
+                                        Change::Cell(i, _) | Change::Row(i) if i < n => stale_rows[i] = true,
+                                        Change::Column(j) if j < n => stale_cols[j] = true,
+                                        _ => {}
+                                }
+                        }
+                        // Violations in a stale column are repaired with that column.
+                        for &(i, j) in &violations {
+                                if !stale_cols[j] {
+                                        stale_rows[i] = true;
+                                }
+                        }
+                        restore_duals(&c, &mut u, &mut v, &stale_rows, &stale_cols)?;
+                        restore_matching(&c, &u, &v, &mut row_of_col, &assignment, &stale_rows, &stale_cols)?;
+
+                        let c_ij = reduced_costs(&c, &u, &v)?;
+                        let m = matching_of(&row_of_col);
+                        self.problem.push_state(ProblemState {
+                                u: u.clone(),
+                                v: v.clone(),
+                                c: c_ij,
+                                g: None,
+                                m: Some(m),
+                                repaired_cells: Some(violations).filter(|cells| !cells.is_empty()),
+                                bid: None,
+                                hungarian: None,
+                        });
+                }
+
+                let matched_rows: Vec<usize> = row_of_col.iter().flatten().copied().collect();
+                let mut iterations = 0;
+                for root in (0..n).filter(|i| !matched_rows.contains(i)) {
+                        iterations += 1;
+                        if let Some((blue_rows, red_cols)) = augment(&c, &mut u, &mut v, &mut row_of_col, root)? {
+                                return Err(self.problem.hall_violator(&blue_rows, &red_cols)?.into());
+                        }
+                }
+
+                let c_ij = reduced_costs(&c, &u, &v)?;
+                let m = matching_of(&row_of_col);
+                self.problem.push_state(ProblemState {
+                        u,
+                        v,
+                        c: c_ij,
+                        g: None,
+                        m: Some(m),
+                        repaired_cells: None,
+                        bid: None,
+                        hungarian: None,
+                });
+                self.problem.finish(iterations)
+        }
+}
+
+// c_i,j - u_i - v_j for every allowed cell.
+pub(super) fn reduced_costs<N: Cost>(c: &DMatrix<Option<N>>, u: &[N], v: &[N]) -> Result<DMatrix<Option<N>>, AssignmentError> {
+        subtract_matrix(&subtract_matrix(c, &generate_u_matrix(c, u))?, &generate_v_matrix(c, v))
+}
+
+// The matching as pairs of equality graph nodes, rows 0..n and columns n..2n.
+pub(super) fn matching_of(row_of_col: &[Option<usize>]) -> Vec<(NodeIndex, NodeIndex)> {
+        let n = row_of_col.len();
+        row_of_col.iter().enumerate().filter_map(|(j, i)| i.map(|i| (NodeIndex::new(i), NodeIndex::new(n + j)))).collect()
+}
+
+// Makes the duals feasible again after an edit: a stale column gets
+// v_j = min (c_i,j - u_i) over the rows that are not stale, then a stale row gets
+// u_i = min (c_i,j - v_j) over all columns. The other cells were feasible before.
+fn restore_duals<N: Cost>(c: &DMatrix<Option<N>>, u: &mut [N], v: &mut [N], stale_rows: &[bool], stale_cols: &[bool]) -> Result<(), AssignmentError> {
+        let n = c.nrows();
+        for j in (0..n).filter(|&j| stale_cols[j]) {
+                let slack = (0..n).filter(|&i| !stale_rows[i]).filter_map(|i| c[(i, j)].map(|c_ij| checked(c_ij.checked_sub(u[i]), "c_ij - u_i")));
+                if let Some(v_j) = min_cost(slack.collect::<Result<Vec<_>, _>>()?) {
+                        v[j] = v_j;
+                }
+        }
+        for i in (0..n).filter(|&i| stale_rows[i]) {
+                let slack = (0..n).filter_map(|j| c[(i, j)].map(|c_ij| checked(c_ij.checked_sub(v[j]), "c_ij - v_j")));
+                if let Some(u_i) = min_cost(slack.collect::<Result<Vec<_>, _>>()?) {
+                        u[i] = u_i;
+                }
+        }
+        Ok(())
+}
+
+// Keeps the pairs of a previous assignment that no change touches and that are
+// still tight under the restored duals.
+fn restore_matching<N: Cost>(c: &DMatrix<Option<N>>, u: &[N], v: &[N], row_of_col: &mut [Option<usize>], assignment: &[(usize, usize)], stale_rows: &[bool], stale_cols: &[bool]) -> Result<(), AssignmentError> {
+        let n = c.nrows();
+        for &(i, j) in assignment.iter().filter(|&&(i, j)| i < n && j < n && !stale_rows[i] && !stale_cols[j]) {
+                if let Some(c_ij) = c[(i, j)] {
+                        let reduced = checked(c_ij.checked_sub(u[i]).and_then(|x| x.checked_sub(v[j])), "reduced cost")?;
+                        if reduced.is_zero() {
+                                row_of_col[j] = Some(i);
+                        }
+                }
+        }
+        Ok(())
+}
+
+// Dijkstra search from the free row `root` for the shortest augmenting path,
+// followed by the dual update and the augmentation. If no free column can be
+// reached, the scanned rows and columns are returned: the scanned rows have
+// only the scanned columns as partners.
+#[allow(clippy::type_complexity)]
+pub(super) fn augment<N: Cost>(c: &DMatrix<Option<N>>, u: &mut [N], v: &mut [N], row_of_col: &mut [Option<usize>], root: usize) -> Result<Option<(Vec<usize>, Vec<usize>)>, AssignmentError> {
+        let n = c.nrows();
+        // Shortest distance to each column and the column before it on the path,
+        // `None` for a column reached directly from the root.
+        let mut dist: Vec<Option<N>> = vec![None; n];
+        let mut prev_col: Vec<Option<usize>> = vec![None; n];
+        let mut scanned = vec![false; n];
+        let mut scanned_cols: Vec<usize> = Vec::new();
+
+        let mut row = root;
+        let mut last_col: Option<usize> = None;
+        let free_col = loop {
+                for j in (0..n).filter(|&j| !scanned[j]) {
+                        if let Some(c_ij) = c[(row, j)] {
+                                let reduced = checked(c_ij.checked_sub(u[row]).and_then(|x| x.checked_sub(v[j])), "reduced cost")?;
+                                let base = match last_col {
+                                        Some(col) => dist[col].unwrap_or_else(N::zero),
+                                        None => N::zero(),
+                                };
+                                let d = checked(base.checked_add(reduced), "path length")?;
+                                if dist[j].is_none_or(|x| d < x) {
+                                        dist[j] = Some(d);
+                                        prev_col[j] = last_col;
+                                }
+                        }
+                }
+
+                let next = (0..n).filter(|&j| !scanned[j]).filter_map(|j| dist[j].map(|d| (d, j))).fold(None, |acc: Option<(N, usize)>, (d, j)| match acc {
+                        Some((best, _)) if best <= d => acc,
+                        _ => Some((d, j)),
+                });
+                let (_, j) = match next {
+                        Some(next) => next,
+                        None => {
+                                let mut blue_rows = vec![root];
+                                blue_rows.extend(scanned_cols.iter().filter_map(|&j| row_of_col[j]));
+                                return Ok(Some((blue_rows, scanned_cols)));
+                        }
+                };
+
+                scanned[j] = true;
+                scanned_cols.push(j);
+                match row_of_col[j] {
+                        Some(i) => {
+                                row = i;
+                                last_col = Some(j);
+                        }
+                        None => break j,
+                }
+        };
+
+        // With d_j the distance of column j, every scanned column j moves by
+        // d_free - d_j and so does the row matched to it, which keeps all
+        // reduced costs non-negative and makes the path tight.
+        let d_free = dist[free_col].unwrap_or_else(N::zero);
+        u[root] = checked(u[root].checked_add(d_free), "u_i + d")?;
+        for &j in &scanned_cols {
+                let delta = checked(d_free.checked_sub(dist[j].unwrap_or_else(N::zero)), "path length")?;
+                if j != free_col {
+                        let i = row_of_col[j].unwrap_or(root);
+                        u[i] = checked(u[i].checked_add(delta), "u_i + d")?;
+                }
+                v[j] = checked(v[j].checked_sub(delta), "v_j - d")?;
+        }
+
+        let mut col = free_col;
+        loop {
+                match prev_col[col] {
+                        Some(before) => {
+                                row_of_col[col] = row_of_col[before];
+                                col = before;
+                        }
+                        None => {
+                                row_of_col[col] = Some(root);
+                                break;
+                        }
+                }
+        }
+        Ok(None)
+}
@@ -0,0 +1,75 @@
+use crate::comb_optimization::assignment_problem::assignment_problem::{node_label, ProblemState};
+use crate::comb_optimization::assignment_problem::error::{checked, AssignmentError};
+use crate::comb_optimization::cost::{sum_cost, Cost};
+use nalgebra::DMatrix;
+use rocket::serde::Serialize;
+
+/// Optimal assignment of the rows of `c` to its columns.
+///
+/// `u` and `v` are the final duals: u_i + v_j <= c_i,j for every allowed
+/// cell (>= for a maximisation problem), with equality on the assigned cells,
+/// so `cost` equals ∑u + ∑v. `states` is the full trace of the Hungarian
+/// method, from the input to the optimal matching.
+///
+/// `ranging` holds, for every cell, how far its cost can move before the
+/// assignment stops being optimal: how much an assigned cell can rise (fall,
+/// for a maximisation problem) and how much an unassigned cell has to drop
+/// (rise) to enter an optimal assignment. At exactly that amount there is a
+/// tie. `None` marks a forbidden cell or a cell whose cost never matters.
+#[derive(Debug, Clone, Serialize)]
+pub struct AssignmentSolution<N: Cost> {
+        /// Assigned (row, column) pairs, ordered by row.
+        pub assignment: Vec<(usize, usize)>,
+        pub cost: N,
+        pub u: Vec<N>,
+        pub v: Vec<N>,
+        /// Rows (or columns) of a rectangular problem that are not assigned.
+        pub unmatched_rows: Vec<usize>,
+        pub unmatched_cols: Vec<usize>,
+        /// Number of equality graphs built before a perfect matching was found.
+        pub iterations: usize,
+        pub ranging: DMatrix<Option<N>>,
+        pub states: Vec<ProblemState<N>>,
+        /// The assigned (row, column) pairs by name, when names were given.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub named_assignment: Option<Vec<(String, String)>>,
+}
+
+impl<N: Cost> AssignmentSolution<N> {
+        // Reads the solution off the reported states: the input matrix is in the
+        // first state, the duals and the matching in the last one. The ranging is
+        // computed by the solver, which still has the padded problem.
+        pub(crate) fn from_states(states: Vec<ProblemState<N>>, iterations: usize, ranging: DMatrix<Option<N>>) -> Result<Self, AssignmentError> {
+                let c = &states.first().ok_or(AssignmentError::EmptyMatrix)?.c;
+                let final_state = states.last().ok_or(AssignmentError::EmptyMatrix)?;
+                let (nrows, ncols) = c.shape();
+
+                let mut assignment: Vec<(usize, usize)> = final_state.m.iter().flatten().map(|(row_node, col_node)| (row_node.index(), col_node.index() - nrows)).collect();
+                assignment.sort_unstable();
+
+                // Only allowed cells are ever matched.
+                let cost = checked(sum_cost(assignment.iter().flat_map(|&(i, j)| c[(i, j)])), "assignment cost")?;
+                let unmatched_rows = (0..nrows).filter(|i| !assignment.iter().any(|p| p.0 == *i)).collect();
+                let unmatched_cols = (0..ncols).filter(|j| !assignment.iter().any(|p| p.1 == *j)).collect();
+
+                Ok(Self {
+                        assignment,
+                        cost,
+                        u: final_state.u.clone(),
+                        v: final_state.v.clone(),
+                        unmatched_rows,
+                        unmatched_cols,
+                        iterations,
+                        ranging,
+                        states,
+                        named_assignment: None,
+                })
+        }
+
+        // Sets `named_assignment`, labelling rows and columns without a name as
+        // the equality graphs do.
+        pub(crate) fn with_names(mut self, row_names: &[String], col_names: &[String]) -> Self {
+                self.named_assignment = Some(self.assignment.iter().map(|&(i, j)| (node_label(row_names, "s", i), node_label(col_names, "t", j))).collect());
+                self
+        }
+}
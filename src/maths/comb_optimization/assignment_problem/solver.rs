@@ -0,0 +1,87 @@
+use crate::comb_optimization::assignment_problem::assignment_problem::{Objective, OptimalSolution};
+use crate::comb_optimization::assignment_problem::auction::AuctionSolver;
+use crate::comb_optimization::assignment_problem::error::AssignmentError;
+use crate::comb_optimization::assignment_problem::shortest_path::{Change, ShortestPathSolver};
+use crate::comb_optimization::assignment_problem::solution::AssignmentSolution;
+use crate::comb_optimization::cost::Cost;
+use nalgebra::DMatrix;
+use rocket::serde::{Deserialize, Serialize};
+
+/// A backend for the assignment problem. Every backend accepts the same input
+/// (objective, forbidden cells, rectangular `c`, optional duals) and returns
+/// the same [`AssignmentSolution`], so they can be swapped freely.
+pub trait AssignmentSolver<N: Cost> {
+        fn set_objective(&mut self, objective: Objective);
+
+        fn set_repair_duals(&mut self, repair_duals: bool);
+
+        /// Record a state for every step instead of only the first and the last
+        /// one. Backends that always record every step, or never do, ignore it.
+        fn set_trace(&mut self, _trace: bool) {}
+
+        /// Names of the rows and columns, see [`OptimalSolution::set_names`].
+        fn set_names(&mut self, row_names: Vec<String>, col_names: Vec<String>);
+
+        /// `u` and `v` may be empty, `None` in `c` marks a forbidden cell.
+        fn init_with_forbidden(&mut self, u_invec: Vec<N>, v_invec: Vec<N>, c: DMatrix<Option<N>>);
+
+        fn init(&mut self, u_invec: Vec<N>, v_invec: Vec<N>, c: DMatrix<N>) {
+                self.init_with_forbidden(u_invec, v_invec, c.map(Some));
+        }
+
+        /// Re-solve from a `previous` solution after the cost matrix passed to
+        /// `init` was edited in `changes`; call it after `init`. Only the
+        /// [`ShortestPathSolver`] reuses the previous solution, the other backends
+        /// ignore it and solve from scratch.
+        fn warm_start(&mut self, _previous: &AssignmentSolution<N>, _changes: &[Change]) {}
+
+        fn find_optimum_cost(&mut self) -> Result<AssignmentSolution<N>, AssignmentError>;
+}
+
+/// The available [`AssignmentSolver`] backends.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SolverKind {
+        /// The Hungarian method on equality graphs, [`OptimalSolution`]. Slow, but
+        /// every step is recorded in the trace.
+        #[default]
+        Hungarian,
+        /// Shortest augmenting paths, [`ShortestPathSolver`]. O(n^3), for large
+        /// problems; only the first and the last state are recorded.
+        ShortestPath,
+        /// Bertsekas' auction with ϵ-scaling, [`AuctionSolver`]. Fast on large
+        /// problems with costs close to each other; records a state per bid on
+        /// request.
+        Auction,
+}
+
+/// A new, empty solver of the given kind.
+pub fn new_solver<N: Cost + 'static>(kind: SolverKind) -> Box<dyn AssignmentSolver<N>> {
+        match kind {
+                SolverKind::Hungarian => Box::new(OptimalSolution::new()),
+                SolverKind::ShortestPath => Box::new(ShortestPathSolver::new()),
+                SolverKind::Auction => Box::new(AuctionSolver::new()),
+        }
+}
+
+impl<N: Cost> AssignmentSolver<N> for OptimalSolution<N> {
+        fn set_objective(&mut self, objective: Objective) {
+                OptimalSolution::set_objective(self, objective);
+        }
+
+        fn set_repair_duals(&mut self, repair_duals: bool) {
+                OptimalSolution::set_repair_duals(self, repair_duals);
+        }
+
+        fn set_names(&mut self, row_names: Vec<String>, col_names: Vec<String>) {
+                OptimalSolution::set_names(self, row_names, col_names);
+        }
+
+        fn init_with_forbidden(&mut self, u_invec: Vec<N>, v_invec: Vec<N>, c: DMatrix<Option<N>>) {
+                OptimalSolution::init_with_forbidden(self, u_invec, v_invec, c);
+        }
+
+        fn find_optimum_cost(&mut self) -> Result<AssignmentSolution<N>, AssignmentError> {
+                OptimalSolution::find_optimum_cost(self)
+        }
+}
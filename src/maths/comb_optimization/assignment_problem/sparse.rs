@@ -0,0 +1,331 @@
+use crate::comb_optimization::assignment_problem::assignment_problem::{HallViolator, Objective, Side};
+use crate::comb_optimization::assignment_problem::error::{checked, AssignmentError};
+use crate::comb_optimization::cost::{sum_cost, Cost};
+use rocket::serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// Cost matrix given by its allowed cells only, for large problems in which
+/// every row has a few allowed columns. Memory grows with the number of
+/// allowed cells, not with nrows * ncols.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseCostMatrix<N: Cost> {
+        nrows: usize,
+        ncols: usize,
+        // Allowed (column, cost) pairs of every row.
+        rows: Vec<Vec<(usize, N)>>,
+}
+
+impl<N: Cost> SparseCostMatrix<N> {
+        /// An `nrows` x `ncols` cost matrix whose allowed cells are the (row,
+        /// column, cost) `edges`; every other cell is forbidden. Fails on an
+        /// empty shape, on an edge outside of it and on a cell given twice.
+        pub fn from_edges(nrows: usize, ncols: usize, edges: &[(usize, usize, N)]) -> Result<Self, AssignmentError> {
+                if nrows == 0 || ncols == 0 {
+                        return Err(AssignmentError::EmptyMatrix);
+                }
+                let mut rows: Vec<Vec<(usize, N)>> = vec![Vec::new(); nrows];
+                for &(row, col, cost) in edges {
+                        if row >= nrows || col >= ncols {
+                                return Err(AssignmentError::EdgeOutOfRange { row, col, nrows, ncols });
+                        }
+                        rows[row].push((col, cost));
+                }
+                for (row, cells) in rows.iter_mut().enumerate() {
+                        cells.sort_unstable_by_key(|&(col, _)| col);
+                        if let Some(pair) = cells.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+                                return Err(AssignmentError::DuplicateEdge { row, col: pair[0].0 });
+                        }
+                }
+                Ok(Self { nrows, ncols, rows })
+        }
+
+        pub fn nrows(&self) -> usize {
+                self.nrows
+        }
+
+        pub fn ncols(&self) -> usize {
+                self.ncols
+        }
+
+        /// Number of allowed cells.
+        pub fn len(&self) -> usize {
+                self.rows.iter().map(Vec::len).sum()
+        }
+
+        pub fn is_empty(&self) -> bool {
+                self.len() == 0
+        }
+
+        /// Allowed (column, cost) pairs of row `i`, ordered by column.
+        pub fn row(&self, i: usize) -> &[(usize, N)] {
+                &self.rows[i]
+        }
+
+        /// The cost of cell (i, j), `None` for a forbidden cell.
+        pub fn get(&self, i: usize, j: usize) -> Option<N> {
+                let cells = self.rows.get(i)?;
+                cells.binary_search_by_key(&j, |&(col, _)| col).ok().map(|k| cells[k].1)
+        }
+
+        pub fn transpose(&self) -> Self {
+                let mut rows: Vec<Vec<(usize, N)>> = vec![Vec::new(); self.ncols];
+                for (i, cells) in self.rows.iter().enumerate() {
+                        for &(j, cost) in cells {
+                                rows[j].push((i, cost));
+                        }
+                }
+                Self {
+                        nrows: self.ncols,
+                        ncols: self.nrows,
+                        rows,
+                }
+        }
+}
+
+/// Optimal assignment of a [`SparseCostMatrix`]. The fields mean the same as
+/// in [`AssignmentSolution`](crate::comb_optimization::AssignmentSolution),
+/// which also holds the dense trace and ranging that a sparse problem leaves
+/// out.
+#[derive(Debug, Clone, Serialize)]
+pub struct SparseAssignmentSolution<N: Cost> {
+        /// Assigned (row, column) pairs, ordered by row.
+        pub assignment: Vec<(usize, usize)>,
+        pub cost: N,
+        pub u: Vec<N>,
+        pub v: Vec<N>,
+        pub unmatched_rows: Vec<usize>,
+        pub unmatched_cols: Vec<usize>,
+        /// Number of augmenting paths.
+        pub iterations: usize,
+}
+
+/// Solves the assignment problem on a [`SparseCostMatrix`] without building
+/// the dense matrix, with the shortest augmenting paths of
+/// [`ShortestPathSolver`](crate::comb_optimization::ShortestPathSolver): a
+/// Dijkstra search per row over the allowed cells only, with a binary heap,
+/// in O(n E log n) time for E allowed cells and O(n + E) memory.
+///
+/// The smaller side is assigned completely; the other side is not padded,
+/// a search simply ends at any free column. Starting from u = v = 0, the
+/// duals of the free columns stay 0 and the others become non-positive
+/// (non-negative for a maximisation problem, which is solved as max c - c),
+/// so they are the duals of the rectangular problem and ∑u + ∑v is the cost.
+///
+/// The column duals of a minimisation problem thus become negative as soon
+/// as a search passes through an assigned column, which an unsigned cost
+/// type cannot hold: use a signed type, since such problems fail with
+/// [`AssignmentError::Overflow`].
+pub fn solve_sparse<N: Cost>(c: &SparseCostMatrix<N>, objective: Objective) -> Result<SparseAssignmentSolution<N>, AssignmentError> {
+        if c.nrows > c.ncols {
+                return match solve_sparse(&c.transpose(), objective) {
+                        Ok(solution) => {
+                                let mut assignment: Vec<(usize, usize)> = solution.assignment.iter().map(|&(j, i)| (i, j)).collect();
+                                assignment.sort_unstable();
+                                Ok(SparseAssignmentSolution {
+                                        assignment,
+                                        cost: solution.cost,
+                                        u: solution.v,
+                                        v: solution.u,
+                                        unmatched_rows: solution.unmatched_cols,
+                                        unmatched_cols: solution.unmatched_rows,
+                                        iterations: solution.iterations,
+                                })
+                        }
+                        Err(AssignmentError::NoPerfectMatching { hall_violator }) => Err(HallViolator { side: Side::Columns, ..hall_violator }.into()),
+                        Err(err) => Err(err),
+                };
+        }
+
+        // Profits p become costs max p - p.
+        let offset = match objective {
+                Objective::Minimize => None,
+                Objective::Maximize => c.rows.iter().flatten().map(|&(_, p)| p).fold(None, |m: Option<N>, p| match m {
+                        Some(m) if m >= p => Some(m),
+                        _ => Some(p),
+                }),
+        };
+        let converted;
+        let costs = match offset {
+                Some(offset) => {
+                        let rows = c.rows.iter().map(|cells| cells.iter().map(|&(j, p)| Ok((j, checked(offset.checked_sub(p), "max c - c_ij")?))).collect::<Result<Vec<_>, AssignmentError>>()).collect::<Result<Vec<_>, _>>()?;
+                        converted = SparseCostMatrix { nrows: c.nrows, ncols: c.ncols, rows };
+                        &converted
+                }
+                None => c,
+        };
+
+        let mut u = vec![N::zero(); c.nrows];
+        let mut v = vec![N::zero(); c.ncols];
+        let mut row_of_col: Vec<Option<usize>> = vec![None; c.ncols];
+        let mut search = Search::new(c.ncols);
+        for root in 0..c.nrows {
+                if let Some(members) = search.augment(costs, &mut u, &mut v, &mut row_of_col, root)? {
+                        let mut neighbours: Vec<usize> = members.iter().flat_map(|&i| c.rows[i].iter().map(|&(j, _)| j)).collect();
+                        neighbours.sort_unstable();
+                        neighbours.dedup();
+                        return Err(HallViolator {
+                                side: Side::Rows,
+                                members,
+                                neighbours,
+                        }
+                        .into());
+                }
+        }
+
+        if let Some(offset) = offset {
+                u = u.iter().map(|&u_i| checked(offset.checked_sub(u_i), "max c - u_i")).collect::<Result<_, _>>()?;
+                v = v.iter().map(|&v_j| checked(N::zero().checked_sub(v_j), "-v_j")).collect::<Result<_, _>>()?;
+        }
+
+        let mut assignment: Vec<(usize, usize)> = row_of_col.iter().enumerate().filter_map(|(j, i)| i.map(|i| (i, j))).collect();
+        assignment.sort_unstable();
+        // Only allowed cells are ever matched.
+        let cost = checked(sum_cost(assignment.iter().flat_map(|&(i, j)| c.get(i, j))), "assignment cost")?;
+        let unmatched_cols = (0..c.ncols).filter(|&j| row_of_col[j].is_none()).collect();
+        Ok(SparseAssignmentSolution {
+                assignment,
+                cost,
+                u,
+                v,
+                unmatched_rows: Vec::new(),
+                unmatched_cols,
+                iterations: c.nrows,
+        })
+}
+
+// A column waiting in the heap with its tentative distance, closest first.
+struct Candidate<N>(N, usize);
+
+impl<N: Cost> PartialEq for Candidate<N> {
+        fn eq(&self, other: &Self) -> bool {
+                self.cmp(other) == Ordering::Equal
+        }
+}
+
+impl<N: Cost> Eq for Candidate<N> {}
+
+impl<N: Cost> PartialOrd for Candidate<N> {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+        }
+}
+
+impl<N: Cost> Ord for Candidate<N> {
+        fn cmp(&self, other: &Self) -> Ordering {
+                other.0.partial_cmp(&self.0).unwrap_or(Ordering::Equal).then_with(|| other.1.cmp(&self.1))
+        }
+}
+
+// Per-column state of a Dijkstra search, reset only where a search touched it
+// so that a search costs time in the number of cells it looks at.
+struct Search<N> {
+        dist: Vec<Option<N>>,
+        prev_col: Vec<Option<usize>>,
+        scanned: Vec<bool>,
+        touched: Vec<usize>,
+}
+
+impl<N: Cost> Search<N> {
+        fn new(ncols: usize) -> Self {
+                Self {
+                        dist: vec![None; ncols],
+                        prev_col: vec![None; ncols],
+                        scanned: vec![false; ncols],
+                        touched: Vec::new(),
+                }
+        }
+
+        // Shortest augmenting path from the free row `root`, the dual update and the
+        // augmentation, as in the dense solver. If no free column can be reached,
+        // the scanned rows are returned: their only partners are the scanned columns,
+        // which are all matched to other scanned rows.
+        fn augment(&mut self, c: &SparseCostMatrix<N>, u: &mut [N], v: &mut [N], row_of_col: &mut [Option<usize>], root: usize) -> Result<Option<Vec<usize>>, AssignmentError> {
+                for &j in &self.touched {
+                        self.dist[j] = None;
+                        self.prev_col[j] = None;
+                        self.scanned[j] = false;
+                }
+                self.touched.clear();
+
+                let mut heap = BinaryHeap::new();
+                let mut scanned_cols: Vec<usize> = Vec::new();
+                let mut row = root;
+                let mut last_col: Option<usize> = None;
+                let free_col = loop {
+                        let base = match last_col {
+                                Some(col) => self.dist[col].unwrap_or_else(N::zero),
+                                None => N::zero(),
+                        };
+                        for &(j, c_ij) in c.row(row) {
+                                if self.scanned[j] {
+                                        continue;
+                                }
+                                let reduced = checked(c_ij.checked_sub(u[row]).and_then(|x| x.checked_sub(v[j])), "reduced cost")?;
+                                let d = checked(base.checked_add(reduced), "path length")?;
+                                if self.dist[j].is_none_or(|x| d < x) {
+                                        if self.dist[j].is_none() {
+                                                self.touched.push(j);
+                                        }
+                                        self.dist[j] = Some(d);
+                                        self.prev_col[j] = last_col;
+                                        heap.push(Candidate(d, j));
+                                }
+                        }
+
+                        // Entries of columns that were scanned or reached by a shorter path since are stale.
+                        let j = loop {
+                                match heap.pop() {
+                                        Some(Candidate(d, j)) if !self.scanned[j] && self.dist[j] == Some(d) => break Some(j),
+                                        Some(_) => continue,
+                                        None => break None,
+                                }
+                        };
+                        let j = match j {
+                                Some(j) => j,
+                                None => {
+                                        let mut members = vec![root];
+                                        members.extend(scanned_cols.iter().filter_map(|&j| row_of_col[j]));
+                                        members.sort_unstable();
+                                        return Ok(Some(members));
+                                }
+                        };
+
+                        self.scanned[j] = true;
+                        scanned_cols.push(j);
+                        match row_of_col[j] {
+                                Some(i) => {
+                                        row = i;
+                                        last_col = Some(j);
+                                }
+                                None => break j,
+                        }
+                };
+
+                let d_free = self.dist[free_col].unwrap_or_else(N::zero);
+                u[root] = checked(u[root].checked_add(d_free), "u_i + d")?;
+                for &j in &scanned_cols {
+                        let delta = checked(d_free.checked_sub(self.dist[j].unwrap_or_else(N::zero)), "path length")?;
+                        if j != free_col {
+                                let i = row_of_col[j].unwrap_or(root);
+                                u[i] = checked(u[i].checked_add(delta), "u_i + d")?;
+                        }
+                        v[j] = checked(v[j].checked_sub(delta), "v_j - d")?;
+                }
+
+                let mut col = free_col;
+                loop {
+                        match self.prev_col[col] {
+                                Some(before) => {
+                                        row_of_col[col] = row_of_col[before];
+                                        col = before;
+                                }
+                                None => {
+                                        row_of_col[col] = Some(root);
+                                        break;
+                                }
+                        }
+                }
+                Ok(None)
+        }
+}
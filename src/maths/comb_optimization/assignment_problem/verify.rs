@@ -0,0 +1,194 @@
+use crate::comb_optimization::assignment_problem::assignment_problem::{dual_violations, Objective, Side};
+use crate::comb_optimization::assignment_problem::error::{checked, AssignmentError};
+use crate::comb_optimization::cost::{sum_cost, Cost};
+use nalgebra::DMatrix;
+use rocket::serde::Serialize;
+
+/// One way in which an assignment and its duals fail to prove optimality.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "violation")]
+pub enum CertificateViolation<N: Cost> {
+        /// The assigned cell (`row`, `col`) lies outside of `c`.
+        CellOutOfRange { row: usize, col: usize },
+
+        /// The assigned cell (`row`, `col`) is forbidden.
+        ForbiddenCell { row: usize, col: usize },
+
+        /// Row (side `Rows`) or column (side `Columns`) `index` is assigned more than once.
+        AssignedTwice { side: Side, index: usize },
+
+        /// Row or column `index` is not assigned, although its side is the
+        /// smaller one (or `c` is square).
+        NotAssigned { side: Side, index: usize },
+
+        /// u_i + v_j > c_i,j (< for a maximisation problem) at the allowed cell
+        /// (`row`, `col`).
+        InfeasibleCell { row: usize, col: usize, cost: N, dual_sum: N },
+
+        /// A dual of the larger side of a rectangular problem is positive
+        /// (negative for a maximisation problem). Those rows or columns may stay
+        /// unassigned, so their duals are only bounded on one side.
+        InfeasibleSign { side: Side, index: usize, dual: N },
+
+        /// u_i + v_j != c_i,j at the assigned cell (`row`, `col`).
+        NotTight { row: usize, col: usize, cost: N, dual_sum: N },
+
+        /// Row or column `index` of the larger side is unassigned but its dual is not zero.
+        SlackDual { side: Side, index: usize, dual: N },
+}
+
+/// Outcome of [`verify_assignment`], one list of violations per condition.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VerificationReport<N: Cost> {
+        /// Whether every condition holds, so the assignment is optimal and the duals prove it.
+        pub optimal: bool,
+        /// Cost of the assigned cells that lie in `c` and are allowed.
+        pub cost: N,
+        /// ∑u + ∑v.
+        pub dual_objective: N,
+        /// The assignment is not an assignment of the smaller side of `c`.
+        pub assignment: Vec<CertificateViolation<N>>,
+        pub dual_feasibility: Vec<CertificateViolation<N>>,
+        pub complementary_slackness: Vec<CertificateViolation<N>>,
+        /// Whether `cost` equals `dual_objective`, up to `Cost::tolerance()`.
+        pub cost_equality: bool,
+}
+
+/// Checks independently of any solver that `assignment` is optimal for `c`,
+/// with the duals `u` and `v` as the certificate:
+///
+/// - the assignment uses allowed cells of `c`, every row and column at most
+///   once, and covers the smaller side (both sides of a square matrix);
+/// - the duals are feasible: u_i + v_j <= c_i,j on every allowed cell (>= for
+///   a maximisation problem), and the duals of the larger side of a
+///   rectangular problem are at most zero (at least zero);
+/// - complementary slackness: u_i + v_j = c_i,j on every assigned cell, and
+///   the unassigned rows or columns of the larger side have a zero dual;
+/// - the cost of the assignment equals ∑u + ∑v.
+///
+/// For an assignment with feasible duals, complementary slackness and the
+/// cost equality hold together or not at all; both are reported so that a
+/// failing equality comes with the cells and lines that break it. Floating
+/// point sums may be off by `Cost::tolerance()`.
+///
+/// Fails if `c` is empty or if `u` and `v` do not have one entry per row and
+/// column of `c`.
+pub fn verify_assignment<N: Cost>(c: &DMatrix<Option<N>>, assignment: &[(usize, usize)], u: &[N], v: &[N], objective: Objective) -> Result<VerificationReport<N>, AssignmentError> {
+        let (nrows, ncols) = c.shape();
+        if nrows == 0 || ncols == 0 {
+                return Err(AssignmentError::EmptyMatrix);
+        }
+        if u.len() != nrows {
+                return Err(AssignmentError::DualLengthMismatch {
+                        side: Side::Rows,
+                        expected: nrows,
+                        found: u.len(),
+                });
+        }
+        if v.len() != ncols {
+                return Err(AssignmentError::DualLengthMismatch {
+                        side: Side::Columns,
+                        expected: ncols,
+                        found: v.len(),
+                });
+        }
+
+        // The assignment itself, keeping the allowed cells that lie in `c`.
+        let mut assignment_violations = Vec::new();
+        let mut cells = Vec::new();
+        for &(row, col) in assignment {
+                if row >= nrows || col >= ncols {
+                        assignment_violations.push(CertificateViolation::CellOutOfRange { row, col });
+                } else if let Some(c_ij) = c[(row, col)] {
+                        cells.push((row, col, c_ij));
+                } else {
+                        assignment_violations.push(CertificateViolation::ForbiddenCell { row, col });
+                }
+        }
+        let in_range: Vec<&(usize, usize)> = assignment.iter().filter(|&&(row, col)| row < nrows && col < ncols).collect();
+        let row_counts = count_assigned(nrows, in_range.iter().map(|p| p.0));
+        let col_counts = count_assigned(ncols, in_range.iter().map(|p| p.1));
+        for (side, counts, smaller) in [(Side::Rows, &row_counts, nrows <= ncols), (Side::Columns, &col_counts, ncols <= nrows)] {
+                for (index, &count) in counts.iter().enumerate() {
+                        if count > 1 {
+                                assignment_violations.push(CertificateViolation::AssignedTwice { side, index });
+                        } else if count == 0 && smaller {
+                                assignment_violations.push(CertificateViolation::NotAssigned { side, index });
+                        }
+                }
+        }
+
+        // Dual feasibility, with the sign of the larger side's duals.
+        let mut dual_feasibility = Vec::new();
+        for (row, col) in dual_violations(u, v, c, objective)? {
+                dual_feasibility.push(CertificateViolation::InfeasibleCell {
+                        row,
+                        col,
+                        cost: c[(row, col)].unwrap_or_else(N::zero),
+                        dual_sum: checked(u[row].checked_add(v[col]), "u_i + v_j")?,
+                });
+        }
+        let larger = if nrows < ncols {
+                Some((Side::Columns, v, &col_counts))
+        } else if ncols < nrows {
+                Some((Side::Rows, u, &row_counts))
+        } else {
+                None
+        };
+        if let Some((side, duals, _)) = larger {
+                for (index, &dual) in duals.iter().enumerate() {
+                        let feasible = dual.is_zero()
+                                || match objective {
+                                        Objective::Minimize => dual < N::zero(),
+                                        Objective::Maximize => dual > N::zero(),
+                                };
+                        if !feasible {
+                                dual_feasibility.push(CertificateViolation::InfeasibleSign { side, index, dual });
+                        }
+                }
+        }
+
+        // Complementary slackness.
+        let mut complementary_slackness = Vec::new();
+        for &(row, col, c_ij) in &cells {
+                let dual_sum = checked(u[row].checked_add(v[col]), "u_i + v_j")?;
+                if !close(dual_sum, c_ij) {
+                        complementary_slackness.push(CertificateViolation::NotTight { row, col, cost: c_ij, dual_sum });
+                }
+        }
+        if let Some((side, duals, counts)) = larger {
+                for (index, &dual) in duals.iter().enumerate() {
+                        if counts[index] == 0 && !dual.is_zero() {
+                                complementary_slackness.push(CertificateViolation::SlackDual { side, index, dual });
+                        }
+                }
+        }
+
+        let cost = checked(sum_cost(cells.iter().map(|cell| cell.2)), "assignment cost")?;
+        let dual_objective = checked(sum_cost(u.iter().chain(v.iter()).copied()), "∑u + ∑v")?;
+        let cost_equality = close(cost, dual_objective);
+        Ok(VerificationReport {
+                optimal: assignment_violations.is_empty() && dual_feasibility.is_empty() && complementary_slackness.is_empty() && cost_equality,
+                cost,
+                dual_objective,
+                assignment: assignment_violations,
+                dual_feasibility,
+                complementary_slackness,
+                cost_equality,
+        })
+}
+
+// How often each of the `len` rows or columns appears in `indices`.
+fn count_assigned(len: usize, indices: impl Iterator<Item = usize>) -> Vec<usize> {
+        let mut counts = vec![0; len];
+        for index in indices {
+                counts[index] += 1;
+        }
+        counts
+}
+
+// a == b up to the tolerance of the cost type, without leaving its range.
+fn close<N: Cost>(a: N, b: N) -> bool {
+        let difference = if a >= b { a.checked_sub(b) } else { b.checked_sub(a) };
+        difference.is_some_and(|d| d.is_zero())
+}
@@ -0,0 +1,96 @@
+use crate::comb_optimization::assignment_problem::{solve_bottleneck, Objective, Side};
+use crate::comb_optimization::birkhoff::error::{checked, BirkhoffError};
+use crate::comb_optimization::cost::{sum_cost, Cost};
+use nalgebra::DMatrix;
+use rocket::serde::Serialize;
+
+/// One term of a [`BirkhoffDecomposition`]: `coefficient` times the
+/// permutation matrix with a one at (i, `permutation[i]`) for every row i.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BirkhoffTerm<N: Cost> {
+        pub coefficient: N,
+        /// The column of every row.
+        pub permutation: Vec<usize>,
+}
+
+/// A matrix written as ∑ coefficient × permutation matrix.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BirkhoffDecomposition<N: Cost> {
+        /// The sum of every row and column of the matrix, and so of the
+        /// coefficients: 1 for a doubly stochastic matrix, whose decomposition is
+        /// a convex combination.
+        pub line_sum: N,
+        /// The terms, by decreasing coefficient.
+        pub terms: Vec<BirkhoffTerm<N>>,
+}
+
+/// Birkhoff–von Neumann decomposition of `x`, a square matrix with
+/// non-negative entries whose rows and columns all have the same sum, e.g. a
+/// doubly stochastic matrix.
+///
+/// By Birkhoff's theorem (Hall's theorem on the support graph, with an edge
+/// (i, j) for every non-zero x_i,j) such a matrix has a permutation inside its
+/// support. Subtracting it, times its smallest entry, from x leaves a matrix
+/// of the same kind with at least one zero more, so at most n^2 - 2n + 2 terms
+/// are needed. Each step takes the permutation whose smallest entry is
+/// largest, found by [`solve_bottleneck`] with the zero cells forbidden, which
+/// keeps the coefficients large.
+///
+/// With [`Rational`](crate::number_theory::Rational) entries, or integer ones
+/// for a matrix of integer line sums, the decomposition is exact. With
+/// floating point entries an entry within `Cost::tolerance()` of zero is zero,
+/// and rounding may leave a remainder without a permutation, which fails with
+/// [`BirkhoffError::NoPermutation`].
+///
+/// Fails if `x` is empty or not square, has a negative entry, or if a row or
+/// column does not sum to the sum of row 0.
+pub fn birkhoff_decomposition<N: Cost>(x: &DMatrix<N>) -> Result<BirkhoffDecomposition<N>, BirkhoffError> {
+        let line_sum = check_line_sums(x)?;
+
+        // Zero cells are forbidden, so that the permutations stay in the support.
+        let mut remainder = x.map(|x_ij| Some(x_ij).filter(|x_ij| !x_ij.is_zero()));
+        let mut terms = Vec::new();
+        while remainder.iter().any(Option::is_some) {
+                let solution = solve_bottleneck(&remainder, Objective::Maximize)?;
+                let coefficient = solution.bottleneck;
+                let mut permutation = vec![0; x.nrows()];
+                for (row, col) in solution.assignment {
+                        permutation[row] = col;
+                        let left = match remainder[(row, col)] {
+                                Some(x_ij) => checked(x_ij.checked_sub(coefficient), "x_i,j - coefficient")?,
+                                None => N::zero(),
+                        };
+                        remainder[(row, col)] = Some(left).filter(|left| !left.is_zero());
+                }
+                terms.push(BirkhoffTerm { coefficient, permutation });
+        }
+        Ok(BirkhoffDecomposition { line_sum, terms })
+}
+
+// The common sum of the rows and columns of `x`, after rejecting an empty or
+// rectangular `x` and negative entries.
+fn check_line_sums<N: Cost>(x: &DMatrix<N>) -> Result<N, BirkhoffError> {
+        let (nrows, ncols) = x.shape();
+        if nrows == 0 || ncols == 0 {
+                return Err(BirkhoffError::EmptyMatrix);
+        }
+        if nrows != ncols {
+                return Err(BirkhoffError::NotSquare { nrows, ncols });
+        }
+        for col in 0..ncols {
+                if let Some(row) = (0..nrows).find(|&row| x[(row, col)] < N::zero() && !x[(row, col)].is_zero()) {
+                        return Err(BirkhoffError::NegativeEntry { row, col });
+                }
+        }
+
+        let line_sum = checked(sum_cost(x.row(0).iter().copied()), "row sum")?;
+        let sums = (0..nrows).map(|i| (Side::Rows, i, sum_cost(x.row(i).iter().copied()))).chain((0..ncols).map(|j| (Side::Columns, j, sum_cost(x.column(j).iter().copied()))));
+        for (side, index, sum) in sums {
+                let sum = checked(sum, "line sum")?;
+                let difference = if sum >= line_sum { sum.checked_sub(line_sum) } else { line_sum.checked_sub(sum) };
+                if !difference.is_some_and(|d| d.is_zero()) {
+                        return Err(BirkhoffError::UnequalLineSums { side, index });
+                }
+        }
+        Ok(line_sum)
+}
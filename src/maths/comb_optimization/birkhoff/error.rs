@@ -0,0 +1,84 @@
+use crate::comb_optimization::assignment_problem::{AssignmentError, Side};
+use rocket::serde::Serialize;
+use std::error::Error;
+use std::fmt;
+
+/// Everything that can go wrong between reading a matrix and returning its
+/// Birkhoff–von Neumann decomposition.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "error")]
+pub enum BirkhoffError {
+        /// The input is not valid JSON or an entry is not a number of the entry type.
+        InvalidInput { details: String },
+
+        /// The matrix has no rows or no columns.
+        EmptyMatrix,
+
+        /// Row `row` of the matrix has `found` entries instead of the `expected` entries of row 0.
+        RaggedRows { row: usize, expected: usize, found: usize },
+
+        /// The matrix has `nrows` rows and `ncols` columns, but has to be square.
+        NotSquare { nrows: usize, ncols: usize },
+
+        /// The entry (`row`, `col`) is negative, but has to be at least zero.
+        NegativeEntry { row: usize, col: usize },
+
+        /// Row (side `Rows`) or column (side `Columns`) `index` does not have the
+        /// sum of row 0.
+        UnequalLineSums { side: Side, index: usize },
+
+        /// What is left of a floating point matrix has no permutation in its
+        /// support, after rounding errors.
+        NoPermutation,
+
+        /// A computation left the range of the entry type.
+        Overflow { operation: &'static str },
+}
+
+impl fmt::Display for BirkhoffError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                        BirkhoffError::InvalidInput { details } => write!(f, "invalid input: {}", details),
+                        BirkhoffError::EmptyMatrix => write!(f, "the matrix is empty"),
+                        BirkhoffError::RaggedRows { row, expected, found } => write!(f, "row {} of the matrix has {} entries, expected {}", row, found, expected),
+                        BirkhoffError::NotSquare { nrows, ncols } => write!(f, "the matrix is {} x {}, not square", nrows, ncols),
+                        BirkhoffError::NegativeEntry { row, col } => write!(f, "the entry ({}, {}) is negative", row, col),
+                        BirkhoffError::UnequalLineSums { side, index } => {
+                                let name = match side {
+                                        Side::Rows => "row",
+                                        Side::Columns => "column",
+                                };
+                                write!(f, "{} {} does not have the sum of row 0", name, index)
+                        }
+                        BirkhoffError::NoPermutation => write!(f, "the remainder has no permutation in its support"),
+                        BirkhoffError::Overflow { operation } => write!(f, "{} overflowed", operation),
+                }
+        }
+}
+
+impl Error for BirkhoffError {}
+
+impl From<serde_json::Error> for BirkhoffError {
+        fn from(err: serde_json::Error) -> Self {
+                BirkhoffError::InvalidInput { details: err.to_string() }
+        }
+}
+
+// The input checks and the bottleneck assignments shared with the assignment problem.
+impl From<AssignmentError> for BirkhoffError {
+        fn from(err: AssignmentError) -> Self {
+                match err {
+                        AssignmentError::InvalidInput { details } => BirkhoffError::InvalidInput { details },
+                        AssignmentError::EmptyMatrix => BirkhoffError::EmptyMatrix,
+                        AssignmentError::RaggedRows { row, expected, found } => BirkhoffError::RaggedRows { row, expected, found },
+                        AssignmentError::NoPerfectMatching { .. } => BirkhoffError::NoPermutation,
+                        AssignmentError::Overflow { operation } => BirkhoffError::Overflow { operation },
+                        err => BirkhoffError::InvalidInput { details: err.to_string() },
+                }
+        }
+}
+
+/// `Some` result of a checked operation, or a [`BirkhoffError::Overflow`] naming it.
+pub(crate) fn checked<N>(result: Option<N>, operation: &'static str) -> Result<N, BirkhoffError> {
+        result.ok_or(BirkhoffError::Overflow { operation })
+}
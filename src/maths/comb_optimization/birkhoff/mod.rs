@@ -0,0 +1,6 @@
+pub use birkhoff::{birkhoff_decomposition, BirkhoffDecomposition, BirkhoffTerm};
+pub use error::BirkhoffError;
+
+#[allow(clippy::module_inception)]
+pub mod birkhoff;
+pub mod error;
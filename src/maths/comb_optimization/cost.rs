@@ -0,0 +1,186 @@
+use crate::number_theory::Rational;
+use nalgebra::Scalar;
+use serde::Serialize;
+use std::fmt::Display;
+
+/// Numeric types that can be used as the entries of a cost matrix.
+///
+/// The assignment solver only needs ordering, addition and subtraction, but it
+/// needs them checked: the dual variables `u` and `v` move by ϵ on every
+/// iteration and a silent wrap-around would give a wrong answer. The
+/// transportation solver also multiplies shipped amounts by unit costs, and
+/// the auction solver divides its ϵ. For floating point costs a reduced cost
+/// `c_ij - u_i - v_j` whose magnitude is within `tolerance()` is treated as
+/// zero when the equality graph is built.
+///
+/// Implemented for all primitive signed and unsigned integers, for `f32`
+/// and `f64`, and for exact fractions, [`Rational`]. With unsigned costs the
+/// solver fails with an overflow as soon as a dual variable would have to
+/// become negative.
+pub trait Cost: Scalar + Copy + PartialOrd + Display + Serialize {
+        fn zero() -> Self;
+
+        /// Largest magnitude that is still considered to be zero.
+        fn tolerance() -> Self;
+
+        fn checked_add(self, rhs: Self) -> Option<Self>;
+
+        fn checked_sub(self, rhs: Self) -> Option<Self>;
+
+        fn checked_mul(self, rhs: Self) -> Option<Self>;
+
+        /// Division, rounded towards zero for integers.
+        fn checked_div(self, rhs: Self) -> Option<Self>;
+
+        /// `n` as a cost, `None` if it does not fit.
+        fn from_usize(n: usize) -> Option<Self>;
+
+        /// The value as an `f64`, rounded for large integers. Used where bounds
+        /// are estimated rather than computed exactly.
+        fn to_f64(self) -> f64;
+
+        /// `true` if the value is zero up to `tolerance()`.
+        fn is_zero(self) -> bool;
+}
+
+macro_rules! impl_cost_for_integer {
+        ($($t:ty),*) => {
+                $(
+                        impl Cost for $t {
+                                fn zero() -> Self {
+                                        0
+                                }
+
+                                fn tolerance() -> Self {
+                                        0
+                                }
+
+                                fn checked_add(self, rhs: Self) -> Option<Self> {
+                                        <$t>::checked_add(self, rhs)
+                                }
+
+                                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                                        <$t>::checked_sub(self, rhs)
+                                }
+
+                                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                                        <$t>::checked_mul(self, rhs)
+                                }
+
+                                fn checked_div(self, rhs: Self) -> Option<Self> {
+                                        <$t>::checked_div(self, rhs)
+                                }
+
+                                fn from_usize(n: usize) -> Option<Self> {
+                                        <$t>::try_from(n).ok()
+                                }
+
+                                fn to_f64(self) -> f64 {
+                                        self as f64
+                                }
+
+                                fn is_zero(self) -> bool {
+                                        self == 0
+                                }
+                        }
+                )*
+        };
+}
+
+macro_rules! impl_cost_for_float {
+        ($($t:ty => $tol:expr),*) => {
+                $(
+                        impl Cost for $t {
+                                fn zero() -> Self {
+                                        0.0
+                                }
+
+                                fn tolerance() -> Self {
+                                        $tol
+                                }
+
+                                fn checked_add(self, rhs: Self) -> Option<Self> {
+                                        Some(self + rhs).filter(|x| x.is_finite())
+                                }
+
+                                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                                        Some(self - rhs).filter(|x| x.is_finite())
+                                }
+
+                                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                                        Some(self * rhs).filter(|x| x.is_finite())
+                                }
+
+                                fn checked_div(self, rhs: Self) -> Option<Self> {
+                                        Some(self / rhs).filter(|x| x.is_finite())
+                                }
+
+                                fn from_usize(n: usize) -> Option<Self> {
+                                        Some(n as $t)
+                                }
+
+                                fn to_f64(self) -> f64 {
+                                        self as f64
+                                }
+
+                                fn is_zero(self) -> bool {
+                                        self.abs() <= $tol
+                                }
+                        }
+                )*
+        };
+}
+
+impl_cost_for_integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+impl_cost_for_float!(f32 => 1e-5, f64 => 1e-9);
+
+impl Cost for Rational {
+        fn zero() -> Self {
+                Rational::from_integer(0)
+        }
+
+        fn tolerance() -> Self {
+                Rational::from_integer(0)
+        }
+
+        fn checked_add(self, rhs: Self) -> Option<Self> {
+                Rational::checked_add(self, rhs)
+        }
+
+        fn checked_sub(self, rhs: Self) -> Option<Self> {
+                Rational::checked_sub(self, rhs)
+        }
+
+        fn checked_mul(self, rhs: Self) -> Option<Self> {
+                Rational::checked_mul(self, rhs)
+        }
+
+        fn checked_div(self, rhs: Self) -> Option<Self> {
+                Rational::checked_div(self, rhs)
+        }
+
+        fn from_usize(n: usize) -> Option<Self> {
+                i64::try_from(n).ok().map(Rational::from_integer)
+        }
+
+        fn to_f64(self) -> f64 {
+                Rational::to_f64(self)
+        }
+
+        fn is_zero(self) -> bool {
+                self.numer() == 0
+        }
+}
+
+/// Smallest element of `items`, or `None` if it is empty.
+pub fn min_cost<N: Cost>(items: impl IntoIterator<Item = N>) -> Option<N> {
+        items.into_iter().fold(None, |acc, x| match acc {
+                Some(m) if m <= x => Some(m),
+                _ => Some(x),
+        })
+}
+
+/// Sum of `items`, or `None` if it overflows.
+pub fn sum_cost<N: Cost>(items: impl IntoIterator<Item = N>) -> Option<N> {
+        items.into_iter().try_fold(N::zero(), |acc, x| acc.checked_add(x))
+}
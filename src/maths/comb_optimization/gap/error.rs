@@ -0,0 +1,88 @@
+use crate::comb_optimization::assignment_problem::AssignmentError;
+use rocket::serde::Serialize;
+use std::error::Error;
+use std::fmt;
+
+/// Everything that can go wrong between reading a generalized assignment
+/// problem and returning its assignment of jobs to agents.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "error")]
+pub enum GapError {
+        /// The input is not valid JSON or an entry is not a number of the cost type.
+        InvalidInput { details: String },
+
+        /// `c` has no rows or no columns.
+        EmptyMatrix,
+
+        /// Row `row` of `c` has `found` entries instead of the `expected` entries of row 0.
+        RaggedRows { row: usize, expected: usize, found: usize },
+
+        /// `sizes` is not of the same shape as `c`.
+        ShapeMismatch { expected: (usize, usize), found: (usize, usize) },
+
+        /// `capacities` does not have one entry per row (agent) of `c`.
+        LengthMismatch { expected: usize, found: usize },
+
+        /// The size of job `col` for agent `row` is negative.
+        NegativeSize { row: usize, col: usize },
+
+        /// The capacity of agent `row` is negative.
+        NegativeCapacity { row: usize },
+
+        /// Job `col` is forbidden or too large for every agent on its own.
+        JobDoesNotFit { col: usize },
+
+        /// The branch-and-bound proved that no assignment respects the capacities.
+        Infeasible,
+
+        /// None of the heuristics found an assignment that respects the
+        /// capacities; the exact method may still find one.
+        NoSolutionFound,
+
+        /// A computation left the range of the cost type.
+        Overflow { operation: &'static str },
+}
+
+impl fmt::Display for GapError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                        GapError::InvalidInput { details } => write!(f, "invalid input: {}", details),
+                        GapError::EmptyMatrix => write!(f, "the cost matrix is empty"),
+                        GapError::RaggedRows { row, expected, found } => write!(f, "row {} of the cost matrix has {} entries, expected {}", row, found, expected),
+                        GapError::ShapeMismatch { expected, found } => write!(f, "sizes is a {}x{} matrix, expected {}x{}", found.0, found.1, expected.0, expected.1),
+                        GapError::LengthMismatch { expected, found } => write!(f, "capacities has {} entries, expected {}", found, expected),
+                        GapError::NegativeSize { row, col } => write!(f, "the size of job {} for agent {} is negative", col, row),
+                        GapError::NegativeCapacity { row } => write!(f, "the capacity of agent {} is negative", row),
+                        GapError::JobDoesNotFit { col } => write!(f, "job {} does not fit any agent", col),
+                        GapError::Infeasible => write!(f, "no assignment respects the capacities"),
+                        GapError::NoSolutionFound => write!(f, "the heuristics found no assignment that respects the capacities"),
+                        GapError::Overflow { operation } => write!(f, "{} overflowed", operation),
+                }
+        }
+}
+
+impl Error for GapError {}
+
+impl From<serde_json::Error> for GapError {
+        fn from(err: serde_json::Error) -> Self {
+                GapError::InvalidInput { details: err.to_string() }
+        }
+}
+
+// The input checks shared with the assignment problem.
+impl From<AssignmentError> for GapError {
+        fn from(err: AssignmentError) -> Self {
+                match err {
+                        AssignmentError::InvalidInput { details } => GapError::InvalidInput { details },
+                        AssignmentError::EmptyMatrix => GapError::EmptyMatrix,
+                        AssignmentError::RaggedRows { row, expected, found } => GapError::RaggedRows { row, expected, found },
+                        AssignmentError::Overflow { operation } => GapError::Overflow { operation },
+                        err => GapError::InvalidInput { details: err.to_string() },
+                }
+        }
+}
+
+/// `Some` result of a checked operation, or a [`GapError::Overflow`] naming it.
+pub(crate) fn checked<N>(result: Option<N>, operation: &'static str) -> Result<N, GapError> {
+        result.ok_or(GapError::Overflow { operation })
+}
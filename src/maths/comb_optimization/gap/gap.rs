@@ -0,0 +1,452 @@
+use crate::comb_optimization::assignment_problem::Objective;
+use crate::comb_optimization::cost::{sum_cost, Cost};
+use crate::comb_optimization::gap::error::{checked, GapError};
+use crate::comb_optimization::gap::heuristic::{give_back, improve, regret_greedy, take};
+use nalgebra::DMatrix;
+use rocket::serde::{Deserialize, Serialize};
+
+// Largest number of jobs that `GapMethod::Auto` solves exactly.
+const MAX_EXACT_JOBS: usize = 20;
+// Branch-and-bound nodes after which the search stops with its incumbent.
+const MAX_NODES: usize = 1_000_000;
+// Largest magnitude up to which f64 holds every integer exactly, 2^53.
+const EXACT_F64: f64 = 9_007_199_254_740_992.0;
+// Subgradient iterations for the Lagrangian multipliers at the root.
+const SUBGRADIENT_ITERATIONS: usize = 200;
+// Iterations without a better bound before the step is halved.
+const STALL_ITERATIONS: usize = 10;
+
+/// How a generalized assignment problem is solved.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GapMethod {
+        /// Branch-and-bound up to 20 jobs, the heuristics above.
+        #[default]
+        Auto,
+        /// Branch-and-bound, started from the best heuristic solution. The
+        /// search grows exponentially with the number of jobs; it stops after
+        /// a million nodes with the best assignment found so far.
+        Exact,
+        /// The best heuristic solution only.
+        Heuristic,
+}
+
+/// An assignment of every job to an agent within the capacities.
+#[derive(Debug, Clone, Serialize)]
+pub struct GapSolution<N: Cost> {
+        /// The (agent, job), i.e. (row, column), pair of every job, ordered by
+        /// agent and job.
+        pub assignment: Vec<(usize, usize)>,
+        pub cost: N,
+        /// Total size of the jobs of each agent, at most its capacity.
+        pub loads: Vec<N>,
+        /// Cost of the best heuristic solution, the first incumbent of the
+        /// branch-and-bound; `None` if no heuristic found one.
+        pub heuristic_cost: Option<N>,
+        /// Lagrangian bound at the root: no assignment costs less (more, for a
+        /// maximisation problem).
+        pub bound: f64,
+        /// Whether `cost` is proven optimal: after a branch-and-bound that ran
+        /// to the end, and for a heuristic solution that meets `bound` while
+        /// the costs of an assignment stay below 2^53 in magnitude.
+        pub optimal: bool,
+        /// Number of branch-and-bound nodes visited.
+        pub nodes: usize,
+}
+
+/// A problem as the heuristics and the branch-and-bound see it: the costs as
+/// `f64`, negated for a maximisation problem, and the sizes and capacities of
+/// the cost type, so that capacities are checked exactly.
+pub(super) struct Instance<'a, N: Cost> {
+        pub(super) agents: usize,
+        pub(super) jobs: usize,
+        // `None` on forbidden cells.
+        pub(super) cost: DMatrix<Option<f64>>,
+        pub(super) sizes: &'a DMatrix<N>,
+        pub(super) capacities: &'a [N],
+}
+
+impl<N: Cost> Instance<'_, N> {
+        /// Whether `job` may go to `agent` with the `remaining` capacities.
+        pub(super) fn fits(&self, agent: usize, job: usize, remaining: &[N]) -> bool {
+                self.cost[(agent, job)].is_some() && self.sizes[(agent, job)] <= remaining[agent]
+        }
+
+        // The total cost of a complete assignment.
+        fn value(&self, agent_of: &[usize]) -> f64 {
+                agent_of.iter().enumerate().map(|(job, &agent)| self.cost[(agent, job)].unwrap_or(f64::INFINITY)).sum()
+        }
+
+        // `f(agent, job)` on every cell.
+        fn matrix(&self, f: impl Fn(usize, usize) -> f64) -> DMatrix<f64> {
+                DMatrix::from_fn(self.agents, self.jobs, f)
+        }
+}
+
+/// Solves the generalized assignment problem: every column (job) of `c` goes
+/// to exactly one row (agent), at the cost `c_i,j`, and uses `sizes_i,j` of
+/// the capacity `capacities[i]` of its agent. A `None` cost forbids the
+/// cell.
+///
+/// Heuristics come first: Martello and Toth's regret heuristic with the
+/// costs, the sizes, the sizes relative to the capacities and the Lagrangian
+/// costs as desirabilities, each followed by a local search of shifts and
+/// swaps. The bound relaxes the capacities with multipliers λ >= 0,
+///
+/// L(λ) = ∑_j min_i (c_i,j + λ_i sizes_i,j) - ∑_i λ_i capacities_i,
+///
+/// which with λ = 0 gives every job its cheapest agent that it fits on its own
+/// (the assignment relaxation). λ is improved by subgradient steps at the
+/// root. With [`GapMethod::Exact`], or [`GapMethod::Auto`] for up to 20
+/// jobs, a depth-first branch-and-bound then fixes the jobs, those with the
+/// largest regret first, and prunes a node when its bound, the larger of both
+/// relaxations over the remaining jobs and capacities, cannot beat the
+/// incumbent. After a million nodes it stops with the incumbent, which is
+/// then not proven optimal.
+///
+/// Bounds and pruning use `f64`, while complete assignments are compared by
+/// their exact cost. Once the costs of an assignment can reach 2^53 in
+/// magnitude, as with large `i64` or `u64` costs, f64 can no longer tell
+/// close costs apart: only nodes whose bound exceeds the incumbent by more
+/// than rounding errors are pruned, so that the exact comparison decides
+/// between the others, and a heuristic solution is not proven optimal by
+/// the rounded root bound.
+///
+/// Fails if the input is inconsistent, if a job fits no agent, with
+/// [`GapError::Infeasible`] if the search proves that no assignment exists,
+/// or with [`GapError::NoSolutionFound`] if none was found otherwise.
+pub fn solve_gap<N: Cost>(c: &DMatrix<Option<N>>, sizes: &DMatrix<N>, capacities: &[N], objective: Objective, method: GapMethod) -> Result<GapSolution<N>, GapError> {
+        check_input(c, sizes, capacities)?;
+        let (agents, jobs) = c.shape();
+        let sign = match objective {
+                Objective::Minimize => 1.0,
+                Objective::Maximize => -1.0,
+        };
+        let instance = Instance {
+                agents,
+                jobs,
+                cost: c.map(|c_ij| c_ij.map(|c_ij| sign * c_ij.to_f64())),
+                sizes,
+                capacities,
+        };
+        if let Some(col) = (0..jobs).find(|&job| !(0..agents).any(|agent| instance.fits(agent, job, capacities))) {
+                return Err(GapError::JobDoesNotFit { col });
+        }
+
+        let mut incumbent = None;
+        let capacity = |agent: usize| capacities[agent].to_f64().max(f64::MIN_POSITIVE);
+        let desirabilities = [
+                instance.matrix(|agent, job| instance.cost[(agent, job)].unwrap_or(f64::INFINITY)),
+                instance.matrix(|agent, job| sizes[(agent, job)].to_f64()),
+                instance.matrix(|agent, job| sizes[(agent, job)].to_f64() / capacity(agent)),
+        ];
+        for desirability in &desirabilities {
+                run_heuristic(&instance, desirability, &mut incumbent);
+        }
+        let (lambda, bound) = lagrangian_bound(&instance, incumbent.as_ref().map(|h: &(Vec<usize>, f64)| h.1));
+        let lagrangian_costs = instance.matrix(|agent, job| lagrangian_cost(&instance, &lambda, agent, job));
+        run_heuristic(&instance, &lagrangian_costs, &mut incumbent);
+        let incumbent = match incumbent {
+                Some((agent_of, value)) => {
+                        let cost = exact_cost(c, &agent_of)?;
+                        Some((agent_of, value, cost))
+                }
+                None => None,
+        };
+        let heuristic_cost = incumbent.as_ref().map(|h| h.2);
+
+        // Whether f64 holds the cost of every assignment and the bounds can be trusted.
+        let largest: f64 = (0..jobs).map(|job| (0..agents).filter_map(|agent| instance.cost[(agent, job)]).fold(0.0, |m: f64, c_ij| m.max(c_ij.abs()))).sum();
+        let exact_in_f64 = largest < EXACT_F64;
+        let integral = instance.cost.iter().flatten().all(|c_ij| c_ij.fract() == 0.0);
+        let exact = match method {
+                GapMethod::Auto => jobs <= MAX_EXACT_JOBS,
+                GapMethod::Exact => true,
+                GapMethod::Heuristic => false,
+        };
+        let (best, complete, nodes) = if exact {
+                let mut search = Search {
+                        instance: &instance,
+                        c,
+                        objective,
+                        order: branching_order(&instance, &lagrangian_costs),
+                        lambda,
+                        integral,
+                        exact_in_f64,
+                        agent_of: vec![0; jobs],
+                        remaining: capacities.to_vec(),
+                        best: incumbent,
+                        nodes: 0,
+                        complete: true,
+                };
+                search.branch(0, 0.0, N::zero())?;
+                (search.best, search.complete, search.nodes)
+        } else {
+                let complete = exact_in_f64 && incumbent.as_ref().is_some_and(|h| prunes(bound, h.1, integral));
+                (incumbent, complete, 0)
+        };
+        let agent_of = match best {
+                Some((agent_of, _, _)) => agent_of,
+                None if exact && complete => return Err(GapError::Infeasible),
+                None => return Err(GapError::NoSolutionFound),
+        };
+        let optimal = complete;
+
+        let mut assignment: Vec<(usize, usize)> = agent_of.iter().enumerate().map(|(job, &agent)| (agent, job)).collect();
+        assignment.sort_unstable();
+        let mut loads = Vec::with_capacity(agents);
+        for agent in 0..agents {
+                let load = sum_cost(assignment.iter().filter(|&&(i, _)| i == agent).map(|&(i, j)| sizes[(i, j)]));
+                loads.push(checked(load, "load of an agent")?);
+        }
+        Ok(GapSolution {
+                cost: exact_cost(c, &agent_of)?,
+                assignment,
+                loads,
+                heuristic_cost,
+                bound: sign * bound,
+                optimal,
+                nodes,
+        })
+}
+
+// Rejects an empty `c`, sizes or capacities that do not fit its shape and
+// negative sizes or capacities.
+fn check_input<N: Cost>(c: &DMatrix<Option<N>>, sizes: &DMatrix<N>, capacities: &[N]) -> Result<(), GapError> {
+        let (nrows, ncols) = c.shape();
+        if nrows == 0 || ncols == 0 {
+                return Err(GapError::EmptyMatrix);
+        }
+        if sizes.shape() != c.shape() {
+                return Err(GapError::ShapeMismatch {
+                        expected: c.shape(),
+                        found: sizes.shape(),
+                });
+        }
+        if capacities.len() != nrows {
+                return Err(GapError::LengthMismatch {
+                        expected: nrows,
+                        found: capacities.len(),
+                });
+        }
+        for row in 0..nrows {
+                if let Some(col) = (0..ncols).find(|&col| sizes[(row, col)] < N::zero()) {
+                        return Err(GapError::NegativeSize { row, col });
+                }
+        }
+        if let Some(row) = capacities.iter().position(|&capacity| capacity < N::zero()) {
+                return Err(GapError::NegativeCapacity { row });
+        }
+        Ok(())
+}
+
+// Runs the regret heuristic and the local search, and keeps the result if it
+// beats `incumbent`.
+fn run_heuristic<N: Cost>(instance: &Instance<N>, desirability: &DMatrix<f64>, incumbent: &mut Option<(Vec<usize>, f64)>) {
+        if let Some(mut agent_of) = regret_greedy(instance, desirability) {
+                improve(instance, &mut agent_of);
+                let value = instance.value(&agent_of);
+                if incumbent.as_ref().is_none_or(|(_, best)| improves(value, *best)) {
+                        *incumbent = Some((agent_of, value));
+                }
+        }
+}
+
+// c_i,j + λ_i sizes_i,j, infinite on a forbidden cell.
+fn lagrangian_cost<N: Cost>(instance: &Instance<N>, lambda: &[f64], agent: usize, job: usize) -> f64 {
+        instance.cost[(agent, job)].map_or(f64::INFINITY, |c_ij| c_ij + lambda[agent] * instance.sizes[(agent, job)].to_f64())
+}
+
+// The multipliers with the best bound L(λ) found by subgradient steps
+// towards `upper`, the cost of an assignment (the sum of the largest costs of
+// the jobs without one), and that bound.
+fn lagrangian_bound<N: Cost>(instance: &Instance<N>, upper: Option<f64>) -> (Vec<f64>, f64) {
+        let upper = upper.unwrap_or_else(|| (0..instance.jobs).map(|job| (0..instance.agents).filter_map(|agent| instance.cost[(agent, job)]).fold(f64::NEG_INFINITY, f64::max)).sum());
+        let mut lambda = vec![0.0; instance.agents];
+        let mut best = (lambda.clone(), f64::NEG_INFINITY);
+        let mut step = 2.0;
+        let mut stall = 0;
+        for _ in 0..SUBGRADIENT_ITERATIONS {
+                // Every job goes to the agent with the smallest Lagrangian cost.
+                let mut value: f64 = -(0..instance.agents).map(|agent| lambda[agent] * instance.capacities[agent].to_f64()).sum::<f64>();
+                let mut loads = vec![0.0; instance.agents];
+                for job in 0..instance.jobs {
+                        let cheapest = (0..instance.agents)
+                                .filter(|&agent| instance.fits(agent, job, instance.capacities))
+                                .map(|agent| (agent, lagrangian_cost(instance, &lambda, agent, job)))
+                                .min_by(|a, b| a.1.total_cmp(&b.1));
+                        if let Some((agent, cost)) = cheapest {
+                                value += cost;
+                                loads[agent] += instance.sizes[(agent, job)].to_f64();
+                        }
+                }
+                if value > best.1 {
+                        best = (lambda.clone(), value);
+                        stall = 0;
+                } else {
+                        stall += 1;
+                        if stall == STALL_ITERATIONS {
+                                step /= 2.0;
+                                stall = 0;
+                        }
+                }
+
+                // Subgradient: the overload of every agent, projected on λ >= 0.
+                let gradient: Vec<f64> = (0..instance.agents)
+                        .map(|agent| {
+                                let g = loads[agent] - instance.capacities[agent].to_f64();
+                                if lambda[agent] == 0.0 && g < 0.0 {
+                                        0.0
+                                } else {
+                                        g
+                                }
+                        })
+                        .collect();
+                let norm: f64 = gradient.iter().map(|g| g * g).sum();
+                if norm == 0.0 || !improves(value, upper) {
+                        break;
+                }
+                let t = step * (upper - value) / norm;
+                for (l, g) in lambda.iter_mut().zip(&gradient) {
+                        *l = (*l + t * g).max(0.0);
+                }
+        }
+        best
+}
+
+// The jobs by decreasing regret of the Lagrangian costs, over the agents
+// each one fits on its own.
+fn branching_order<N: Cost>(instance: &Instance<N>, lagrangian_costs: &DMatrix<f64>) -> Vec<usize> {
+        let regret = |job: usize| {
+                let mut costs: Vec<f64> = (0..instance.agents).filter(|&agent| instance.fits(agent, job, instance.capacities)).map(|agent| lagrangian_costs[(agent, job)]).collect();
+                costs.sort_by(f64::total_cmp);
+                if costs.len() < 2 {
+                        f64::INFINITY
+                } else {
+                        costs[1] - costs[0]
+                }
+        };
+        let mut order: Vec<(usize, f64)> = (0..instance.jobs).map(|job| (job, regret(job))).collect();
+        order.sort_by(|a, b| b.1.total_cmp(&a.1));
+        order.into_iter().map(|(job, _)| job).collect()
+}
+
+// Depth-first branch-and-bound over the agent of each job in `order`.
+struct Search<'a, 'b, N: Cost> {
+        instance: &'a Instance<'b, N>,
+        c: &'a DMatrix<Option<N>>,
+        objective: Objective,
+        order: Vec<usize>,
+        lambda: Vec<f64>,
+        // Whether every cost is an integer, so that bounds can be rounded up.
+        integral: bool,
+        // Whether f64 holds the cost of every assignment exactly.
+        exact_in_f64: bool,
+        agent_of: Vec<usize>,
+        remaining: Vec<N>,
+        // The incumbent with its cost as the search sees it and its exact cost.
+        best: Option<(Vec<usize>, f64, N)>,
+        nodes: usize,
+        // Whether the search ran to the end, within `MAX_NODES`.
+        complete: bool,
+}
+
+impl<N: Cost> Search<'_, '_, N> {
+        // Extends the assignment of the first `depth` jobs of `order`, which
+        // costs `fixed`, exactly `fixed_exact`.
+        fn branch(&mut self, depth: usize, fixed: f64, fixed_exact: N) -> Result<(), GapError> {
+                if self.nodes == MAX_NODES {
+                        self.complete = false;
+                        return Ok(());
+                }
+                self.nodes += 1;
+                if depth == self.instance.jobs {
+                        let better = match &self.best {
+                                None => true,
+                                Some((_, _, best)) => match self.objective {
+                                        Objective::Minimize => fixed_exact < *best,
+                                        Objective::Maximize => fixed_exact > *best,
+                                },
+                        };
+                        if better {
+                                self.best = Some((self.agent_of.clone(), fixed, fixed_exact));
+                        }
+                        return Ok(());
+                }
+                let bound = match self.bound(depth, fixed) {
+                        Some(bound) => bound,
+                        None => return Ok(()),
+                };
+                if self.best.as_ref().is_some_and(|(_, best, _)| self.prunes(bound, *best)) {
+                        return Ok(());
+                }
+
+                // Cheapest agents first, by Lagrangian cost.
+                let job = self.order[depth];
+                let mut agents: Vec<(usize, f64)> = (0..self.instance.agents)
+                        .filter(|&agent| self.instance.fits(agent, job, &self.remaining))
+                        .map(|agent| (agent, lagrangian_cost(self.instance, &self.lambda, agent, job)))
+                        .collect();
+                agents.sort_by(|a, b| a.1.total_cmp(&b.1));
+                for (agent, _) in agents {
+                        let size = self.instance.sizes[(agent, job)];
+                        self.remaining[agent] = take(self.remaining[agent], size);
+                        self.agent_of[job] = agent;
+                        let cost = self.instance.cost[(agent, job)].unwrap_or(f64::INFINITY);
+                        let exact = checked(fixed_exact.checked_add(self.c[(agent, job)].unwrap_or_else(N::zero)), "assignment cost")?;
+                        self.branch(depth + 1, fixed + cost, exact)?;
+                        self.remaining[agent] = give_back(self.remaining[agent], size);
+                }
+                Ok(())
+        }
+
+        // Whether `bound` rules out a better assignment than `incumbent`. Beyond
+        // the exact range of f64 only a bound clearly above the incumbent does.
+        fn prunes(&self, bound: f64, incumbent: f64) -> bool {
+                if self.exact_in_f64 {
+                        prunes(bound, incumbent, self.integral)
+                } else {
+                        improves(incumbent, bound)
+                }
+        }
+
+        // Lower bound on the cost of every completion, the larger of the
+        // assignment relaxation and L(λ) over the remaining jobs and
+        // capacities, or `None` if some remaining job no longer fits.
+        fn bound(&self, depth: usize, fixed: f64) -> Option<f64> {
+                let mut relaxation = fixed;
+                let mut lagrangian = fixed - (0..self.instance.agents).map(|agent| self.lambda[agent] * self.remaining[agent].to_f64()).sum::<f64>();
+                for &job in &self.order[depth..] {
+                        let mut cheapest = f64::INFINITY;
+                        let mut cheapest_lagrangian = f64::INFINITY;
+                        for agent in (0..self.instance.agents).filter(|&agent| self.instance.fits(agent, job, &self.remaining)) {
+                                cheapest = cheapest.min(self.instance.cost[(agent, job)].unwrap_or(f64::INFINITY));
+                                cheapest_lagrangian = cheapest_lagrangian.min(lagrangian_cost(self.instance, &self.lambda, agent, job));
+                        }
+                        if cheapest == f64::INFINITY {
+                                return None;
+                        }
+                        relaxation += cheapest;
+                        lagrangian += cheapest_lagrangian;
+                }
+                Some(relaxation.max(lagrangian))
+        }
+}
+
+/// Whether `value` is smaller than `other` by more than rounding errors.
+pub(super) fn improves(value: f64, other: f64) -> bool {
+        value < other - 1e-9 * (1.0 + other.abs())
+}
+
+// Whether no assignment with a cost of at least `bound` beats `incumbent`;
+// with integer costs the bound is rounded up first.
+fn prunes(bound: f64, incumbent: f64, integral: bool) -> bool {
+        let bound = if integral { (bound - 1e-6).ceil() } else { bound };
+        !improves(bound, incumbent)
+}
+
+// ∑ c_i,j over the (agent, job) pairs, summed in the cost type.
+fn exact_cost<N: Cost>(c: &DMatrix<Option<N>>, agent_of: &[usize]) -> Result<N, GapError> {
+        let costs = agent_of.iter().enumerate().map(|(job, &agent)| c[(agent, job)].unwrap_or_else(N::zero));
+        checked(sum_cost(costs), "assignment cost")
+}
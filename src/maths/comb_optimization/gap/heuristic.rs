@@ -0,0 +1,112 @@
+use crate::comb_optimization::cost::Cost;
+use crate::comb_optimization::gap::gap::{improves, Instance};
+use nalgebra::DMatrix;
+
+/// Martello and Toth's regret heuristic: as long as jobs are left, the job
+/// whose best agent is preferred the most over its second best, by the
+/// `desirability` of each (agent, job) cell (smaller is better), goes to its
+/// best agent. Only agents with enough capacity left count.
+///
+/// Returns the agent of every job, or `None` if some job no longer fits any
+/// agent.
+pub(super) fn regret_greedy<N: Cost>(instance: &Instance<N>, desirability: &DMatrix<f64>) -> Option<Vec<usize>> {
+        let mut remaining = instance.capacities.to_vec();
+        let mut agent_of = vec![None; instance.jobs];
+        for _ in 0..instance.jobs {
+                // (job, its best agent, regret) of the job with the largest regret.
+                let mut pick: Option<(usize, usize, f64)> = None;
+                for job in (0..instance.jobs).filter(|&job| agent_of[job].is_none()) {
+                        let mut best: Option<(usize, f64)> = None;
+                        let mut second = f64::INFINITY;
+                        for agent in (0..instance.agents).filter(|&agent| instance.fits(agent, job, &remaining)) {
+                                let f = desirability[(agent, job)];
+                                match best {
+                                        Some((_, b)) if f >= b => second = second.min(f),
+                                        _ => {
+                                                second = best.map_or(f64::INFINITY, |(_, b)| b);
+                                                best = Some((agent, f));
+                                        }
+                                }
+                        }
+                        let (agent, f) = best?;
+                        // A job with a single agent left has an infinite regret.
+                        let regret = second - f;
+                        if pick.is_none_or(|(_, _, r)| regret > r) {
+                                pick = Some((job, agent, regret));
+                        }
+                }
+                let (job, agent, _) = pick?;
+                remaining[agent] = take(remaining[agent], instance.sizes[(agent, job)]);
+                agent_of[job] = Some(agent);
+        }
+        agent_of.into_iter().collect()
+}
+
+/// Local search on a feasible assignment: moves single jobs to a cheaper agent
+/// with enough capacity left, and swaps the agents of two jobs when both still
+/// fit and the cost drops, until neither helps.
+pub(super) fn improve<N: Cost>(instance: &Instance<N>, agent_of: &mut [usize]) {
+        let mut remaining = instance.capacities.to_vec();
+        for (job, &agent) in agent_of.iter().enumerate() {
+                remaining[agent] = take(remaining[agent], instance.sizes[(agent, job)]);
+        }
+        let cost = |agent: usize, job: usize| instance.cost[(agent, job)];
+
+        loop {
+                let mut improved = false;
+                for (job, agent) in agent_of.iter_mut().enumerate() {
+                        let from = *agent;
+                        let current = cost(from, job).unwrap_or(f64::INFINITY);
+                        let target = (0..instance.agents)
+                                .filter(|&to| to != from && instance.fits(to, job, &remaining))
+                                .filter_map(|to| cost(to, job).map(|c| (to, c)))
+                                .min_by(|a, b| a.1.total_cmp(&b.1));
+                        if let Some((to, c)) = target {
+                                if improves(c, current) {
+                                        remaining[from] = give_back(remaining[from], instance.sizes[(from, job)]);
+                                        remaining[to] = take(remaining[to], instance.sizes[(to, job)]);
+                                        *agent = to;
+                                        improved = true;
+                                }
+                        }
+                }
+                for first in 0..instance.jobs {
+                        for second in first + 1..instance.jobs {
+                                let (a, b) = (agent_of[first], agent_of[second]);
+                                if a == b {
+                                        continue;
+                                }
+                                let (swapped_first, swapped_second) = match (cost(b, first), cost(a, second)) {
+                                        (Some(x), Some(y)) => (x, y),
+                                        _ => continue,
+                                };
+                                let current = cost(a, first).unwrap_or(f64::INFINITY) + cost(b, second).unwrap_or(f64::INFINITY);
+                                if !improves(swapped_first + swapped_second, current) {
+                                        continue;
+                                }
+                                let free_a = give_back(remaining[a], instance.sizes[(a, first)]);
+                                let free_b = give_back(remaining[b], instance.sizes[(b, second)]);
+                                if instance.sizes[(a, second)] <= free_a && instance.sizes[(b, first)] <= free_b {
+                                        remaining[a] = take(free_a, instance.sizes[(a, second)]);
+                                        remaining[b] = take(free_b, instance.sizes[(b, first)]);
+                                        agent_of.swap(first, second);
+                                        improved = true;
+                                }
+                        }
+                }
+                if !improved {
+                        return;
+                }
+        }
+}
+
+/// Capacity left after a job of `size` is added; `size` is at most `remaining`.
+pub(super) fn take<N: Cost>(remaining: N, size: N) -> N {
+        remaining.checked_sub(size).unwrap_or_else(N::zero)
+}
+
+/// Capacity left after a job of `size` is removed again, which never exceeds
+/// the capacity it was taken from.
+pub(super) fn give_back<N: Cost>(remaining: N, size: N) -> N {
+        remaining.checked_add(size).unwrap_or(remaining)
+}
@@ -0,0 +1,7 @@
+pub use error::GapError;
+pub use gap::{solve_gap, GapMethod, GapSolution};
+
+pub mod error;
+#[allow(clippy::module_inception)]
+pub mod gap;
+pub mod heuristic;
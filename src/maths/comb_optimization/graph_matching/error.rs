@@ -0,0 +1,47 @@
+use crate::comb_optimization::assignment_problem::AssignmentError;
+use rocket::serde::Serialize;
+use std::error::Error;
+use std::fmt;
+
+/// Everything that can go wrong between reading a weighted graph and
+/// returning its best bipartite matching.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "error")]
+pub enum GraphMatchingError {
+        /// The graph has an odd cycle, so its vertices cannot be split into two sides.
+        NotBipartite,
+
+        /// The assignment problem built from the graph failed, with `details`.
+        Assignment { details: String },
+
+        /// A computation left the range of the weight type, e.g. the penalty of
+        /// a maximum cardinality matching.
+        Overflow { operation: &'static str },
+}
+
+impl fmt::Display for GraphMatchingError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                        GraphMatchingError::NotBipartite => write!(f, "the graph is not bipartite"),
+                        GraphMatchingError::Assignment { details } => write!(f, "the assignment problem failed: {}", details),
+                        GraphMatchingError::Overflow { operation } => write!(f, "{} overflowed", operation),
+                }
+        }
+}
+
+impl Error for GraphMatchingError {}
+
+// The assignment problem that every matching is solved as.
+impl From<AssignmentError> for GraphMatchingError {
+        fn from(err: AssignmentError) -> Self {
+                match err {
+                        AssignmentError::Overflow { operation } => GraphMatchingError::Overflow { operation },
+                        err => GraphMatchingError::Assignment { details: err.to_string() },
+                }
+        }
+}
+
+/// `Some` result of a checked operation, or a [`GraphMatchingError::Overflow`] naming it.
+pub(crate) fn checked<N>(result: Option<N>, operation: &'static str) -> Result<N, GraphMatchingError> {
+        result.ok_or(GraphMatchingError::Overflow { operation })
+}
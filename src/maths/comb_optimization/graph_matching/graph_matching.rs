@@ -0,0 +1,100 @@
+use crate::comb_optimization::assignment_problem::{solve_sparse, Objective, SparseCostMatrix};
+use crate::comb_optimization::cost::{sum_cost, Cost};
+use crate::comb_optimization::graph_matching::error::{checked, GraphMatchingError};
+use crate::graph_theory::bipartite::bipartite_undirected;
+use petgraph::visit::{EdgeRef, GraphRef, IntoEdgeReferences, IntoNeighbors, IntoNodeIdentifiers, Visitable};
+use rocket::serde::Serialize;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A matching of a weighted bipartite graph, see [`weighted_bipartite_matching`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WeightedMatching<N, W: Cost> {
+        /// Matched edges (u, v, weight), u in the side `U` found by
+        /// [`bipartite_undirected`] and v in `V`, in the order of `U`.
+        pub edges: Vec<(N, N, W)>,
+        pub total_weight: W,
+}
+
+/// Maximum or minimum weight matching of a bipartite petgraph graph whose
+/// edge weights are costs, e.g. `&Graph<String, i64, Undirected>`.
+///
+/// The sides are found by [`bipartite_undirected`], so vertices of either
+/// side may stay unmatched and no perfect matching is needed. With
+/// `max_cardinality` false the matching of any size with the best total
+/// weight is returned: only positive weights are worth matching when
+/// maximising, only negative ones when minimising. With `max_cardinality`
+/// true the matching is one of maximum cardinality, the best among those.
+///
+/// The problem is solved by [`solve_sparse`] as an assignment of every vertex
+/// u of `U` either to a neighbour in `V`, at the weight of their edge, or to
+/// an "unmatched" column of its own, at 0, so that memory grows with the
+/// number of edges. For a maximum cardinality matching every real edge is
+/// made better than any unmatched column by a penalty of 2 ∑ |w| + 1. Of
+/// parallel edges the better one is used.
+///
+/// Fails with [`GraphMatchingError::NotBipartite`] if the graph is not
+/// bipartite, and with [`GraphMatchingError::Overflow`] if the penalty does
+/// not fit into the weight type.
+pub fn weighted_bipartite_matching<G, N, W>(g: G, objective: Objective, max_cardinality: bool) -> Result<WeightedMatching<N, W>, GraphMatchingError>
+where
+        G: GraphRef + Visitable<NodeId = N> + IntoNeighbors<NodeId = N> + IntoNodeIdentifiers<NodeId = N> + IntoEdgeReferences<NodeId = N, EdgeWeight = W>,
+        N: Copy + Eq + Hash + std::fmt::Debug,
+        W: Cost,
+{
+        let sides = bipartite_undirected(g).ok_or(GraphMatchingError::NotBipartite)?;
+        let row_of: HashMap<N, usize> = sides.nodes_u.iter().enumerate().map(|(i, &u)| (u, i)).collect();
+        let col_of: HashMap<N, usize> = sides.nodes_v.iter().enumerate().map(|(j, &v)| (v, j)).collect();
+        let (nrows, ncols) = (sides.nodes_u.len(), sides.nodes_v.len());
+
+        let is_better = |a: W, b: W| match objective {
+                Objective::Minimize => a < b,
+                Objective::Maximize => a > b,
+        };
+        let mut weights: HashMap<(usize, usize), W> = HashMap::new();
+        for edge in g.edge_references() {
+                let cell = match (row_of.get(&edge.source()), col_of.get(&edge.target())) {
+                        (Some(&i), Some(&j)) => (i, j),
+                        _ => match (row_of.get(&edge.target()), col_of.get(&edge.source())) {
+                                (Some(&i), Some(&j)) => (i, j),
+                                _ => continue,
+                        },
+                };
+                let w = *edge.weight();
+                if weights.get(&cell).is_none_or(|&best| is_better(w, best)) {
+                        weights.insert(cell, w);
+                }
+        }
+        if weights.is_empty() {
+                return Ok(WeightedMatching { edges: Vec::new(), total_weight: W::zero() });
+        }
+
+        // Each row has its own "unmatched" column after the real ones.
+        let (real, unmatched) = match max_cardinality {
+                false => (None, W::zero()),
+                true => {
+                        let magnitudes = weights.values().map(|&w| if w < W::zero() { W::zero().checked_sub(w) } else { Some(w) });
+                        let total = checked(magnitudes.collect::<Option<Vec<W>>>().and_then(sum_cost), "∑ |w|")?;
+                        let penalty = checked(total.checked_add(total).and_then(|p| p.checked_add(W::from_usize(1)?)), "matching penalty")?;
+                        match objective {
+                                Objective::Minimize => (None, penalty),
+                                Objective::Maximize => (Some(penalty), W::zero()),
+                        }
+                }
+        };
+        let mut edges: Vec<(usize, usize, W)> = Vec::with_capacity(weights.len() + nrows);
+        for (&(i, j), &w) in &weights {
+                let cost = match real {
+                        Some(penalty) => checked(w.checked_add(penalty), "w + penalty")?,
+                        None => w,
+                };
+                edges.push((i, j, cost));
+        }
+        edges.extend((0..nrows).map(|i| (i, ncols + i, unmatched)));
+        let c = SparseCostMatrix::from_edges(nrows, ncols + nrows, &edges)?;
+
+        let solution = solve_sparse(&c, objective)?;
+        let edges: Vec<(N, N, W)> = solution.assignment.iter().filter(|&&(_, j)| j < ncols).flat_map(|&(i, j)| weights.get(&(i, j)).map(|&w| (sides.nodes_u[i], sides.nodes_v[j], w))).collect();
+        let total_weight = checked(sum_cost(edges.iter().map(|&(_, _, w)| w)), "matching weight")?;
+        Ok(WeightedMatching { edges, total_weight })
+}
@@ -0,0 +1,6 @@
+pub use error::GraphMatchingError;
+pub use graph_matching::{weighted_bipartite_matching, WeightedMatching};
+
+pub mod error;
+#[allow(clippy::module_inception)]
+pub mod graph_matching;
@@ -1,92 +1,144 @@
 extern crate petgraph;
 
 use crate::graph_theory::bipartite::BipartiteGraph;
-use petgraph::data::DataMap;
 use petgraph::graph::IndexType;
-use petgraph::graph::*;
-use petgraph::visit::{Dfs, GraphRef, IntoNeighbors, IntoNodeIdentifiers, NodeCount, NodeIndexable, VisitMap, Visitable};
-use petgraph::*;
-use rocket::{
-        self,
-        serde::{json::Json, Deserialize, Serialize},
-};
+use petgraph::visit::{GraphRef, IntoNeighbors, IntoNodeIdentifiers, NodeCount, NodeIndexable, VisitMap, Visitable};
+use rocket::{self, serde::Serialize};
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::iter::Iterator;
 
-use rocket::response::stream::Event;
-use serde_json::{Map, Value};
+/// Minimum cover, maximum matching and the steps taken, returned by
+/// [`hungarian_maximum_matching`].
+pub type HungarianOutput<N> = (Vec<N>, HashSet<(N, N)>, Vec<HungarianStep<N>>);
 
-pub fn hungarian_maximum_matching<G: DataMap, N, VM>(g: G, mut node_index_weight_map: HashMap<usize, String>, matched_edges: HashSet<(N, N)>, g_vertex_u_v_sets: &BipartiteGraph<N>) -> Option<(Vec<N>, HashSet<(N, N)>)>
+/// A vertex named in a [`HungarianStep`], with its label in the graph.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StepVertex<N> {
+        pub node: N,
+        pub label: String,
+}
+
+/// Colour given to a vertex while the Hungarian tree grows: blue for the
+/// vertices of S in the tree, red for those of T.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Color {
+        Blue,
+        Red,
+}
+
+/// One step of [`hungarian_maximum_matching`]. Matching edges are written
+/// (s, t) with s in S and t in T.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "step")]
+pub enum HungarianStep<N> {
+        /// The given matching; all vertices are uncolored and unscanned.
+        Start { matching: Vec<(StepVertex<N>, StepVertex<N>)> },
+        /// An uncolored exposed vertex of S, the root of a new tree.
+        ExposedVertex { vertex: StepVertex<N> },
+        Colored { vertex: StepVertex<N>, color: Color },
+        /// The tree edge from `parent` to `child`, an edge of the matching when
+        /// it leads from T back to S.
+        TreeGrown {
+                parent: StepVertex<N>,
+                child: StepVertex<N>,
+                matched: bool,
+        },
+        /// The path from the root to an exposed vertex of T, alternating between
+        /// edges outside and inside the matching.
+        AugmentingPath { path: Vec<StepVertex<N>> },
+        /// The matching after taking its symmetric difference with the path; all
+        /// colours are removed again.
+        Augmented { matching: Vec<(StepVertex<N>, StepVertex<N>)> },
+        /// No uncolored exposed vertex is left: the uncolored vertices of S and the
+        /// red vertices of T cover every edge, and as many as the matching has.
+        Cover { cover: Vec<StepVertex<N>> },
+}
+
+impl<N: Copy> HungarianStep<N> {
+        /// The same step with every node mapped by `f`. Vertices that `f` maps
+        /// to `None` are left out of the lists and pairs they belong to, and a
+        /// step about such a vertex is dropped.
+        pub fn filter_map_nodes<M>(&self, f: impl Fn(N) -> Option<M>) -> Option<HungarianStep<M>> {
+                let vertex = |v: &StepVertex<N>| f(v.node).map(|node| StepVertex { node, label: v.label.clone() });
+                let pairs = |m: &[(StepVertex<N>, StepVertex<N>)]| m.iter().filter_map(|(s, t)| Some((vertex(s)?, vertex(t)?))).collect();
+                Some(match self {
+                        HungarianStep::Start { matching } => HungarianStep::Start { matching: pairs(matching) },
+                        HungarianStep::ExposedVertex { vertex: v } => HungarianStep::ExposedVertex { vertex: vertex(v)? },
+                        HungarianStep::Colored { vertex: v, color } => HungarianStep::Colored { vertex: vertex(v)?, color: *color },
+                        HungarianStep::TreeGrown { parent, child, matched } => HungarianStep::TreeGrown {
+                                parent: vertex(parent)?,
+                                child: vertex(child)?,
+                                matched: *matched,
+                        },
+                        HungarianStep::AugmentingPath { path } => HungarianStep::AugmentingPath { path: path.iter().filter_map(vertex).collect() },
+                        HungarianStep::Augmented { matching } => HungarianStep::Augmented { matching: pairs(matching) },
+                        HungarianStep::Cover { cover } => HungarianStep::Cover { cover: cover.iter().filter_map(vertex).collect() },
+                })
+        }
+}
+
+/// Hungarian method for a maximum matching and a minimum cover of a
+/// bipartite graph G = (S, T, E), starting from the matching `matched_edges`.
+///
+/// Trees are grown from the uncolored exposed vertices of S, colouring the
+/// vertices of S blue and those of T red, until an exposed vertex of T gives
+/// an augmenting path or no uncolored exposed vertex of S is left. Every step
+/// is returned as a [`HungarianStep`], its vertices labelled by `label`.
+///
+/// The node weights of `g` can be of any type; `label` turns a node into the
+/// text shown in the steps, e.g. `|node| g[node].to_string()`.
+///
+/// Edges of `matched_edges` may be given either way round. Each vertex of T
+/// joins a tree at most once, and the tree keeps the parent of every vertex,
+/// so an augmenting path is read off by following the parents from the
+/// exposed vertex of T back to the root. After each augmentation all colours
+/// are removed and the search starts again from the new matching.
+pub fn hungarian_maximum_matching<G, N, VM>(g: G, label: impl Fn(N) -> String, matched_edges: HashSet<(N, N)>, g_vertex_u_v_sets: &BipartiteGraph<N>) -> Option<HungarianOutput<N>>
 where
-        G: GraphRef + Visitable<NodeId = N, Map = VM> + IntoNeighbors<NodeId = N> + IntoNodeIdentifiers<NodeId = N> + NodeCount + NodeIndexable + Serialize + std::fmt::Debug,
-        N: Default + IndexType + Copy + Clone + PartialEq + Eq + std::hash::Hash + Serialize + std::fmt::Debug,
+        G: GraphRef + Visitable<NodeId = N, Map = VM> + IntoNeighbors<NodeId = N> + IntoNodeIdentifiers<NodeId = N> + NodeCount + NodeIndexable,
+        N: Default + IndexType + Copy + Clone + PartialEq + Eq + std::hash::Hash + std::fmt::Debug,
         VM: VisitMap<N>,
 {
         let nodes_u: HashSet<N> = g_vertex_u_v_sets.nodes_u.iter().copied().collect();
-        // println!("nodes_u = {:?}", nodes_u);
         let nodes_v: HashSet<N> = g_vertex_u_v_sets.nodes_v.iter().copied().collect();
 
-        let mut hungarian_states = Map::new();
+        let vertex = |node: N| StepVertex { node, label: label(node) };
+        let matching_vertices = |m: &HashSet<(N, N)>| {
+                let mut edges: Vec<(N, N)> = m.iter().copied().collect();
+                edges.sort_by_key(|&(s, t)| (s.index(), t.index()));
+                edges.into_iter().map(|(s, t)| (vertex(s), vertex(t))).collect()
+        };
+        let mut steps = Vec::new();
 
         // Thm: Given a bipartite graph G = (S, T, E), the Hungarian algorithm returns a
         // matching M and a cover K with |M| = |K| in time O(n^3)
-        // Set min cover size to zero to start with
-        // let mut min_cover_size = 0;
-
-        // // set maximum matching size to the size of the given matching
-        // let mut max_matching_size = m.len();
 
         // Function return min_cover and max_matching
         let mut min_cover: Vec<N> = Vec::new();
         let mut max_matching: HashSet<(N, N)> = HashSet::new();
         let mut covered_nodes: HashSet<N> = HashSet::new();
 
+        // Edges of the matching are kept as (u, v) with u in U and v in V.
         for edge in &matched_edges {
-                // println!("m edge = {:?}", edge);
-                max_matching.insert(*edge);
+                if nodes_v.contains(&edge.0) {
+                        max_matching.insert((edge.1, edge.0));
+                } else {
+                        max_matching.insert(*edge);
+                }
         }
 
-        // println!("covered_nodes = {:?}", covered_nodes);
-
         let mut red = g.visit_map();
         let mut blue = g.visit_map();
         let mut scanned = g.visit_map();
         let mut exposed_nodes_u = g.visit_map();
         let mut exposed_nodes_v = g.visit_map();
 
-        /*
-         * Below block is to describe the solution step
-         */
-        let mut start_state = Map::new();
-        start_state.insert(
-                "input_graph".to_string(),
-                Value::String(serde_json::to_string(&g).unwrap()),
-        );
-        let matching_str = max_matching.iter().cloned().collect::<Vec<(N, N)>>();
-        start_state.insert(
-                "matching".to_string(),
-                Value::String(serde_json::to_string(&matching_str).unwrap()),
-        );
-        start_state.insert("blue".to_string(), Value::Array(vec![]));
-        start_state.insert("red".to_string(), Value::Array(vec![]));
-        start_state.insert("scanned".to_string(), Value::Array(vec![]));
-        let description = "Algorithm starts with the given bipartite input_graph `g` and a matching `m`. All the vertices are uncolored \
-        and unscanned (blue, red and scanned are empty). Go to Step `Exposed Vertex`.";
-        start_state.insert(
-                "description".to_string(),
-                Value::String(description.to_string()),
-        );
-        hungarian_states.insert("start".to_string(), Value::Object(start_state));
-        /*
-         * Above block is to describe the solution step
-         */
-
-        // println!("hungarian_states = {:?}", hungarian_states);
+        steps.push(HungarianStep::Start { matching: matching_vertices(&max_matching) });
 
         'hungarian: loop {
                 for edge in max_matching.iter() {
-                        // println!("m edge = {:?}", edge);
                         let node1 = edge.0;
                         let node2 = edge.1;
                         covered_nodes.insert(node1);
@@ -94,337 +146,121 @@ where
                 }
 
                 for node in &nodes_u {
-                        if !covered_nodes.contains(&node) {
+                        if !covered_nodes.contains(node) {
                                 exposed_nodes_u.visit(*node);
                         }
                 }
 
                 for node in &nodes_v {
-                        if !covered_nodes.contains(&node) {
+                        if !covered_nodes.contains(node) {
                                 exposed_nodes_v.visit(*node);
                         }
                 }
 
                 let mut node_ids = g.node_identifiers().peekable();
-                // println!("graph = {:?}", &g);
-
-                // let mut exposed_vertex_state_counter = 1;
 
                 'exposed_vertex: while let Some(node_id) = node_ids.next() {
-                        // println!("node_id = {:?} {:?}", node_id, node_index_weight_map.get(&node_id.index()).unwrap());
                         let is_exposed_u = exposed_nodes_u.is_visited(&node_id);
                         let mut is_blue = blue.is_visited(&node_id);
 
-                        // /*
-                        // * Below block is to describe the solution step
-                        // */
-                        // let mut state_exposed_vertex = Map::new();
-
-                        // state_exposed_vertex.insert(format!("Step {}", exposed_vertex_state_counter), Value::String(format!("Check if there is an uncolored exposed vertex in S")));
-                        // exposed_vertex_state_counter += 1;
-                        // /*
-                        //  * Above block is to describe the solution step
-                        //  */
                         match (is_exposed_u, is_blue) {
                                 (true, false) => {
-                                        let node_root_weight = node_index_weight_map.get(&node_id.index()).unwrap();
-                                        // /*
-                                        // * Below block is to describe the solution step
-                                        // */
-                                        // state_exposed_vertex.insert(format!("Step {}", exposed_vertex_state_counter), Value::String(format!("Node `{}` in S is uncolored and exposed. Color `{}` as blue",
-                                        //         node_root_weight, node_root_weight)));
-                                        // exposed_vertex_state_counter += 1;
-                                        // /*
-                                        //  * Above block is to describe the solution step
-                                        //  */
+                                        steps.push(HungarianStep::ExposedVertex { vertex: vertex(node_id) });
                                         blue.visit(node_id);
-
-                                        // /*
-                                        // * Below block is to describe the solution step
-                                        // */
-                                        // state_exposed_vertex.insert(format!("Step {}", exposed_vertex_state_counter), Value::String(format!("Start a rooted tree X with root `{}` and go to step Tree Building",
-                                        //         node_root_weight)));
-                                        // exposed_vertex_state_counter += 1;
-                                        // /*
-                                        //  * Above block is to describe the solution step
-                                        //  */
-                                        let mut tree: Graph<String, String, Directed> = Graph::new();
-                                        // let root = tree.add_node("s".to_string() + &node.index().to_string());
-                                        // println!("node weight = {}", g.node_weight(node.index()));
-                                        // let n_index: N = node.index();
-                                        let mut tree_paths: Vec<Vec<String>> = Vec::new();
-                                        let root = tree.add_node(node_root_weight.clone());
-                                        let mut tree_last_added_node = ::std::collections::VecDeque::new();
-                                        tree_last_added_node.push_back(root);
+                                        steps.push(HungarianStep::Colored {
+                                                vertex: vertex(node_id),
+                                                color: Color::Blue,
+                                        });
+
+                                        // The tree X is kept as parent links between the nodes of `g`, which give
+                                        // the augmenting path once an exposed vertex of T is reached.
+                                        let mut tree_parent: HashMap<N, N> = HashMap::new();
                                         let mut graph_last_visited_blue_node = ::std::collections::VecDeque::new();
                                         graph_last_visited_blue_node.push_back(node_id);
 
-                                        /*
-                                         * Below block is to describe the solution step
-                                         */
-                                        let mut tree_building_state_counter = 1;
-                                        let mut state_tree_building = Map::new();
-
-                                        state_tree_building.insert(
-                                                format!("Step {}", tree_building_state_counter),
-                                                Value::String(serde_json::to_string(&tree).unwrap()),
-                                        );
-                                        tree_building_state_counter += 1;
-                                        /*
-                                         * Above block is to describe the solution step
-                                         */
                                         'tree_building: while let Some(s) = graph_last_visited_blue_node.pop_front() {
-                                                // /*
-                                                // * Below block is to describe the solution step
-                                                // */
-                                                // state_tree_building.insert(format!("Step {}", tree_building_state_counter), Value::String(serde_json::to_string(&tree).unwrap()));
-                                                // tree_building_state_counter += 1;
-                                                // /*
-                                                // * Above block is to describe the solution step
-                                                // */
-                                                let node_s_weight = node_index_weight_map.get(&s.index()).unwrap();
-                                                println!("s = {:?}", node_s_weight);
                                                 is_blue = blue.is_visited(&s);
                                                 let is_scanned = scanned.is_visited(&s);
-                                                // /*
-                                                // * Below block is to describe the solution step
-                                                // */
-                                                // state_tree_building.insert(format!("Step {}", tree_building_state_counter), Value::String("Check if there is an unscanned blue vertex in S.
-                                                //         If there is no such vertex, go to Exposed Vertex step".to_string()));
-                                                // tree_building_state_counter += 1;
-                                                // /*
-                                                // * Above block is to describe the solution step
-                                                // */
-                                                match (is_scanned, is_blue) {
-                                                        (false, true) => {
-                                                                // /*
-                                                                // * Below block is to describe the solution step
-                                                                // */
-                                                                // state_tree_building.insert(format!("Step {}", tree_building_state_counter), Value::String(format!("Node '{}' is an unscanned blue vertex", node_s_weight)));
-                                                                // tree_building_state_counter += 1;
-                                                                // /*
-                                                                // * Above block is to describe the solution step
-                                                                // */
-                                                                // Sort neighbours of  's' in ascending order
-                                                                let mut neighbours_of_s: Vec<N> = g.neighbors(s).map(|neighbor| neighbor.clone()).collect();
-                                                                neighbours_of_s.sort_by(|node_x, node_y| node_x.index().cmp(&node_y.index()));
-                                                                // println!("neighbours_of_s => {:?}", neighbours_of_s);
-                                                                // /*
-                                                                // * Below block is to describe the solution step
-                                                                // */
-                                                                // state_tree_building.insert(format!("Step {}", tree_building_state_counter), Value::Array(format!("Neighbors of node '{}' are {}",
-                                                                //         node_s_weight, serde_json::to_string(&neighbours_of_s).unwrap())));
-                                                                // tree_building_state_counter += 1;
-                                                                // /*
-                                                                // * Above block is to describe the solution step
-                                                                // */
-                                                                for w in neighbours_of_s {
-                                                                        let is_exposed_v = exposed_nodes_v.is_visited(&w);
-                                                                        match is_exposed_v {
-                                                                                true => {
-                                                                                        // this should be storing all augmenting path options
-                                                                                        let node_w_weight = node_index_weight_map.get(&w.index()).unwrap();
-                                                                                        // println!("w = {:?}", node_w_weight);
-                                                                                        // println!("w = {:?} is exposed", node_w_weight);
-                                                                                        let t_node_w = tree.add_node(node_w_weight.clone());
-
-                                                                                        // Adding the tree edge
-                                                                                        let mut dfs = Dfs::new(&tree, root);
-                                                                                        while let Some(nx) = dfs.next(&tree) {
-                                                                                                if tree.node_weight(nx).unwrap() == node_s_weight {
-                                                                                                        tree.add_edge(nx, t_node_w, "".to_string());
-                                                                                                }
-                                                                                        }
-
-                                                                                        println!("tree = {:?}", tree);
-                                                                                        // longest path algm
-                                                                                        let mut path: Vec<String> = Vec::new();
-                                                                                        let mut seen = tree.visit_map();
-                                                                                        let mut processed_path = tree.visit_map();
-                                                                                        seen.visit(root);
-                                                                                        processed_path.visit(root);
-                                                                                        let mut all_nodes_visited = false;
-                                                                                        loop {
-                                                                                                if all_nodes_visited == true {
-                                                                                                        break;
-                                                                                                }
-
-                                                                                                let mut dfs = Dfs::new(&tree, root);
-                                                                                                dfs.next(&tree);
-                                                                                                let mut prev = root;
-                                                                                                while let Some(nx) = dfs.next(&tree) {
-                                                                                                        if !seen.is_visited(&nx) {
-                                                                                                                match tree.contains_edge(prev, nx) {
-                                                                                                                        true => {
-                                                                                                                                seen.visit(nx);
-                                                                                                                                prev = nx;
-                                                                                                                        }
-                                                                                                                        false => {
-                                                                                                                                break;
-                                                                                                                        }
-                                                                                                                }
-                                                                                                        }
-                                                                                                }
-
-                                                                                                let mut tree_nodes = tree.node_indices().peekable();
-                                                                                                while let Some(t_node) = tree_nodes.next() {
-                                                                                                        if seen.is_visited(&t_node) {
-                                                                                                                if tree_nodes.peek().is_none() {
-                                                                                                                        println!("last node = {:?}", t_node);
-                                                                                                                        all_nodes_visited = true;
-                                                                                                                }
-
-                                                                                                                if !processed_path.is_visited(&t_node) || t_node == root {
-                                                                                                                        if let Some(t_node_weight) = tree.node_weight(t_node) {
-                                                                                                                                path.push(t_node_weight.clone());
-                                                                                                                                processed_path.visit(t_node);
-                                                                                                                        } else {
-                                                                                                                                panic!("Node do not have weight!");
-                                                                                                                        }
-                                                                                                                }
-                                                                                                        }
-                                                                                                }
-                                                                                                tree_paths.push(path.clone());
-                                                                                                path.clear();
-                                                                                        }
-
-                                                                                        // aug_path_nodes contains node weight like s1, s2, t1, t2
-                                                                                        let mut aug_path_nodes = vec!["".to_string(); 0];
-                                                                                        println!("aug_path_nodes = {:?}", aug_path_nodes);
-                                                                                        for item in &tree_paths {
-                                                                                                if item.len() > aug_path_nodes.len() {
-                                                                                                        aug_path_nodes = item.clone();
-                                                                                                }
-                                                                                        }
-                                                                                        println!("aug_path_nodes = {:?}", aug_path_nodes);
-
-                                                                                        let mut aug_path_edges = vec![("".to_string(), "".to_string()); 0];
-
-                                                                                        for (i, id1) in aug_path_nodes.iter().enumerate() {
-                                                                                                if let Some(id2) = aug_path_nodes.get(i + 1) {
-                                                                                                        aug_path_edges.push((id1.clone(), id2.clone()));
-                                                                                                }
-                                                                                        }
-                                                                                        println!("aug_path_edges = {:?}", aug_path_edges);
-                                                                                        let mut n1_index = 0;
-                                                                                        let mut n2_index = 0;
-                                                                                        let mut aug_path: HashSet<(N, N)> = HashSet::new();
-                                                                                        for edge in aug_path_edges {
-                                                                                                println!("edge = {:?}", edge);
-                                                                                                let n1_weight = edge.0;
-                                                                                                let n2_weight = edge.1;
-
-                                                                                                for (n_index, n_weight) in node_index_weight_map.iter_mut() {
-                                                                                                        if n1_weight == *n_weight {
-                                                                                                                n1_index = n_index.clone();
-                                                                                                        } else if n2_weight == *n_weight {
-                                                                                                                n2_index = n_index.clone();
-                                                                                                        }
-                                                                                                }
-
-                                                                                                if g_vertex_u_v_sets.nodes_v.contains(&g.from_index(n1_index)) {
-                                                                                                        aug_path.insert((g.from_index(n2_index), g.from_index(n1_index)));
-                                                                                                } else {
-                                                                                                        aug_path.insert((g.from_index(n1_index), g.from_index(n2_index)));
-                                                                                                }
+                                                if is_scanned || !is_blue {
+                                                        continue 'tree_building;
+                                                }
+                                                // Sort neighbours of  's' in ascending order
+                                                let mut neighbours_of_s: Vec<N> = g.neighbors(s).collect();
+                                                neighbours_of_s.sort_by_key(|node| node.index());
+                                                // Every neighbour of s is scanned before moving on to the next blue vertex,
+                                                // otherwise the colouring (and so the cover) is incomplete.
+                                                for w in neighbours_of_s {
+                                                        if red.is_visited(&w) {
+                                                                continue;
+                                                        }
+                                                        red.visit(w);
+                                                        steps.push(HungarianStep::Colored { vertex: vertex(w), color: Color::Red });
+
+                                                        // Adding the tree edge sw
+                                                        tree_parent.insert(w, s);
+                                                        steps.push(HungarianStep::TreeGrown {
+                                                                parent: vertex(s),
+                                                                child: vertex(w),
+                                                                matched: false,
+                                                        });
+
+                                                        let is_exposed_v = exposed_nodes_v.is_visited(&w);
+                                                        match is_exposed_v {
+                                                                true => {
+                                                                        // The path from w back to the root alternates between edges
+                                                                        // not in M (from a red to a blue vertex) and edges of M.
+                                                                        let mut aug_path: HashSet<(N, N)> = HashSet::new();
+                                                                        let mut path = vec![w];
+                                                                        let mut t = w;
+                                                                        while let Some(&u) = tree_parent.get(&t) {
+                                                                                aug_path.insert((u, t));
+                                                                                path.push(u);
+                                                                                match tree_parent.get(&u) {
+                                                                                        Some(&matched_t) => {
+                                                                                                aug_path.insert((u, matched_t));
+                                                                                                path.push(matched_t);
+                                                                                                t = matched_t;
                                                                                         }
-
-                                                                                        //let max_matching = max_matching.clone();
-                                                                                        println!("aug_path = {:?}", aug_path);
-                                                                                        // /*
-                                                                                        // * Below block is to describe the solution step
-                                                                                        // */
-                                                                                        // state_tree_building.insert(format!("Step {}", tree_building_state_counter), Value::String(format!("Node '{}' of T is exposed. The new tree is, X = {}. We have an augmenting
-                                                                                        //         path P = {}. Go to step Augment", node_w_weight, serde_json::to_string(&tree).unwrap(), serde_json::to_string(&aug_path).unwrap())));
-                                                                                        // tree_building_state_counter += 1;
-                                                                                        // /*
-                                                                                        // * Above block is to describe the solution step
-                                                                                        // */
-                                                                                        println!("max_matching = {:?}", max_matching);
-                                                                                        max_matching = max_matching.symmetric_difference(&aug_path).copied().map(|x| x).collect();
-                                                                                        println!("new match = {:?}", &max_matching);
-
-                                                                                        covered_nodes.clear();
-                                                                                        // for edge in &max_matching {
-                                                                                        //         println!("max edge = {:?}", edge);
-                                                                                        // }
-                                                                                        g.reset_map(&mut blue);
-                                                                                        g.reset_map(&mut red);
-                                                                                        g.reset_map(&mut scanned);
-                                                                                        g.reset_map(&mut exposed_nodes_u);
-                                                                                        g.reset_map(&mut exposed_nodes_v);
-
-                                                                                        // /*
-                                                                                        // * Below block is to describe the solution step
-                                                                                        // */
-                                                                                        // let mut augment_state_counter = 1;
-                                                                                        // let mut state_augment = Map::new();
-
-                                                                                        // state_augment.insert(format!("Step {}", augment_state_counter), Value::String(format!("The new matching is given by M = M Δ P = {} \n
-                                                                                        //         Remove all colors and mark each vertex as unscanned and go to step Exposed Vertex.",
-                                                                                        //         serde_json::to_string(&max_matching).unwrap())));
-                                                                                        // augment_state_counter += 1;
-                                                                                        // /*
-                                                                                        // * Above block is to describe the solution step
-                                                                                        // */
-                                                                                        continue 'hungarian;
+                                                                                        None => break,
                                                                                 }
-                                                                                false => {
-                                                                                        let is_red = red.is_visited(&w);
-                                                                                        if !is_red {
-                                                                                                red.visit(w);
-                                                                                                if let Some(uw_edge) = max_matching.iter().find(|&x| x.1.index() == w.index()) {
-                                                                                                        let u = uw_edge.0;
-                                                                                                        blue.visit(u);
-                                                                                                        let node_w_weight = node_index_weight_map.get(&w.index()).unwrap();
-                                                                                                        // println!("w = {:?}", node_w_weight);
-                                                                                                        let t_node_w = tree.add_node(node_w_weight.clone());
-                                                                                                        let node_u_weight = node_index_weight_map.get(&u.index()).unwrap();
-                                                                                                        // println!("u = {:?}", node_u_weight);
-                                                                                                        let t_node_u = tree.add_node(node_u_weight.clone());
+                                                                        }
+                                                                        path.reverse();
+                                                                        steps.push(HungarianStep::AugmentingPath { path: path.into_iter().map(vertex).collect() });
 
-                                                                                                        // Adding the tree edge
-                                                                                                        let mut dfs = Dfs::new(&tree, root);
-                                                                                                        while let Some(nx) = dfs.next(&tree) {
-                                                                                                                if tree.node_weight(nx).unwrap() == node_s_weight {
-                                                                                                                        tree.add_edge(nx, t_node_w, "".to_string());
-                                                                                                                }
-                                                                                                        }
-                                                                                                        tree.add_edge(t_node_w, t_node_u, "".to_string());
+                                                                        max_matching = max_matching.symmetric_difference(&aug_path).copied().collect();
+                                                                        steps.push(HungarianStep::Augmented { matching: matching_vertices(&max_matching) });
 
-                                                                                                        scanned.visit(node_id);
-                                                                                                        graph_last_visited_blue_node.push_back(u);
-                                                                                                        // println!("graph_last_visited_blue_node = {:?}", &graph_last_visited_blue_node);
-                                                                                                        // /*
-                                                                                                        // * Below block is to describe the solution step
-                                                                                                        // */
-                                                                                                        // state_tree_building.insert(format!("Step {}", tree_building_state_counter), Value::String(format!("Node '{}' of T is not exposed and uncolored
-                                                                                                        //         We color it red. Colored the node u = {} blue. Since the edge uw = '{}{}' ∈ M, we add vertices w = {} and u = {} to the tree and then we add
-                                                                                                        //         the edges sw = {}{} and wu = {}{} to the tree. We mark s = {} as scanned and continue with tree building step.",
-                                                                                                        //         node_w_weight, node_u_weight, node_u_weight, node_w_weight, node_w_weight, node_u_weight, node_s_weight, node_w_weight,
-                                                                                                        //         node_w_weight, node_u_weight, node_s_weight)));
-                                                                                                        // tree_building_state_counter += 1;
-                                                                                                        // /*
-                                                                                                        // * Above block is to describe the solution step
-                                                                                                        // */
-                                                                                                        continue 'tree_building;
-                                                                                                }
-                                                                                        }
-                                                                                }
-                                                                        }
+                                                                        covered_nodes.clear();
+                                                                        g.reset_map(&mut blue);
+                                                                        g.reset_map(&mut red);
+                                                                        g.reset_map(&mut scanned);
+                                                                        g.reset_map(&mut exposed_nodes_u);
+                                                                        g.reset_map(&mut exposed_nodes_v);
+
+                                                                        continue 'hungarian;
                                                                 }
-                                                        }
-                                                        (_, _) => {
-                                                                if graph_last_visited_blue_node.is_empty() {
-                                                                        continue 'exposed_vertex;
+                                                                false => {
+                                                                        if let Some(uw_edge) = max_matching.iter().find(|&x| x.1 == w) {
+                                                                                let u = uw_edge.0;
+                                                                                blue.visit(u);
+                                                                                steps.push(HungarianStep::Colored { vertex: vertex(u), color: Color::Blue });
+                                                                                tree_parent.insert(u, w);
+                                                                                steps.push(HungarianStep::TreeGrown {
+                                                                                        parent: vertex(w),
+                                                                                        child: vertex(u),
+                                                                                        matched: true,
+                                                                                });
+
+                                                                                graph_last_visited_blue_node.push_back(u);
+                                                                        }
                                                                 }
                                                         }
                                                 }
+                                                scanned.visit(s);
                                         }
                                 }
                                 (_, _) => {
                                         if node_ids.peek().is_none() {
-                                                println!("No uncolored exposed vertex in S");
                                                 break 'hungarian;
                                         } else {
                                                 continue 'exposed_vertex;
@@ -432,20 +268,24 @@ where
                                 }
                         }
                 }
+
+                // No uncolored exposed vertex in S is left and no augmenting path was found.
+                break 'hungarian;
         }
 
         // min_cover contains uncolored vertices from S and colored vertices from T in G(S, T)
         for node in &g_vertex_u_v_sets.nodes_u {
-                if !blue.is_visited(&node) {
+                if !blue.is_visited(node) {
                         min_cover.push(*node);
                 }
         }
 
         for node in &g_vertex_u_v_sets.nodes_v {
-                if red.is_visited(&node) {
+                if red.is_visited(node) {
                         min_cover.push(*node);
                 }
         }
+        steps.push(HungarianStep::Cover { cover: min_cover.iter().copied().map(vertex).collect() });
 
-        Some((min_cover, max_matching))
+        Some((min_cover, max_matching, steps))
 }
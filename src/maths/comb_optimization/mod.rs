@@ -1,6 +1,18 @@
-pub use assignment_problem::OptimalSolution;
+pub use assignment_problem::{count_optimal_assignments, dual_violations, new_solver, optimal_assignments, solve_bottleneck, solve_sparse, verify_assignment, AssignmentError, AssignmentSolution, AssignmentSolver, AuctionDirection, AuctionSolver, Bid, BottleneckSolution, BottleneckStep, CertificateViolation, Change, HallViolator, KBestAssignments, Objective, OptimalSolution, ProblemState, ShortestPathSolver, Side, SolverKind, SparseAssignmentSolution, SparseCostMatrix, VerificationReport};
 pub use assignment_problem::inputs::parse;
-pub use hungarian::hungarian_maximum_matching;
+pub use birkhoff::{birkhoff_decomposition, BirkhoffDecomposition, BirkhoffError, BirkhoffTerm};
+pub use cost::Cost;
+pub use graph_matching::{weighted_bipartite_matching, GraphMatchingError, WeightedMatching};
+pub use gap::{solve_gap, GapError, GapMethod, GapSolution};
+pub use hungarian::{hungarian_maximum_matching, Color, HungarianStep, StepVertex};
+pub use qap::{gilmore_lawler_bound, parse_qaplib, read_qaplib, solve_qap, QapError, QapMatrices, QapMethod, QapSolution};
+pub use transportation::{solve_transportation, InitialMethod, TransportationError, TransportationSolution, TransportationState};
 
 pub mod assignment_problem;
+pub mod birkhoff;
+pub mod cost;
+pub mod gap;
+pub mod graph_matching;
 pub mod hungarian;
+pub mod qap;
+pub mod transportation;
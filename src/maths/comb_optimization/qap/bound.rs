@@ -0,0 +1,127 @@
+use crate::comb_optimization::assignment_problem::{AssignmentError, AssignmentSolver, ShortestPathSolver};
+use crate::comb_optimization::cost::{sum_cost, Cost};
+use crate::comb_optimization::qap::error::{checked, QapError};
+use crate::comb_optimization::qap::qap::check_input;
+use nalgebra::DMatrix;
+
+// The unplaced facilities, the free locations and the lower costs l between them.
+type LowerCosts<N> = (Vec<usize>, Vec<usize>, DMatrix<N>);
+
+/// The Gilmore–Lawler bound of a node of the branch-and-bound: the cost of
+/// the facilities placed so far plus an optimal assignment of the others,
+/// priced by [`lower_costs`].
+pub(super) struct NodeBound<N: Cost> {
+        pub(super) value: N,
+        /// The unplaced facilities and the free locations, the rows and columns
+        /// of l.
+        pub(super) facilities: Vec<usize>,
+        pub(super) locations: Vec<usize>,
+        /// The location of each unplaced facility in the optimal assignment of l.
+        pub(super) assignment: Vec<usize>,
+        /// l_i,k - u_i - v_k for the optimal duals of that assignment, as `f64`:
+        /// placing facility i at location k raises the bound by at least this.
+        pub(super) reduced: DMatrix<f64>,
+}
+
+/// The Gilmore–Lawler lower bound on ∑ flow_i,j distance_p(i),p(j) over all
+/// permutations p.
+///
+/// Placing facility i at location k costs at least
+///
+/// l_i,k = flow_i,i distance_k,k + ⟨flow_i,·, distance_k,·⟩_min,
+///
+/// where the minimal scalar product pairs the flows of i to the other
+/// facilities, in increasing order, with the distances of k to the other
+/// locations, in decreasing order. Every permutation costs at least the
+/// optimal linear assignment of l, which is solved by the
+/// [`ShortestPathSolver`]. An unsigned cost type cannot hold the negative
+/// duals that this assignment may need, so when it overflows it is solved
+/// again with `f64` costs, and its cost summed in the cost type.
+pub fn gilmore_lawler_bound<N: Cost>(flow: &DMatrix<N>, distance: &DMatrix<N>) -> Result<N, QapError> {
+        check_input(flow, distance)?;
+        let unplaced = vec![None; flow.nrows()];
+        Ok(node_bound(flow, distance, &unplaced, N::zero())?.value)
+}
+
+/// The bound of the node that has placed facility i at `location_of[i]`, if
+/// any, at a cost of `fixed`.
+pub(super) fn node_bound<N: Cost>(flow: &DMatrix<N>, distance: &DMatrix<N>, location_of: &[Option<usize>], fixed: N) -> Result<NodeBound<N>, QapError> {
+        let (facilities, locations, l) = lower_costs(flow, distance, location_of)?;
+        let (assignment, reduced) = match solve_linear(&l) {
+                Err(AssignmentError::Overflow { .. }) => solve_linear(&l.map(|l_ik| l_ik.to_f64()))?,
+                result => result?,
+        };
+        let cost = checked(sum_cost(assignment.iter().enumerate().map(|(row, &col)| l[(row, col)])), "linear assignment cost")?;
+        Ok(NodeBound {
+                value: checked(fixed.checked_add(cost), "Gilmore–Lawler bound")?,
+                assignment: assignment.iter().map(|&col| locations[col]).collect(),
+                facilities,
+                locations,
+                reduced,
+        })
+}
+
+// The column of every row in an optimal assignment of the square matrix `l`
+// and the reduced costs of its optimal duals.
+fn solve_linear<M: Cost>(l: &DMatrix<M>) -> Result<(Vec<usize>, DMatrix<f64>), AssignmentError> {
+        let mut solver = ShortestPathSolver::new();
+        solver.init(Vec::new(), Vec::new(), l.clone());
+        let solution = solver.find_optimum_cost()?;
+        let assignment = solution.assignment.iter().map(|&(_, col)| col).collect();
+        let reduced = DMatrix::from_fn(l.nrows(), l.ncols(), |row, col| (l[(row, col)].to_f64() - solution.u[row].to_f64() - solution.v[col].to_f64()).max(0.0));
+        Ok((assignment, reduced))
+}
+
+// The unplaced facilities, the free locations and the matrix l of lower costs
+// of placing each of those facilities at each of those locations: its cost
+// with the placed facilities, which is known, and the minimal scalar product
+// of its flows to the other unplaced facilities with the distances to the
+// other free locations.
+fn lower_costs<N: Cost>(flow: &DMatrix<N>, distance: &DMatrix<N>, location_of: &[Option<usize>]) -> Result<LowerCosts<N>, QapError> {
+        let n = flow.nrows();
+        let facilities: Vec<usize> = (0..n).filter(|&i| location_of[i].is_none()).collect();
+        let mut used = vec![false; n];
+        for &k in location_of.iter().flatten() {
+                used[k] = true;
+        }
+        let locations: Vec<usize> = (0..n).filter(|&k| !used[k]).collect();
+
+        let flows: Vec<Vec<N>> = facilities.iter().map(|&i| sorted(facilities.iter().filter(|&&j| j != i).map(|&j| flow[(i, j)]))).collect();
+        let distances: Vec<Vec<N>> = locations
+                .iter()
+                .map(|&k| {
+                        let mut d = sorted(locations.iter().filter(|&&m| m != k).map(|&m| distance[(k, m)]));
+                        d.reverse();
+                        d
+                })
+                .collect();
+
+        let mut l = DMatrix::from_element(facilities.len(), locations.len(), N::zero());
+        for (row, &i) in facilities.iter().enumerate() {
+                for (col, &k) in locations.iter().enumerate() {
+                        let scalar_product = flows[row].iter().zip(&distances[col]).try_fold(N::zero(), |acc, (&f, &d)| acc.checked_add(f.checked_mul(d)?));
+                        let known = placement_cost(flow, distance, location_of, i, k)?;
+                        l[(row, col)] = checked(scalar_product.and_then(|s| s.checked_add(known)), "l_i,k")?;
+                }
+        }
+        Ok((facilities, locations, l))
+}
+
+/// What placing facility `i` at location `k` adds to the cost of the placed
+/// facilities: flow_i,i distance_k,k and the flows between i and each placed
+/// facility times their distances, both ways.
+pub(super) fn placement_cost<N: Cost>(flow: &DMatrix<N>, distance: &DMatrix<N>, location_of: &[Option<usize>], i: usize, k: usize) -> Result<N, QapError> {
+        let mut cost = checked(flow[(i, i)].checked_mul(distance[(k, k)]), "flow × distance")?;
+        for (j, m) in location_of.iter().enumerate().filter_map(|(j, m)| m.map(|m| (j, m))) {
+                let there = checked(flow[(i, j)].checked_mul(distance[(k, m)]), "flow × distance")?;
+                let back = checked(flow[(j, i)].checked_mul(distance[(m, k)]), "flow × distance")?;
+                cost = checked(cost.checked_add(there).and_then(|c| c.checked_add(back)), "placement cost")?;
+        }
+        Ok(cost)
+}
+
+fn sorted<N: Cost>(items: impl Iterator<Item = N>) -> Vec<N> {
+        let mut items: Vec<N> = items.collect();
+        items.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        items
+}
@@ -0,0 +1,68 @@
+use crate::comb_optimization::assignment_problem::AssignmentError;
+use rocket::serde::Serialize;
+use std::error::Error;
+use std::fmt;
+
+/// Everything that can go wrong between reading a quadratic assignment
+/// problem and returning its permutation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "error")]
+pub enum QapError {
+        /// The input is not valid JSON or QAPLIB, or an entry is not a number of the cost type.
+        InvalidInput { details: String },
+
+        /// The flow and distance matrices are empty.
+        EmptyMatrix,
+
+        /// Row `row` of `matrix` has `found` entries instead of the `expected` entries of row 0.
+        RaggedRows { row: usize, expected: usize, found: usize },
+
+        /// `matrix` ("flow" or "distance") is not square.
+        NotSquare { matrix: &'static str, nrows: usize, ncols: usize },
+
+        /// The flow matrix is `flow` × `flow`, the distance matrix `distance` × `distance`.
+        SizeMismatch { flow: usize, distance: usize },
+
+        /// A computation left the range of the cost type.
+        Overflow { operation: &'static str },
+}
+
+impl fmt::Display for QapError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                        QapError::InvalidInput { details } => write!(f, "invalid input: {}", details),
+                        QapError::EmptyMatrix => write!(f, "the flow and distance matrices are empty"),
+                        QapError::RaggedRows { row, expected, found } => write!(f, "row {} has {} entries, expected {}", row, found, expected),
+                        QapError::NotSquare { matrix, nrows, ncols } => write!(f, "the {} matrix is {}x{}, not square", matrix, nrows, ncols),
+                        QapError::SizeMismatch { flow, distance } => write!(f, "{} facilities but {} locations", flow, distance),
+                        QapError::Overflow { operation } => write!(f, "{} overflowed", operation),
+                }
+        }
+}
+
+impl Error for QapError {}
+
+impl From<serde_json::Error> for QapError {
+        fn from(err: serde_json::Error) -> Self {
+                QapError::InvalidInput { details: err.to_string() }
+        }
+}
+
+// The input checks shared with the assignment problem, and the errors of the
+// assignments solved for the bounds.
+impl From<AssignmentError> for QapError {
+        fn from(err: AssignmentError) -> Self {
+                match err {
+                        AssignmentError::InvalidInput { details } => QapError::InvalidInput { details },
+                        AssignmentError::EmptyMatrix => QapError::EmptyMatrix,
+                        AssignmentError::RaggedRows { row, expected, found } => QapError::RaggedRows { row, expected, found },
+                        AssignmentError::Overflow { operation } => QapError::Overflow { operation },
+                        err => QapError::InvalidInput { details: err.to_string() },
+                }
+        }
+}
+
+/// `Some` result of a checked operation, or a [`QapError::Overflow`] naming it.
+pub(crate) fn checked<N>(result: Option<N>, operation: &'static str) -> Result<N, QapError> {
+        result.ok_or(QapError::Overflow { operation })
+}
@@ -0,0 +1,60 @@
+use nalgebra::DMatrix;
+
+/// Pairwise exchange: swaps the locations of the two facilities whose swap
+/// lowers the cost the most, until no swap does. `location_of` is a
+/// permutation; the costs are `f64` copies of the flows and distances.
+///
+/// The change of a swap of r and s is computed in O(n) with Taillard's
+/// formula for asymmetric matrices, so a pass over all pairs takes O(n^3).
+pub(super) fn pairwise_exchange(flow: &DMatrix<f64>, distance: &DMatrix<f64>, location_of: &mut [usize]) {
+        let n = location_of.len();
+        loop {
+                let mut best: Option<(usize, usize, f64)> = None;
+                for r in 0..n {
+                        for s in r + 1..n {
+                                let delta = swap_delta(flow, distance, location_of, r, s);
+                                if delta < -1e-9 && best.is_none_or(|(_, _, d)| delta < d) {
+                                        best = Some((r, s, delta));
+                                }
+                        }
+                }
+                match best {
+                        Some((r, s, _)) => location_of.swap(r, s),
+                        None => return,
+                }
+        }
+}
+
+/// Change of ∑ flow_i,j distance_p(i),p(j) when facilities `r` and `s` swap locations.
+pub(super) fn swap_delta(flow: &DMatrix<f64>, distance: &DMatrix<f64>, p: &[usize], r: usize, s: usize) -> f64 {
+        let (pr, ps) = (p[r], p[s]);
+        let mut delta = (flow[(r, r)] - flow[(s, s)]) * (distance[(ps, ps)] - distance[(pr, pr)]) + (flow[(r, s)] - flow[(s, r)]) * (distance[(ps, pr)] - distance[(pr, ps)]);
+        for (k, &pk) in p.iter().enumerate() {
+                if k != r && k != s {
+                        delta += (flow[(k, r)] - flow[(k, s)]) * (distance[(pk, ps)] - distance[(pk, pr)]) + (flow[(r, k)] - flow[(s, k)]) * (distance[(ps, pk)] - distance[(pr, pk)]);
+                }
+        }
+        delta
+}
+
+/// The permutations the local search starts from besides the given ones:
+/// the identity and `count` shuffles by a fixed xorshift sequence, so that
+/// results can be reproduced.
+pub(super) fn starting_permutations(n: usize, count: usize) -> Vec<Vec<usize>> {
+        let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+        let mut next = |bound: usize| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                (state % bound as u64) as usize
+        };
+        let mut starts = vec![(0..n).collect::<Vec<usize>>()];
+        for _ in 0..count {
+                let mut p: Vec<usize> = (0..n).collect();
+                for i in (1..n).rev() {
+                        p.swap(i, next(i + 1));
+                }
+                starts.push(p);
+        }
+        starts
+}
@@ -0,0 +1,11 @@
+pub use bound::gilmore_lawler_bound;
+pub use error::QapError;
+pub use qap::{solve_qap, QapMethod, QapSolution};
+pub use qaplib::{parse_qaplib, read_qaplib, QapMatrices};
+
+pub mod bound;
+pub mod error;
+pub mod local_search;
+#[allow(clippy::module_inception)]
+pub mod qap;
+pub mod qaplib;
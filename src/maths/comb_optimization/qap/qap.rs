@@ -0,0 +1,221 @@
+use crate::comb_optimization::cost::Cost;
+use crate::comb_optimization::qap::bound::{node_bound, placement_cost, NodeBound};
+use crate::comb_optimization::qap::error::{checked, QapError};
+use crate::comb_optimization::qap::local_search::{pairwise_exchange, starting_permutations};
+use nalgebra::DMatrix;
+use rocket::serde::{Deserialize, Serialize};
+
+// Largest problem that `QapMethod::Auto` solves exactly.
+const MAX_EXACT_SIZE: usize = 12;
+// Branch-and-bound nodes after which the search stops with its incumbent.
+const MAX_NODES: usize = 200_000;
+// Random permutations the local search starts from.
+const RESTARTS: usize = 20;
+
+/// How a quadratic assignment problem is solved.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QapMethod {
+        /// Branch-and-bound up to 12 facilities, local search above.
+        #[default]
+        Auto,
+        /// Branch-and-bound, started from the local search solution. The
+        /// search grows exponentially; it stops after 200 000 nodes with the
+        /// best placement found so far, which is often reached beyond 12 to 15
+        /// facilities.
+        Exact,
+        /// Multi-start pairwise exchange only.
+        LocalSearch,
+}
+
+/// A placement of the facilities at the locations.
+#[derive(Debug, Clone, Serialize)]
+pub struct QapSolution<N: Cost> {
+        /// The location of every facility.
+        pub assignment: Vec<usize>,
+        pub cost: N,
+        /// Gilmore–Lawler bound of the whole problem: no placement costs less.
+        pub bound: N,
+        /// Cost of the best local search solution, the first incumbent of the
+        /// branch-and-bound.
+        pub heuristic_cost: N,
+        /// Whether `cost` is proven optimal, after a branch-and-bound that ran to
+        /// the end and for a local search solution that meets `bound`.
+        pub optimal: bool,
+        /// Number of branch-and-bound nodes visited.
+        pub nodes: usize,
+}
+
+/// Solves the quadratic assignment problem (Koopmans–Beckmann form): place
+/// facility i at location p(i), every location taking one facility, so that
+/// ∑_i,j flow_i,j distance_p(i),p(j) is as small as possible.
+///
+/// The Gilmore–Lawler bound of the whole problem, see
+/// [`gilmore_lawler_bound`](super::gilmore_lawler_bound), gives a first
+/// placement, its linear assignment. Pairwise exchange improves that
+/// placement, the identity and a few shuffled permutations, and the best
+/// result becomes the incumbent. The branch-and-bound then places
+/// the facilities with the largest total flow first, tries the locations in
+/// the order of their reduced costs in the bound's linear assignment, which
+/// also bound a child from below before its own bound is computed, and
+/// prunes every node whose Gilmore–Lawler bound, given the facilities
+/// already placed, is not below the incumbent. After 200 000 nodes the
+/// search stops with the incumbent, which is then not proven optimal.
+///
+/// The local search and the children's bounds compare costs as `f64`; the
+/// costs and bounds returned are summed exactly in the cost type.
+pub fn solve_qap<N: Cost>(flow: &DMatrix<N>, distance: &DMatrix<N>, method: QapMethod) -> Result<QapSolution<N>, QapError> {
+        check_input(flow, distance)?;
+        let n = flow.nrows();
+        let root = node_bound(flow, distance, &vec![None; n], N::zero())?;
+
+        // Local search from the root's linear assignment and the other starts.
+        let (flow_f64, distance_f64) = (flow.map(|f| f.to_f64()), distance.map(|d| d.to_f64()));
+        let mut starts = vec![root.assignment.clone()];
+        starts.extend(starting_permutations(n, RESTARTS));
+        let mut incumbent: Option<(Vec<usize>, N)> = None;
+        for mut location_of in starts {
+                pairwise_exchange(&flow_f64, &distance_f64, &mut location_of);
+                let cost = qap_cost(flow, distance, &location_of)?;
+                if incumbent.as_ref().is_none_or(|(_, best)| cost < *best) {
+                        incumbent = Some((location_of, cost));
+                }
+        }
+        let (location_of, heuristic_cost) = match incumbent {
+                Some(incumbent) => incumbent,
+                None => return Err(QapError::EmptyMatrix),
+        };
+
+        let bound = root.value;
+        let exact = match method {
+                QapMethod::Auto => n <= MAX_EXACT_SIZE,
+                QapMethod::Exact => true,
+                QapMethod::LocalSearch => false,
+        };
+        if !exact {
+                return Ok(QapSolution {
+                        assignment: location_of,
+                        cost: heuristic_cost,
+                        bound,
+                        heuristic_cost,
+                        optimal: heuristic_cost <= bound,
+                        nodes: 0,
+                });
+        }
+
+        // Facilities with the largest total flow first.
+        let mut order: Vec<usize> = (0..n).collect();
+        let total_flow = |i: usize| flow.row(i).iter().chain(flow.column(i).iter()).map(|f| f.to_f64()).sum::<f64>();
+        order.sort_by(|&a, &b| total_flow(b).total_cmp(&total_flow(a)));
+        let mut search = Search {
+                flow,
+                distance,
+                order,
+                location_of: vec![None; n],
+                best: (location_of, heuristic_cost),
+                nodes: 0,
+                complete: true,
+        };
+        search.branch(0, N::zero(), root)?;
+        let (assignment, cost) = search.best;
+        Ok(QapSolution {
+                assignment,
+                cost,
+                bound,
+                heuristic_cost,
+                optimal: search.complete,
+                nodes: search.nodes,
+        })
+}
+
+/// Rejects empty, non-square or differently sized flow and distance matrices.
+pub(super) fn check_input<N: Cost>(flow: &DMatrix<N>, distance: &DMatrix<N>) -> Result<(), QapError> {
+        for (matrix, m) in [("flow", flow), ("distance", distance)] {
+                let (nrows, ncols) = m.shape();
+                if nrows == 0 || ncols == 0 {
+                        return Err(QapError::EmptyMatrix);
+                }
+                if nrows != ncols {
+                        return Err(QapError::NotSquare { matrix, nrows, ncols });
+                }
+        }
+        if flow.nrows() != distance.nrows() {
+                return Err(QapError::SizeMismatch {
+                        flow: flow.nrows(),
+                        distance: distance.nrows(),
+                });
+        }
+        Ok(())
+}
+
+// ∑_i,j flow_i,j distance_p(i),p(j).
+fn qap_cost<N: Cost>(flow: &DMatrix<N>, distance: &DMatrix<N>, location_of: &[usize]) -> Result<N, QapError> {
+        let mut cost = N::zero();
+        for (i, &k) in location_of.iter().enumerate() {
+                for (j, &m) in location_of.iter().enumerate() {
+                        let term = checked(flow[(i, j)].checked_mul(distance[(k, m)]), "flow × distance")?;
+                        cost = checked(cost.checked_add(term), "placement cost")?;
+                }
+        }
+        Ok(cost)
+}
+
+// Depth-first branch-and-bound over the location of each facility in `order`.
+struct Search<'a, N: Cost> {
+        flow: &'a DMatrix<N>,
+        distance: &'a DMatrix<N>,
+        order: Vec<usize>,
+        location_of: Vec<Option<usize>>,
+        best: (Vec<usize>, N),
+        nodes: usize,
+        // Whether the search ran to the end, within `MAX_NODES`.
+        complete: bool,
+}
+
+impl<N: Cost> Search<'_, N> {
+        // Places the facility `order[depth]`, the others before it costing
+        // `fixed`, at the node with the Gilmore–Lawler bound `bound`.
+        fn branch(&mut self, depth: usize, fixed: N, bound: NodeBound<N>) -> Result<(), QapError> {
+                if self.nodes >= MAX_NODES {
+                        self.complete = false;
+                        return Ok(());
+                }
+                self.nodes += 1;
+                if bound.value >= self.best.1 {
+                        return Ok(());
+                }
+                let i = self.order[depth];
+                let row = match bound.facilities.iter().position(|&f| f == i) {
+                        Some(row) => row,
+                        None => return Ok(()),
+                };
+
+                // A child is bounded by its reduced cost in the linear assignment.
+                // As that is an `f64`, only children that cannot even tie with the
+                // incumbent are skipped; their own bound settles the others.
+                let value = bound.value.to_f64();
+                let mut children: Vec<(usize, f64)> = bound.locations.iter().enumerate().map(|(col, &k)| (k, value + bound.reduced[(row, col)])).collect();
+                children.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+                let best = self.best.1.to_f64();
+                for (k, child_bound) in children {
+                        if child_bound > best + 1e-9 * (1.0 + best.abs()) {
+                                break;
+                        }
+                        let added = placement_cost(self.flow, self.distance, &self.location_of, i, k)?;
+                        let fixed = checked(fixed.checked_add(added), "placement cost")?;
+                        self.location_of[i] = Some(k);
+                        if depth + 1 == self.order.len() {
+                                self.nodes += 1;
+                                if fixed < self.best.1 {
+                                        self.best = (self.location_of.iter().flatten().copied().collect(), fixed);
+                                }
+                        } else {
+                                let child = node_bound(self.flow, self.distance, &self.location_of, fixed)?;
+                                self.branch(depth + 1, fixed, child)?;
+                        }
+                        self.location_of[i] = None;
+                }
+                Ok(())
+        }
+}
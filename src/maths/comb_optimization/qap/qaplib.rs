@@ -0,0 +1,44 @@
+use crate::comb_optimization::cost::Cost;
+use crate::comb_optimization::qap::error::QapError;
+use nalgebra::DMatrix;
+use serde::de::DeserializeOwned;
+use std::fs;
+use std::path::Path;
+
+/// The flow and distance matrices of a quadratic assignment problem.
+pub type QapMatrices<N> = (DMatrix<N>, DMatrix<N>);
+
+/// Reads a problem in the QAPLIB format: the size n, then the n × n flow
+/// matrix A and the n × n distance matrix B in row order, all separated by
+/// any whitespace, so that lines may wrap anywhere. QAPLIB minimises
+/// ∑ a_i,j b_p(i),p(j), the objective of [`solve_qap`](super::solve_qap).
+///
+/// The entries are read as the cost type `N`, e.g. `parse_qaplib::<f64>()`
+/// for fractional distances.
+pub fn parse_qaplib<N: Cost + DeserializeOwned>(text: &str) -> Result<QapMatrices<N>, QapError> {
+        let mut tokens = text.split_whitespace();
+        let n: usize = match tokens.next() {
+                Some(token) => token.parse().map_err(|_| QapError::InvalidInput {
+                        details: format!("the size {:?} is not a number", token),
+                })?,
+                None => return Err(QapError::EmptyMatrix),
+        };
+        if n == 0 {
+                return Err(QapError::EmptyMatrix);
+        }
+
+        let entries = tokens.map(serde_json::from_str).collect::<Result<Vec<N>, _>>()?;
+        if entries.len() != 2 * n * n {
+                return Err(QapError::InvalidInput {
+                        details: format!("expected {} entries for two {}x{} matrices, found {}", 2 * n * n, n, n, entries.len()),
+                });
+        }
+        let (flow, distance) = entries.split_at(n * n);
+        Ok((DMatrix::from_row_slice(n, n, flow), DMatrix::from_row_slice(n, n, distance)))
+}
+
+/// Reads the QAPLIB file at `path`, see [`parse_qaplib`].
+pub fn read_qaplib<N: Cost + DeserializeOwned>(path: impl AsRef<Path>) -> Result<QapMatrices<N>, QapError> {
+        let text = fs::read_to_string(path).map_err(|err| QapError::InvalidInput { details: err.to_string() })?;
+        parse_qaplib(&text)
+}
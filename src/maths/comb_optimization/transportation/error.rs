@@ -0,0 +1,79 @@
+use crate::comb_optimization::assignment_problem::{AssignmentError, Side};
+use rocket::serde::Serialize;
+use std::error::Error;
+use std::fmt;
+
+/// Everything that can go wrong between reading a transportation problem and
+/// returning its optimal shipments.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "error")]
+pub enum TransportationError {
+        /// The input is not valid JSON or an entry is not a number of the cost type.
+        InvalidInput { details: String },
+
+        /// `c` has no rows or no columns.
+        EmptyMatrix,
+
+        /// Row `row` of `c` has `found` entries instead of the `expected` entries of row 0.
+        RaggedRows { row: usize, expected: usize, found: usize },
+
+        /// `supply` (side `Rows`) or `demand` (side `Columns`) does not have one
+        /// entry per row or column of `c`.
+        LengthMismatch { side: Side, expected: usize, found: usize },
+
+        /// Entry `index` of `supply` (side `Rows`) or `demand` (side `Columns`) is negative.
+        NegativeQuantity { side: Side, index: usize },
+
+        /// A computation left the range of the cost type, e.g. the total supply.
+        Overflow { operation: &'static str },
+
+        /// The MODI method made `iterations` pivots without reaching the optimum.
+        IterationLimit { iterations: usize },
+}
+
+impl fmt::Display for TransportationError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                        TransportationError::InvalidInput { details } => write!(f, "invalid input: {}", details),
+                        TransportationError::EmptyMatrix => write!(f, "the cost matrix is empty"),
+                        TransportationError::RaggedRows { row, expected, found } => write!(f, "row {} of the cost matrix has {} entries, expected {}", row, found, expected),
+                        TransportationError::LengthMismatch { side, expected, found } => write!(f, "{} has {} entries, expected {}", quantity_name(*side), found, expected),
+                        TransportationError::NegativeQuantity { side, index } => write!(f, "entry {} of {} is negative", index, quantity_name(*side)),
+                        TransportationError::Overflow { operation } => write!(f, "{} overflowed", operation),
+                        TransportationError::IterationLimit { iterations } => write!(f, "no optimum after {} pivots", iterations),
+                }
+        }
+}
+
+fn quantity_name(side: Side) -> &'static str {
+        match side {
+                Side::Rows => "supply",
+                Side::Columns => "demand",
+        }
+}
+
+impl Error for TransportationError {}
+
+impl From<serde_json::Error> for TransportationError {
+        fn from(err: serde_json::Error) -> Self {
+                TransportationError::InvalidInput { details: err.to_string() }
+        }
+}
+
+// The input checks shared with the assignment problem.
+impl From<AssignmentError> for TransportationError {
+        fn from(err: AssignmentError) -> Self {
+                match err {
+                        AssignmentError::InvalidInput { details } => TransportationError::InvalidInput { details },
+                        AssignmentError::EmptyMatrix => TransportationError::EmptyMatrix,
+                        AssignmentError::RaggedRows { row, expected, found } => TransportationError::RaggedRows { row, expected, found },
+                        AssignmentError::Overflow { operation } => TransportationError::Overflow { operation },
+                        err => TransportationError::InvalidInput { details: err.to_string() },
+                }
+        }
+}
+
+/// `Some` result of a checked operation, or a [`TransportationError::Overflow`] naming it.
+pub(crate) fn checked<N>(result: Option<N>, operation: &'static str) -> Result<N, TransportationError> {
+        result.ok_or(TransportationError::Overflow { operation })
+}
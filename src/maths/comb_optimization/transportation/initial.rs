@@ -0,0 +1,154 @@
+use crate::comb_optimization::cost::Cost;
+use crate::comb_optimization::transportation::error::{checked, TransportationError};
+use nalgebra::DMatrix;
+use rocket::serde::{Deserialize, Serialize};
+
+// Shipments and the basic cells, in the order they were filled.
+type BasicSolution<N> = (DMatrix<N>, Vec<(usize, usize)>);
+
+/// How the first basic feasible solution of a transportation problem is built.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InitialMethod {
+        /// Fill the cells from the top left corner, moving down when a row is
+        /// used up and right when a column is.
+        #[default]
+        NorthwestCorner,
+        /// Fill the cheapest cell of the rows and columns that are left first.
+        LeastCost,
+        /// Vogel's approximation: fill the cheapest cell of the row or column with
+        /// the largest penalty, the difference between its two cheapest cells.
+        Vogel,
+}
+
+/// Initial shipments `x` of a balanced problem and their m + n - 1 basic
+/// cells, in the order they were filled.
+///
+/// Every allocation ships as much as the row and the column of its cell
+/// allow and crosses out one of them. When both are used up at once only the
+/// row is crossed out (the column, if it is the last row), so the column
+/// gets a basic cell with a zero shipment later on: a degenerate solution
+/// still has m + n - 1 basic cells, which form a spanning tree of the rows
+/// and columns.
+pub(super) fn initial_solution<N: Cost>(supply: &[N], demand: &[N], c: &DMatrix<N>, method: InitialMethod) -> Result<BasicSolution<N>, TransportationError> {
+        let (nrows, ncols) = c.shape();
+        let mut left = Remaining {
+                supply: supply.to_vec(),
+                demand: demand.to_vec(),
+                active_rows: vec![true; nrows],
+                active_cols: vec![true; ncols],
+                x: DMatrix::from_element(nrows, ncols, N::zero()),
+                basis: Vec::new(),
+        };
+
+        let (mut i, mut j) = (0, 0);
+        for _ in 0..(nrows + ncols - 1) {
+                let (row, col) = match method {
+                        InitialMethod::NorthwestCorner => (i, j),
+                        InitialMethod::LeastCost => left.cheapest(c, left.rows(), |_| true),
+                        InitialMethod::Vogel => left.vogel_cell(c)?,
+                };
+                // The northwest corner moves on past the line that was crossed out.
+                if left.allocate(row, col)? {
+                        i += 1;
+                } else {
+                        j += 1;
+                }
+        }
+        Ok((left.x, left.basis))
+}
+
+// What is left to ship while an initial solution is built.
+struct Remaining<N> {
+        supply: Vec<N>,
+        demand: Vec<N>,
+        active_rows: Vec<bool>,
+        active_cols: Vec<bool>,
+        x: DMatrix<N>,
+        basis: Vec<(usize, usize)>,
+}
+
+impl<N: Cost> Remaining<N> {
+        fn rows(&self) -> Vec<usize> {
+                (0..self.active_rows.len()).filter(|&i| self.active_rows[i]).collect()
+        }
+
+        fn cols(&self) -> Vec<usize> {
+                (0..self.active_cols.len()).filter(|&j| self.active_cols[j]).collect()
+        }
+
+        // Ships min(s_i, d_j) on (i, j) and crosses out the row or the column,
+        // returning whether it was the row.
+        fn allocate(&mut self, i: usize, j: usize) -> Result<bool, TransportationError> {
+                let row_used_up = self.supply[i] <= self.demand[j];
+                let q = if row_used_up { self.supply[i] } else { self.demand[j] };
+                self.x[(i, j)] = q;
+                self.basis.push((i, j));
+                self.supply[i] = checked(self.supply[i].checked_sub(q), "s_i - x_i,j")?;
+                self.demand[j] = checked(self.demand[j].checked_sub(q), "d_j - x_i,j")?;
+
+                let cross_row = (row_used_up && self.rows().len() > 1) || self.cols().len() == 1;
+                if cross_row {
+                        self.active_rows[i] = false;
+                        self.supply[i] = N::zero();
+                } else {
+                        self.active_cols[j] = false;
+                        self.demand[j] = N::zero();
+                }
+                Ok(cross_row)
+        }
+
+        // Cheapest active cell in the given rows and the columns accepted by `in_cols`.
+        fn cheapest(&self, c: &DMatrix<N>, rows: Vec<usize>, in_cols: impl Fn(usize) -> bool) -> (usize, usize) {
+                let cols: Vec<usize> = self.cols().into_iter().filter(|&j| in_cols(j)).collect();
+                let mut best = (rows[0], cols[0]);
+                for &i in &rows {
+                        for &j in &cols {
+                                if c[(i, j)] < c[best] {
+                                        best = (i, j);
+                                }
+                        }
+                }
+                best
+        }
+
+        // The cheapest cell of the row or column with the largest penalty, rows
+        // first on a tie. A line with one active cell left has that cell's cost as
+        // its penalty.
+        fn vogel_cell(&self, c: &DMatrix<N>) -> Result<(usize, usize), TransportationError> {
+                let (rows, cols) = (self.rows(), self.cols());
+                let mut best: Option<(N, (usize, usize))> = None;
+                for &i in &rows {
+                        let penalty = penalty(cols.iter().map(|&j| c[(i, j)]))?;
+                        if best.is_none_or(|(p, _)| penalty > p) {
+                                best = Some((penalty, self.cheapest(c, vec![i], |_| true)));
+                        }
+                }
+                for &j in &cols {
+                        let penalty = penalty(rows.iter().map(|&i| c[(i, j)]))?;
+                        if best.is_none_or(|(p, _)| penalty > p) {
+                                best = Some((penalty, self.cheapest(c, rows.clone(), |col| col == j)));
+                        }
+                }
+                Ok(best.map_or((rows[0], cols[0]), |(_, cell)| cell))
+        }
+}
+
+// Difference between the two smallest costs, or the only cost.
+fn penalty<N: Cost>(costs: impl Iterator<Item = N>) -> Result<N, TransportationError> {
+        let mut smallest: Option<N> = None;
+        let mut second: Option<N> = None;
+        for x in costs {
+                if smallest.is_none_or(|s| x < s) {
+                        second = smallest;
+                        smallest = Some(x);
+                } else if second.is_none_or(|s| x < s) {
+                        second = Some(x);
+                }
+        }
+        match (smallest, second) {
+                (Some(a), Some(b)) => checked(b.checked_sub(a), "Vogel penalty"),
+                (Some(a), None) => Ok(a),
+                _ => Ok(N::zero()),
+        }
+}
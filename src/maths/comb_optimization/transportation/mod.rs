@@ -0,0 +1,8 @@
+pub use error::TransportationError;
+pub use initial::InitialMethod;
+pub use transportation::{solve_transportation, MAX_ITERATIONS, TransportationSolution, TransportationState};
+
+pub mod error;
+pub mod initial;
+#[allow(clippy::module_inception)]
+pub mod transportation;
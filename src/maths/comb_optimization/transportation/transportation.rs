@@ -0,0 +1,355 @@
+use crate::comb_optimization::assignment_problem::Side;
+use crate::comb_optimization::cost::{sum_cost, Cost};
+use crate::comb_optimization::transportation::error::{checked, TransportationError};
+use crate::comb_optimization::transportation::initial::{initial_solution, InitialMethod};
+use nalgebra::DMatrix;
+use rocket::serde::Serialize;
+use std::collections::VecDeque;
+
+/// One basic feasible solution of the MODI method: the potentials, the
+/// reduced costs, the shipments and the stepping-stone pivot taken from it.
+/// The first state holds the initial solution, the last one the optimum,
+/// which has no pivot.
+#[derive(Debug, Clone, Serialize)]
+pub struct TransportationState<N: Cost> {
+        // u = (u_1, u_2, ..., u_m), u_1 = 0
+        pub u: Vec<N>,
+
+        // v = (v_1, v_2, ..., v_n), u_i + v_j = c_i,j on the basic cells
+        pub v: Vec<N>,
+
+        // c = reduced costs c_i,j - u_i - v_j, zero on the basic cells.
+        // The solution is optimal when none is negative.
+        pub c: DMatrix<N>,
+
+        // x = shipments, non-zero on basic cells only
+        pub x: DMatrix<N>,
+
+        // The m + n - 1 basic cells, a spanning tree of the rows and columns.
+        pub basis: Vec<(usize, usize)>,
+
+        // The first cell, by row and column, with a negative reduced cost, if any.
+        pub entering: Option<(usize, usize)>,
+
+        // Stepping-stone loop: the entering cell, then the basic cells that lose
+        // and gain θ in turn.
+        pub cycle: Option<Vec<(usize, usize)>>,
+
+        // θ = smallest shipment on the losing cells of the loop.
+        pub theta: Option<N>,
+
+        // The first losing cell, by row and column, whose shipment drops to
+        // zero; it leaves the basis.
+        pub leaving: Option<(usize, usize)>,
+}
+
+/// Optimal shipments of a transportation problem.
+///
+/// `u` and `v` are the final potentials: u_i + v_j <= c_i,j for every cell,
+/// with equality on the cells that ship, so `cost` equals
+/// ∑ supply_i u_i + ∑ demand_j v_j. `states` is the full trace of the MODI
+/// method.
+#[derive(Debug, Clone, Serialize)]
+pub struct TransportationSolution<N: Cost> {
+        /// The (row, column, amount) of every cell that ships a non-zero amount,
+        /// ordered by row and column.
+        pub shipments: Vec<(usize, usize, N)>,
+        pub cost: N,
+        pub u: Vec<N>,
+        pub v: Vec<N>,
+        /// The supply of each row that is not shipped, when the supply exceeds the demand.
+        pub unused_supply: Vec<N>,
+        /// The demand of each column that is not met, when the demand exceeds the supply.
+        pub unmet_demand: Vec<N>,
+        /// The side to which a zero cost dummy row or column was added to balance
+        /// supply and demand; the states include it.
+        pub dummy: Option<Side>,
+        /// Number of stepping-stone pivots.
+        pub iterations: usize,
+        pub states: Vec<TransportationState<N>>,
+}
+
+/// Largest number of pivots [`solve_transportation`] makes before it gives up.
+pub const MAX_ITERATIONS: usize = 10_000;
+
+/// Solves the transportation problem: ship `supply[i]` units from row i and
+/// `demand[j]` units to column j at the unit costs `c` as cheaply as
+/// possible.
+///
+/// If supply and demand differ, a dummy column (or row) with zero costs takes
+/// up the difference. An initial basic feasible solution is built with
+/// `method`, then the MODI (u-v) method takes over: the potentials are solved
+/// from u_i + v_j = c_i,j on the basic cells, as the duals of the assignment
+/// problem, and while some cell has a negative reduced cost c_i,j - u_i - v_j
+/// it enters the basis along its stepping-stone loop, which moves θ units
+/// around the loop and drops a cell whose shipment reaches zero.
+///
+/// A degenerate solution keeps basic cells with zero shipments, both in the
+/// initial solution and when several cells of a loop reach zero at once
+/// (only one leaves), so the basis always has m + n - 1 cells. A pivot with
+/// θ = 0 then changes the basis but not the shipments, and could lead back to
+/// an earlier basis. Bland's rule rules that out: the first cell by row and
+/// column with a negative reduced cost enters, and of the losing cells that
+/// reach zero the first by row and column leaves. Should the method still not
+/// finish within [`MAX_ITERATIONS`] pivots, it fails with
+/// [`TransportationError::IterationLimit`].
+///
+/// Potentials and reduced costs are kept in the cost type and take negative
+/// values, so an unsigned cost type is rejected with
+/// [`TransportationError::InvalidInput`].
+pub fn solve_transportation<N: Cost>(supply: &[N], demand: &[N], c: &DMatrix<N>, method: InitialMethod) -> Result<TransportationSolution<N>, TransportationError> {
+        let (nrows, ncols) = c.shape();
+        check_input(supply, demand, c)?;
+
+        // Balance supply and demand with a zero cost dummy column or row.
+        let total_supply = checked(sum_cost(supply.iter().copied()), "total supply")?;
+        let total_demand = checked(sum_cost(demand.iter().copied()), "total demand")?;
+        let (mut supply, mut demand, mut c) = (supply.to_vec(), demand.to_vec(), c.clone());
+        let mut dummy = None;
+        if total_supply > total_demand {
+                let excess = checked(total_supply.checked_sub(total_demand), "total supply - total demand")?;
+                if !excess.is_zero() {
+                        demand.push(excess);
+                        c = c.insert_column(ncols, N::zero());
+                        dummy = Some(Side::Columns);
+                }
+        } else {
+                let shortage = checked(total_demand.checked_sub(total_supply), "total demand - total supply")?;
+                if !shortage.is_zero() {
+                        supply.push(shortage);
+                        c = c.insert_row(nrows, N::zero());
+                        dummy = Some(Side::Rows);
+                }
+        }
+
+        let (mut x, mut basis) = initial_solution(&supply, &demand, &c, method)?;
+        let mut states = Vec::new();
+        let mut iterations = 0;
+        loop {
+                if iterations == MAX_ITERATIONS {
+                        return Err(TransportationError::IterationLimit { iterations });
+                }
+                let (u, v) = potentials(&c, &basis)?;
+                let reduced = reduced_costs(&c, &u, &v, &basis)?;
+
+                let mut state = TransportationState {
+                        u,
+                        v,
+                        c: reduced,
+                        x: x.clone(),
+                        basis: basis.clone(),
+                        entering: None,
+                        cycle: None,
+                        theta: None,
+                        leaving: None,
+                };
+                let entering = match entering_cell(&state.c) {
+                        Some(entering) => entering,
+                        None => {
+                                states.push(state);
+                                break;
+                        }
+                };
+
+                let cycle = stepping_stone(&basis, entering, c.nrows(), c.ncols());
+                // Of the losing cells with the smallest shipment the first by row and
+                // column leaves, the others that reach zero stay basic.
+                let losing = cycle.iter().skip(1).step_by(2).fold(None, |acc: Option<((usize, usize), N)>, &cell| match acc {
+                        Some((first, theta)) if theta < x[cell] || (theta <= x[cell] && first < cell) => acc,
+                        _ => Some((cell, x[cell])),
+                });
+                let (leaving, theta) = match losing {
+                        Some(losing) => losing,
+                        // The basis spans all rows and columns, so every loop has losing cells.
+                        None => {
+                                states.push(state);
+                                break;
+                        }
+                };
+
+                for (k, &cell) in cycle.iter().enumerate() {
+                        x[cell] = if k % 2 == 0 { checked(x[cell].checked_add(theta), "x_i,j + θ")? } else { checked(x[cell].checked_sub(theta), "x_i,j - θ")? };
+                }
+                x[leaving] = N::zero();
+                if let Some(position) = basis.iter().position(|&cell| cell == leaving) {
+                        basis[position] = entering;
+                }
+
+                state.entering = Some(entering);
+                state.cycle = Some(cycle);
+                state.theta = Some(theta);
+                state.leaving = Some(leaving);
+                states.push(state);
+                iterations += 1;
+        }
+
+        finish(states, c, dummy, nrows, ncols, iterations)
+}
+
+// Rejects an unsigned cost type, an empty `c`, quantities that do not fit its
+// shape and negative quantities.
+fn check_input<N: Cost>(supply: &[N], demand: &[N], c: &DMatrix<N>) -> Result<(), TransportationError> {
+        if N::from_usize(1).and_then(|one| N::zero().checked_sub(one)).is_none() {
+                return Err(TransportationError::InvalidInput {
+                        details: "potentials and reduced costs can be negative, use a signed cost type".to_string(),
+                });
+        }
+        let (nrows, ncols) = c.shape();
+        if nrows == 0 || ncols == 0 {
+                return Err(TransportationError::EmptyMatrix);
+        }
+        for (side, quantities, expected) in [(Side::Rows, supply, nrows), (Side::Columns, demand, ncols)] {
+                if quantities.len() != expected {
+                        return Err(TransportationError::LengthMismatch {
+                                side,
+                                expected,
+                                found: quantities.len(),
+                        });
+                }
+                if let Some(index) = quantities.iter().position(|&q| q < N::zero()) {
+                        return Err(TransportationError::NegativeQuantity { side, index });
+                }
+        }
+        Ok(())
+}
+
+// Potentials with u_1 = 0 and u_i + v_j = c_i,j on the basic cells, solved
+// outwards from row 0 along the spanning tree of the basis.
+fn potentials<N: Cost>(c: &DMatrix<N>, basis: &[(usize, usize)]) -> Result<(Vec<N>, Vec<N>), TransportationError> {
+        let (nrows, ncols) = c.shape();
+        let mut u: Vec<Option<N>> = vec![None; nrows];
+        let mut v: Vec<Option<N>> = vec![None; ncols];
+        u[0] = Some(N::zero());
+
+        let mut queue = VecDeque::from([0]);
+        while let Some(node) = queue.pop_front() {
+                for &(i, j) in basis {
+                        if node == i && v[j].is_none() {
+                                let u_i = u[i].unwrap_or_else(N::zero);
+                                v[j] = Some(checked(c[(i, j)].checked_sub(u_i), "c_ij - u_i")?);
+                                queue.push_back(nrows + j);
+                        } else if node == nrows + j && u[i].is_none() {
+                                let v_j = v[j].unwrap_or_else(N::zero);
+                                u[i] = Some(checked(c[(i, j)].checked_sub(v_j), "c_ij - v_j")?);
+                                queue.push_back(i);
+                        }
+                }
+        }
+        Ok((u.into_iter().map(|x| x.unwrap_or_else(N::zero)).collect(), v.into_iter().map(|x| x.unwrap_or_else(N::zero)).collect()))
+}
+
+// c_i,j - u_i - v_j for every cell, exactly zero on the basic cells.
+fn reduced_costs<N: Cost>(c: &DMatrix<N>, u: &[N], v: &[N], basis: &[(usize, usize)]) -> Result<DMatrix<N>, TransportationError> {
+        let mut reduced = c.clone();
+        for i in 0..c.nrows() {
+                for j in 0..c.ncols() {
+                        reduced[(i, j)] = checked(c[(i, j)].checked_sub(u[i]).and_then(|x| x.checked_sub(v[j])), "reduced cost")?;
+                }
+        }
+        for &cell in basis {
+                reduced[cell] = N::zero();
+        }
+        Ok(reduced)
+}
+
+// The first cell by row and column with a negative reduced cost, as Bland's
+// rule picks it. Floating point reduced costs within `Cost::tolerance()` of
+// zero do not count.
+fn entering_cell<N: Cost>(reduced: &DMatrix<N>) -> Option<(usize, usize)> {
+        (0..reduced.nrows()).flat_map(|i| (0..reduced.ncols()).map(move |j| (i, j))).find(|&cell| reduced[cell] < N::zero() && !reduced[cell].is_zero())
+}
+
+// The loop that the entering cell (i, j) closes in the spanning tree of the
+// basis: the tree path from column j back to row i, as cells, after (i, j).
+// Rows are the nodes 0..m and columns the nodes m..m+n, as in the equality graph.
+fn stepping_stone(basis: &[(usize, usize)], entering: (usize, usize), nrows: usize, ncols: usize) -> Vec<(usize, usize)> {
+        let (row, col) = entering;
+        let start = nrows + col;
+        let target = row;
+
+        // Breadth first search from column j, remembering the cell each node was reached by.
+        let mut reached_by: Vec<Option<(usize, usize)>> = vec![None; nrows + ncols];
+        let mut seen = vec![false; reached_by.len()];
+        seen[start] = true;
+        let mut queue = VecDeque::from([start]);
+        while let Some(node) = queue.pop_front() {
+                if node == target {
+                        break;
+                }
+                for &(i, j) in basis {
+                        let next = if node == i {
+                                nrows + j
+                        } else if node == nrows + j {
+                                i
+                        } else {
+                                continue;
+                        };
+                        if !seen[next] {
+                                seen[next] = true;
+                                reached_by[next] = Some((i, j));
+                                queue.push_back(next);
+                        }
+                }
+        }
+
+        // Walk back from row i to column j.
+        let mut path = Vec::new();
+        let mut node = target;
+        while let Some((i, j)) = reached_by[node] {
+                path.push((i, j));
+                node = if node == i { nrows + j } else { i };
+        }
+        path.reverse();
+
+        let mut cycle = vec![entering];
+        cycle.extend(path);
+        cycle
+}
+
+// Reports the last state in terms of the original problem: the dummy line is
+// dropped after its potential has been moved onto the real potentials, which
+// keeps every u_i + v_j and gives the potentials of the unbalanced problem.
+fn finish<N: Cost>(states: Vec<TransportationState<N>>, c: DMatrix<N>, dummy: Option<Side>, nrows: usize, ncols: usize, iterations: usize) -> Result<TransportationSolution<N>, TransportationError> {
+        let final_state = states.last().ok_or(TransportationError::EmptyMatrix)?;
+        let (mut u, mut v) = (final_state.u.clone(), final_state.v.clone());
+        match dummy {
+                Some(Side::Columns) => {
+                        let t = v[ncols];
+                        u = u.iter().map(|&u_i| checked(u_i.checked_add(t), "u_i + t")).collect::<Result<_, _>>()?;
+                        v = v.iter().map(|&v_j| checked(v_j.checked_sub(t), "v_j - t")).collect::<Result<_, _>>()?;
+                }
+                Some(Side::Rows) => {
+                        let t = u[nrows];
+                        u = u.iter().map(|&u_i| checked(u_i.checked_sub(t), "u_i - t")).collect::<Result<_, _>>()?;
+                        v = v.iter().map(|&v_j| checked(v_j.checked_add(t), "v_j + t")).collect::<Result<_, _>>()?;
+                }
+                None => {}
+        }
+        u.truncate(nrows);
+        v.truncate(ncols);
+
+        let x = &final_state.x;
+        let mut shipments = Vec::new();
+        for i in 0..nrows {
+                for j in 0..ncols {
+                        if !x[(i, j)].is_zero() {
+                                shipments.push((i, j, x[(i, j)]));
+                        }
+                }
+        }
+        let costs = shipments.iter().map(|&(i, j, amount)| checked(c[(i, j)].checked_mul(amount), "c_ij x_ij")).collect::<Result<Vec<_>, _>>()?;
+        let cost = checked(sum_cost(costs), "total cost")?;
+        let unused_supply = (0..nrows).map(|i| if dummy == Some(Side::Columns) { x[(i, ncols)] } else { N::zero() }).collect();
+        let unmet_demand = (0..ncols).map(|j| if dummy == Some(Side::Rows) { x[(nrows, j)] } else { N::zero() }).collect();
+        Ok(TransportationSolution {
+                cost,
+                shipments,
+                u,
+                v,
+                unused_supply,
+                unmet_demand,
+                dummy,
+                iterations,
+                states,
+        })
+}
@@ -7,6 +7,8 @@
 // Modifications in this version:
 // - The original `is_bipartite_undirected` assumed a connected graph.
 // - Adjusted the logic to correctly handle disconnected bipartite graphs.
+// - `bipartite_undirected` colours each component by BFS and keeps isolated
+//   nodes; `is_bipartite_undirected` uses it.
 // - Modified selected private methods to make them public for integration.
 // - Additional minor changes for compatibility with this project.
 //
@@ -16,6 +18,7 @@
 extern crate petgraph;
 
 use petgraph::visit::{GraphRef, IntoNeighbors, IntoNodeIdentifiers, VisitMap, Visitable};
+use std::collections::VecDeque;
 
 pub struct BipartiteGraph<N> {
         pub nodes_u: Vec<N>,
@@ -26,7 +29,7 @@ pub struct BipartiteGraph<N> {
 ///
 /// A graph is bipartite if it's nodes can be divided into two disjoint and indepedent
 /// sets `U` and `V` such that every edge connects `U` to one in `V`.
-/// This function implements 2-coloring algorithm based on BFS.
+/// This function implements 2-coloring algorithm based on BFS, see [`bipartite_undirected`].
 ///
 /// The input graph is always treated as undirected.
 pub fn is_bipartite_undirected<G, N, VM>(g: G) -> bool
@@ -35,46 +38,18 @@ where
         N: Copy + PartialEq + std::fmt::Debug,
         VM: VisitMap<N>,
 {
-        let node_ids: Vec<N> = g.node_identifiers().map(|id| id).collect();
-
-        let mut red = g.visit_map();
-        let mut blue = g.visit_map();
-
-        for node in node_ids {
-                for neighbor in g.neighbors(node) {
-                        let is_blue = blue.is_visited(&node);
-                        let is_red = red.is_visited(&node);
-                        let is_neighbor_blue = blue.is_visited(&neighbor);
-                        let is_neighbor_red = red.is_visited(&neighbor);
-
-                        match (is_blue, is_red, is_neighbor_blue, is_neighbor_red) {
-                                (false, false, false, false) => {
-                                        blue.visit(node);
-                                        red.visit(neighbor);
-                                }
-                                (false, false, false, true) => {
-                                        blue.visit(node);
-                                }
-                                (false, false, true, false) => {
-                                        red.visit(node);
-                                }
-                                (true, false, false, false) => {
-                                        red.visit(neighbor);
-                                }
-                                (true, false, false, true) => {
-                                        continue;
-                                }
-                                (false, true, true, false) => {
-                                        continue;
-                                }
-                                (_, _, _, _) => return false,
-                        }
-                }
-        }
-
-        true
+        bipartite_undirected(g).is_some()
 }
 
+/// The sides `U` and `V` of a bipartite graph, `None` if the graph is not bipartite.
+///
+/// Each connected component is 2-coloured by BFS from its first node, which goes
+/// to `U`. An isolated node is a component of its own and so lies in `U`. A
+/// self-loop makes the graph non-bipartite.
+///
+/// Only the `neighbors` of each node are followed, which an undirected graph
+/// reports in both directions; a directed graph needs every edge in both
+/// directions to be coloured as undirected.
 pub fn bipartite_undirected<G, N, VM>(g: G) -> Option<BipartiteGraph<N>>
 where
         G: GraphRef + Visitable<NodeId = N, Map = VM> + IntoNeighbors<NodeId = N> + IntoNodeIdentifiers<NodeId = N>,
@@ -83,31 +58,30 @@ where
 {
         let mut nodes_u: Vec<N> = Vec::new();
         let mut nodes_v: Vec<N> = Vec::new();
-        let r_nodes: Vec<N> = g.node_identifiers().map(|x| x).collect();
-        // println!("r_nodes = {:?}", r_nodes);
 
         let mut blue = g.visit_map();
         let mut red = g.visit_map();
 
-        for node in r_nodes {
-                for neighbor in g.neighbors(node) {
+        for root in g.node_identifiers() {
+                if blue.is_visited(&root) || red.is_visited(&root) {
+                        continue;
+                }
+                blue.visit(root);
+                let mut queue = VecDeque::from([root]);
+                while let Some(node) = queue.pop_front() {
                         let is_blue = blue.is_visited(&node);
-                        let is_red = red.is_visited(&node);
-                        let is_neighbor_blue = blue.is_visited(&neighbor);
-                        let is_neighbor_red = red.is_visited(&neighbor);
-                        match (is_blue, is_red, is_neighbor_blue, is_neighbor_red) {
-                                (false, false, false, false) => {
-                                        blue.visit(node);
-                                        red.visit(neighbor);
-                                }
-                                (true, false, false, false) => {
-                                        red.visit(neighbor);
-                                }
-                                (false, false, false, true) => {
-                                        blue.visit(node);
-                                }
-                                (_, _, _, _) => {
-                                        continue;
+                        for neighbor in g.neighbors(node) {
+                                match (blue.is_visited(&neighbor), red.is_visited(&neighbor)) {
+                                        (false, false) => {
+                                                if is_blue {
+                                                        red.visit(neighbor);
+                                                } else {
+                                                        blue.visit(neighbor);
+                                                }
+                                                queue.push_back(neighbor);
+                                        }
+                                        (is_neighbor_blue, _) if is_neighbor_blue == is_blue => return None,
+                                        (_, _) => continue,
                                 }
                         }
                 }
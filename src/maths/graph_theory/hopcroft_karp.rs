@@ -0,0 +1,166 @@
+use crate::graph_theory::bipartite::BipartiteGraph;
+use petgraph::visit::{GraphRef, IntoNeighbors};
+use rocket::serde::Serialize;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// One phase of [`hopcroft_karp`]: the layers of the breadth-first search
+/// and the augmenting paths found along them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HopcroftKarpPhase<N> {
+        /// The free vertices of U, then alternately the vertices of V reached
+        /// from the previous layer by an edge outside the matching and their
+        /// partners in U, up to the first layer of V with a free vertex. Without
+        /// such a layer the matching is maximum and the phase is the last one.
+        pub layers: Vec<Vec<N>>,
+        /// Vertex-disjoint shortest augmenting paths, each from a free vertex of
+        /// U to a free vertex of V, by which the matching grows in this phase.
+        pub augmenting_paths: Vec<Vec<N>>,
+}
+
+/// A maximum matching found by [`hopcroft_karp`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HopcroftKarpMatching<N> {
+        /// Matched (u, v) pairs, u in U and v in V, in the order of U.
+        pub matching: Vec<(N, N)>,
+        pub phases: Vec<HopcroftKarpPhase<N>>,
+}
+
+/// Hopcroft–Karp maximum matching of a bipartite graph with the sides
+/// `sides`, e.g. from [`bipartite_undirected`](super::bipartite_undirected).
+///
+/// Every phase grows the layers of a breadth-first search from the free
+/// vertices of U, along edges outside the matching to V and along matching
+/// edges back to U, until a free vertex of V is reached. Depth-first searches
+/// along the layers then take a maximal set of vertex-disjoint shortest
+/// augmenting paths. The paths get longer from phase to phase, and there are
+/// O(√n) phases of O(m) each.
+///
+/// Neighbours are tried in the order of `sides.nodes_v`, so the result does not
+/// depend on the order of the edges. Edges inside a side are ignored.
+pub fn hopcroft_karp<G, N>(g: G, sides: &BipartiteGraph<N>) -> HopcroftKarpMatching<N>
+where
+        G: GraphRef + IntoNeighbors<NodeId = N>,
+        N: Copy + Eq + Hash,
+{
+        let position_v: HashMap<N, usize> = sides.nodes_v.iter().enumerate().map(|(j, &v)| (v, j)).collect();
+        let adjacent: Vec<Vec<usize>> = sides
+                .nodes_u
+                .iter()
+                .map(|&u| {
+                        let mut vs: Vec<usize> = g.neighbors(u).filter_map(|v| position_v.get(&v).copied()).collect();
+                        vs.sort_unstable();
+                        vs.dedup();
+                        vs
+                })
+                .collect();
+
+        let mut search = Search {
+                adjacent,
+                partner_u: vec![None; sides.nodes_u.len()],
+                partner_v: vec![None; sides.nodes_v.len()],
+                layer_u: Vec::new(),
+                layer_v: Vec::new(),
+                visited_v: Vec::new(),
+        };
+        let mut phases = Vec::new();
+        loop {
+                let (layers, found) = search.layers();
+                let mut augmenting_paths = Vec::new();
+                if found {
+                        for i in 0..sides.nodes_u.len() {
+                                if search.partner_u[i].is_none() && search.layer_u[i] == Some(0) {
+                                        if let Some(path) = search.augment(i) {
+                                                augmenting_paths.push(path);
+                                        }
+                                }
+                        }
+                }
+                phases.push(HopcroftKarpPhase {
+                        layers: layers.into_iter().enumerate().map(|(k, layer)| layer.into_iter().map(|x| if k % 2 == 0 { sides.nodes_u[x] } else { sides.nodes_v[x] }).collect()).collect(),
+                        augmenting_paths: augmenting_paths.into_iter().map(|path| path.into_iter().enumerate().map(|(k, x)| if k % 2 == 0 { sides.nodes_u[x] } else { sides.nodes_v[x] }).collect()).collect(),
+                });
+                if !found {
+                        break;
+                }
+        }
+
+        let matching = search.partner_u.iter().enumerate().filter_map(|(i, j)| j.map(|j| (sides.nodes_u[i], sides.nodes_v[j]))).collect();
+        HopcroftKarpMatching { matching, phases }
+}
+
+// The matching and the layers of the current phase, by position in U and V.
+struct Search {
+        adjacent: Vec<Vec<usize>>,
+        partner_u: Vec<Option<usize>>,
+        partner_v: Vec<Option<usize>>,
+        // The layer of each vertex, counting U and V layers together.
+        layer_u: Vec<Option<usize>>,
+        layer_v: Vec<Option<usize>>,
+        visited_v: Vec<bool>,
+}
+
+impl Search {
+        // The breadth-first layers, alternately of U and V, and whether the last
+        // layer holds a free vertex of V.
+        fn layers(&mut self) -> (Vec<Vec<usize>>, bool) {
+                self.layer_u = self.partner_u.iter().map(|j| if j.is_none() { Some(0) } else { None }).collect();
+                self.layer_v = vec![None; self.partner_v.len()];
+                self.visited_v = vec![false; self.partner_v.len()];
+
+                let mut layers = vec![(0..self.partner_u.len()).filter(|&i| self.partner_u[i].is_none()).collect::<Vec<_>>()];
+                loop {
+                        let depth = layers.len();
+                        let mut reached = Vec::new();
+                        for &i in &layers[depth - 1] {
+                                for &j in &self.adjacent[i] {
+                                        if self.layer_v[j].is_none() {
+                                                self.layer_v[j] = Some(depth);
+                                                reached.push(j);
+                                        }
+                                }
+                        }
+                        if reached.is_empty() {
+                                return (layers, false);
+                        }
+                        let found = reached.iter().any(|&j| self.partner_v[j].is_none());
+                        let partners: Vec<usize> = reached.iter().filter_map(|&j| self.partner_v[j]).collect();
+                        layers.push(reached);
+                        if found {
+                                return (layers, true);
+                        }
+                        for &i in &partners {
+                                self.layer_u[i] = Some(depth + 1);
+                        }
+                        layers.push(partners);
+                }
+        }
+
+        // A shortest augmenting path from `i` along the layers, alternately of U
+        // and V, applied to the matching.
+        fn augment(&mut self, i: usize) -> Option<Vec<usize>> {
+                let depth = self.layer_u[i]?;
+                for k in 0..self.adjacent[i].len() {
+                        let j = self.adjacent[i][k];
+                        if self.visited_v[j] || self.layer_v[j] != Some(depth + 1) {
+                                continue;
+                        }
+                        self.visited_v[j] = true;
+                        let rest = match self.partner_v[j] {
+                                None => Some(Vec::new()),
+                                Some(next) if self.layer_u[next] == Some(depth + 2) => self.augment(next),
+                                Some(_) => None,
+                        };
+                        if let Some(rest) = rest {
+                                self.partner_u[i] = Some(j);
+                                self.partner_v[j] = Some(i);
+                                let mut path = vec![i, j];
+                                path.extend(rest);
+                                return Some(path);
+                        }
+                }
+                // Nothing is left to reach from `i` in this phase.
+                self.layer_u[i] = None;
+                None
+        }
+}
@@ -0,0 +1,140 @@
+use crate::graph_theory::bipartite::BipartiteGraph;
+use crate::graph_theory::hopcroft_karp::hopcroft_karp;
+use petgraph::visit::{GraphRef, IntoNeighbors};
+use rocket::serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+/// A maximum matching of a bipartite graph with a minimum vertex cover and a
+/// maximum independent set, split by side, as given by König's theorem.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct KonigCover<N> {
+        /// Matched (u, v) pairs, u in U and v in V, in the order of U.
+        pub matching: Vec<(N, N)>,
+        /// The cover: every edge has an end in `cover_u` or `cover_v`, and there
+        /// are as many of them as matched edges.
+        pub cover_u: Vec<N>,
+        pub cover_v: Vec<N>,
+        /// The vertices outside the cover, no two of them adjacent.
+        pub independent_u: Vec<N>,
+        pub independent_v: Vec<N>,
+}
+
+/// One way in which a [`KonigCover`] fails to be a certificate.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "violation")]
+pub enum KonigViolation<N> {
+        /// The matched pair (`u`, `v`) is not an edge of the graph.
+        NotAnEdge { u: N, v: N },
+
+        /// `vertex` is matched more than once.
+        MatchedTwice { vertex: N },
+
+        /// Neither end of the edge (`u`, `v`) is in the cover.
+        UncoveredEdge { u: N, v: N },
+
+        /// `vertex` is in both the cover and the independent set, or in neither.
+        NotComplementary { vertex: N },
+
+        /// The cover has `cover` vertices but the matching `matching` edges, so
+        /// neither is proven optimal.
+        SizeMismatch { matching: usize, cover: usize },
+}
+
+/// König's theorem for the bipartite graph `g` with the sides `sides`: a
+/// maximum matching M by [`hopcroft_karp`] and a minimum vertex cover K with
+/// |K| = |M|, whose complement is a maximum independent set.
+///
+/// Let Z be the vertices reached from the free vertices of U by alternating
+/// paths, along edges outside M from U to V and along edges of M back to U.
+/// Then K = (U \ Z) ∪ (V ∩ Z): an edge from U ∩ Z leads into Z, and every
+/// vertex of K is matched, no two of them to each other.
+///
+/// Vertices outside `sides` are ignored, like edges inside a side; those are
+/// reported by [`verify_konig`].
+pub fn konig_cover<G, N>(g: G, sides: &BipartiteGraph<N>) -> KonigCover<N>
+where
+        G: GraphRef + IntoNeighbors<NodeId = N>,
+        N: Copy + Eq + Hash,
+{
+        let matching = hopcroft_karp(g, sides).matching;
+        let in_v: HashSet<N> = sides.nodes_v.iter().copied().collect();
+        let partner_of_v: HashMap<N, N> = matching.iter().map(|&(u, v)| (v, u)).collect();
+        let matched_u: HashSet<N> = matching.iter().map(|&(u, _)| u).collect();
+
+        let mut reached: HashSet<N> = sides.nodes_u.iter().filter(|u| !matched_u.contains(u)).copied().collect();
+        let mut queue: VecDeque<N> = reached.iter().copied().collect();
+        while let Some(u) = queue.pop_front() {
+                for v in g.neighbors(u).filter(|v| in_v.contains(v)) {
+                        if !reached.insert(v) {
+                                continue;
+                        }
+                        // A vertex of V reached from a free vertex is matched, or M would not be maximum.
+                        if let Some(&partner) = partner_of_v.get(&v) {
+                                if reached.insert(partner) {
+                                        queue.push_back(partner);
+                                }
+                        }
+                }
+        }
+
+        let (independent_u, cover_u) = sides.nodes_u.iter().partition(|u| reached.contains(u));
+        let (cover_v, independent_v) = sides.nodes_v.iter().partition(|v| reached.contains(v));
+        KonigCover {
+                matching,
+                cover_u,
+                cover_v,
+                independent_u,
+                independent_v,
+        }
+}
+
+/// Checks a [`KonigCover`] against the graph, independently of how it was
+/// found: the matching uses edges of `g` and every vertex at most once, the
+/// cover covers every edge between vertices of `sides`, the independent set
+/// is its complement and |M| = |K|. An empty list proves that the matching is
+/// maximum and the cover minimum.
+pub fn verify_konig<G, N>(g: G, sides: &BipartiteGraph<N>, cover: &KonigCover<N>) -> Vec<KonigViolation<N>>
+where
+        G: GraphRef + IntoNeighbors<NodeId = N>,
+        N: Copy + Eq + Hash,
+{
+        let mut violations = Vec::new();
+        let mut matched = HashSet::new();
+        for &(u, v) in &cover.matching {
+                if !g.neighbors(u).any(|x| x == v) {
+                        violations.push(KonigViolation::NotAnEdge { u, v });
+                }
+                for vertex in [u, v] {
+                        if !matched.insert(vertex) {
+                                violations.push(KonigViolation::MatchedTwice { vertex });
+                        }
+                }
+        }
+
+        let in_cover: HashSet<N> = cover.cover_u.iter().chain(&cover.cover_v).copied().collect();
+        let independent: HashSet<N> = cover.independent_u.iter().chain(&cover.independent_v).copied().collect();
+        let vertices: Vec<N> = sides.nodes_u.iter().chain(&sides.nodes_v).copied().collect();
+        let in_sides: HashSet<N> = vertices.iter().copied().collect();
+        let mut checked = HashSet::new();
+        for &x in &vertices {
+                if in_cover.contains(&x) == independent.contains(&x) {
+                        violations.push(KonigViolation::NotComplementary { vertex: x });
+                }
+                checked.insert(x);
+                for y in g.neighbors(x) {
+                        // Each edge once, from the end listed first.
+                        if in_sides.contains(&y) && !checked.contains(&y) && !in_cover.contains(&x) && !in_cover.contains(&y) {
+                                violations.push(KonigViolation::UncoveredEdge { u: x, v: y });
+                        }
+                }
+        }
+
+        if in_cover.len() != cover.matching.len() {
+                violations.push(KonigViolation::SizeMismatch {
+                        matching: cover.matching.len(),
+                        cover: in_cover.len(),
+                });
+        }
+        violations
+}
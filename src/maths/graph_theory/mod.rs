@@ -1,5 +1,9 @@
 pub use bipartite::bipartite_undirected;
 pub use bipartite::is_bipartite_undirected;
 pub use bipartite::BipartiteGraph;
+pub use hopcroft_karp::{hopcroft_karp, HopcroftKarpMatching, HopcroftKarpPhase};
+pub use konig::{konig_cover, verify_konig, KonigCover, KonigViolation};
 
 pub mod bipartite;
+pub mod hopcroft_karp;
+pub mod konig;
@@ -1,2 +1,3 @@
 pub mod comb_optimization;
 pub mod graph_theory;
+pub mod number_theory;
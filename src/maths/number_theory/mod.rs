@@ -0,0 +1,3 @@
+pub use rational::{ParseRationalError, Rational};
+
+pub mod rational;
@@ -0,0 +1,187 @@
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::{Serialize, Serializer};
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// An exact fraction p/q of two `i64`, kept in lowest terms with q > 0.
+///
+/// The arithmetic is checked: a result whose numerator or denominator does
+/// not fit into an `i64` in lowest terms is `None`, like an overflowing
+/// integer operation. Intermediate products are taken in `i128`.
+///
+/// Written and read as a string, "p/q", "p" or a finite decimal such as
+/// "0.25"; JSON integers and floats are read as well, a float by its
+/// shortest decimal representation, so that 0.1 is read as 1/10.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+        numer: i64,
+        denom: i64,
+}
+
+impl Rational {
+        /// `numer`/`denom` in lowest terms, `None` if `denom` is zero.
+        pub fn new(numer: i64, denom: i64) -> Option<Self> {
+                Self::from_i128(numer as i128, denom as i128)
+        }
+
+        pub fn from_integer(n: i64) -> Self {
+                Rational { numer: n, denom: 1 }
+        }
+
+        pub fn numer(self) -> i64 {
+                self.numer
+        }
+
+        /// The denominator, always positive.
+        pub fn denom(self) -> i64 {
+                self.denom
+        }
+
+        pub fn checked_add(self, rhs: Self) -> Option<Self> {
+                let numer = self.numer as i128 * rhs.denom as i128 + rhs.numer as i128 * self.denom as i128;
+                Self::from_i128(numer, self.denom as i128 * rhs.denom as i128)
+        }
+
+        pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+                let numer = self.numer as i128 * rhs.denom as i128 - rhs.numer as i128 * self.denom as i128;
+                Self::from_i128(numer, self.denom as i128 * rhs.denom as i128)
+        }
+
+        pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+                Self::from_i128(self.numer as i128 * rhs.numer as i128, self.denom as i128 * rhs.denom as i128)
+        }
+
+        /// `None` when dividing by zero.
+        pub fn checked_div(self, rhs: Self) -> Option<Self> {
+                Self::from_i128(self.numer as i128 * rhs.denom as i128, self.denom as i128 * rhs.numer as i128)
+        }
+
+        pub fn to_f64(self) -> f64 {
+                self.numer as f64 / self.denom as f64
+        }
+
+        // numer/denom in lowest terms with a positive denominator, if both fit.
+        fn from_i128(numer: i128, denom: i128) -> Option<Self> {
+                if denom == 0 {
+                        return None;
+                }
+                let divisor = gcd(numer.unsigned_abs(), denom.unsigned_abs()) as i128;
+                let (mut numer, mut denom) = (numer / divisor, denom / divisor);
+                if denom < 0 {
+                        numer = -numer;
+                        denom = -denom;
+                }
+                Some(Rational {
+                        numer: i64::try_from(numer).ok()?,
+                        denom: i64::try_from(denom).ok()?,
+                })
+        }
+}
+
+// Greatest common divisor, with gcd(0, b) = b.
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+        while b != 0 {
+                (a, b) = (b, a % b);
+        }
+        a
+}
+
+impl Ord for Rational {
+        fn cmp(&self, other: &Self) -> Ordering {
+                (self.numer as i128 * other.denom as i128).cmp(&(other.numer as i128 * self.denom as i128))
+        }
+}
+
+impl PartialOrd for Rational {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+        }
+}
+
+impl fmt::Display for Rational {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                if self.denom == 1 {
+                        write!(f, "{}", self.numer)
+                } else {
+                        write!(f, "{}/{}", self.numer, self.denom)
+                }
+        }
+}
+
+/// A string that is neither "p/q", nor an integer, nor a finite decimal
+/// whose fraction fits into a [`Rational`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseRationalError {
+        pub input: String,
+}
+
+impl fmt::Display for ParseRationalError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{:?} is not a fraction p/q or a decimal number", self.input)
+        }
+}
+
+impl Error for ParseRationalError {}
+
+impl FromStr for Rational {
+        type Err = ParseRationalError;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let err = || ParseRationalError { input: s.to_string() };
+                let s = s.trim();
+                if let Some((numer, denom)) = s.split_once('/') {
+                        let numer = numer.trim().parse().map_err(|_| err())?;
+                        let denom = denom.trim().parse().map_err(|_| err())?;
+                        return Rational::new(numer, denom).ok_or_else(err);
+                }
+
+                // A decimal: the digits without the point over a power of ten.
+                let (whole, fraction) = s.split_once('.').unwrap_or((s, ""));
+                if !fraction.chars().all(|c| c.is_ascii_digit()) {
+                        return Err(err());
+                }
+                let digits: i128 = format!("{}{}", whole, fraction).parse().map_err(|_| err())?;
+                let denom = 10i128.checked_pow(fraction.len() as u32).ok_or_else(err)?;
+                Rational::from_i128(digits, denom).ok_or_else(err)
+        }
+}
+
+impl Serialize for Rational {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+        }
+}
+
+impl<'de> Deserialize<'de> for Rational {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                deserializer.deserialize_any(RationalVisitor)
+        }
+}
+
+struct RationalVisitor;
+
+impl Visitor<'_> for RationalVisitor {
+        type Value = Rational;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a fraction \"p/q\", an integer or a decimal number")
+        }
+
+        fn visit_i64<E: de::Error>(self, v: i64) -> Result<Rational, E> {
+                Ok(Rational::from_integer(v))
+        }
+
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<Rational, E> {
+                i64::try_from(v).map(Rational::from_integer).map_err(|_| E::custom(format!("{} does not fit into a rational", v)))
+        }
+
+        fn visit_f64<E: de::Error>(self, v: f64) -> Result<Rational, E> {
+                v.to_string().parse().map_err(E::custom)
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Rational, E> {
+                v.parse().map_err(E::custom)
+        }
+}
@@ -1,54 +1,310 @@
 #![allow(dead_code)]
 
-use nalgebra::{DMatrix, Scalar};
+use maths::comb_optimization::assignment_problem::inputs::{check_cost_rows, ParsedInput};
+use maths::comb_optimization::qap::parse_qaplib;
+use maths::comb_optimization::{AssignmentError, BirkhoffError, Cost, GapError, GapMethod, InitialMethod, Objective, QapError, QapMatrices, QapMethod, SolverKind, SparseCostMatrix, TransportationError};
+use nalgebra::DMatrix;
 use rocket::serde::json::Json;
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
-use std::error::Error;
-use std::str::FromStr;
+use serde_json::{Number, Value};
+
+// Largest `k` of the k best assignments; every one of them costs a solve per
+// partition of Murty's algorithm.
+const MAX_K: usize = 100;
+
+/// Numeric type used for the entries of `u`, `v` and `c`.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CostType {
+        #[default]
+        I32,
+        I64,
+        U64,
+        F64,
+}
+
+/// Numeric type of the entries of a matrix to decompose: the cost types and
+/// exact fractions.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryType {
+        #[default]
+        I32,
+        I64,
+        U64,
+        F64,
+        // Exact fractions, given as numbers or as strings such as "1/3".
+        Rational,
+}
 
 #[derive(Debug, Deserialize)]
 pub struct InputAssPblm {
-        u: Option<Vec<i32>>,
-        v: Option<Vec<i32>>,
-        c: Vec<Vec<i32>>,
+        #[serde(rename = "type", default)]
+        pub cost_type: CostType,
+        #[serde(default)]
+        pub objective: Objective,
+        // Replace infeasible `u` and `v` by row and column minima instead of rejecting them.
+        #[serde(default)]
+        pub repair_duals: bool,
+        // The Hungarian method if not given.
+        pub solver: Option<SolverKind>,
+        // Record every step, for the auction solver.
+        #[serde(default)]
+        pub trace: bool,
+        // Return the k best assignments instead of one optimum, at most 100.
+        pub k: Option<usize>,
+        u: Option<Vec<Number>>,
+        v: Option<Vec<Number>>,
+        // `null` marks a forbidden cell.
+        #[serde(default)]
+        c: Vec<Vec<Option<Number>>>,
+        // Sparse input instead of `c`: the allowed (row, column, cost) cells, every
+        // other cell is forbidden. The shape defaults to the largest indices + 1.
+        pub edges: Option<Vec<(usize, usize, Number)>>,
+        nrows: Option<usize>,
+        ncols: Option<usize>,
+        // The (row, column) pairs of an assignment to verify against `u` and `v`.
+        pub assignment: Option<Vec<(usize, usize)>>,
+        // Names of the rows and columns, e.g. workers and jobs, for the equality
+        // graphs, the trace and `named_assignment`.
+        #[serde(default)]
+        pub row_names: Vec<String>,
+        #[serde(default)]
+        pub col_names: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InputTransportation {
+        #[serde(rename = "type", default)]
+        pub cost_type: CostType,
+        // How the initial basic feasible solution is built.
+        #[serde(default)]
+        pub method: InitialMethod,
+        supply: Vec<Number>,
+        demand: Vec<Number>,
+        c: Vec<Vec<Number>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InputGap {
+        #[serde(rename = "type", default)]
+        pub cost_type: CostType,
+        #[serde(default)]
+        pub objective: Objective,
+        // Branch-and-bound, the heuristics only, or the first up to 20 jobs.
+        #[serde(default)]
+        pub method: GapMethod,
+        // Agents as rows, jobs as columns; `null` marks a forbidden cell.
+        c: Vec<Vec<Option<Number>>>,
+        // The capacity each job uses on each agent, of the same shape as `c`.
+        sizes: Vec<Vec<Number>>,
+        capacities: Vec<Number>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InputQap {
+        #[serde(rename = "type", default)]
+        pub cost_type: CostType,
+        // Branch-and-bound, local search, or the first up to 12 facilities.
+        #[serde(default)]
+        pub method: QapMethod,
+        #[serde(default)]
+        flow: Vec<Vec<Number>>,
+        #[serde(default)]
+        distance: Vec<Vec<Number>>,
+        // The text of a QAPLIB file instead of `flow` and `distance`.
+        qaplib: Option<String>,
 }
 
-pub fn parse<N>(ass_pblm_input: Json<InputAssPblm>) -> Result<(Vec<i32>, Vec<i32>, DMatrix<i32>), Box<dyn Error>>
+#[derive(Debug, Deserialize)]
+pub struct InputBirkhoff {
+        #[serde(rename = "type", default)]
+        pub entry_type: EntryType,
+        // Numbers, or strings "p/q" for the rational type.
+        x: Vec<Vec<Value>>,
+}
+
+pub fn parse<N>(ass_pblm_input: Json<InputAssPblm>) -> Result<ParsedInput<N>, AssignmentError>
 where
-        N: FromStr + Scalar,
-        N::Err: Error,
+        N: Cost + DeserializeOwned,
 {
-        let mut u: Vec<i32> = Vec::new();
-        let mut v: Vec<i32> = Vec::new();
-        let c: DMatrix<i32>;
+        let mut u: Vec<N> = Vec::new();
+        let mut v: Vec<N> = Vec::new();
 
         if let Some(u_in) = &ass_pblm_input.u {
-                u = u_in.clone();
+                u = get_cost_vector(u_in)?;
         }
 
         if let Some(v_in) = &ass_pblm_input.v {
-                v = v_in.clone();
+                v = get_cost_vector(v_in)?;
         }
 
         let c_in = &ass_pblm_input.c;
-        c = get_cost_matrix(c_in.clone());
-        
+        let c = get_cost_matrix(c_in)?;
+
         Ok((u, v, c))
 }
 
-fn get_cost_matrix(c_inmatrix: Vec<Vec<i32>>) -> DMatrix<i32> {
+/// `k` and the costs `c` of a k best assignments request.
+pub type ParsedKBest<N> = (usize, DMatrix<Option<N>>);
+
+pub fn parse_k_best<N>(ass_pblm_input: Json<InputAssPblm>) -> Result<ParsedKBest<N>, AssignmentError>
+where
+        N: Cost + DeserializeOwned,
+{
+        reject_options(single_solve_options(&ass_pblm_input), "k")?;
+        let k = ass_pblm_input.k.unwrap_or(1);
+        if k > MAX_K {
+                return Err(AssignmentError::InvalidInput {
+                        details: format!("k is {}, at most {} best assignments are returned", k, MAX_K),
+                });
+        }
+        let (_, _, c) = parse::<N>(ass_pblm_input)?;
+        Ok((k, c))
+}
+
+pub fn parse_sparse<N>(ass_pblm_input: Json<InputAssPblm>) -> Result<SparseCostMatrix<N>, AssignmentError>
+where
+        N: Cost + DeserializeOwned,
+{
+        if !ass_pblm_input.c.is_empty() {
+                return Err(AssignmentError::InvalidInput {
+                        details: "give either c or edges, not both".to_string(),
+                });
+        }
+        let mut options = single_solve_options(&ass_pblm_input);
+        if ass_pblm_input.k.is_some() {
+                options.push("k");
+        }
+        if ass_pblm_input.solver.is_some() {
+                options.push("solver");
+        }
+        reject_options(options, "edges")?;
+        // The column duals of `solve_sparse` become negative, see its documentation.
+        if let CostType::U64 = ass_pblm_input.cost_type {
+                return Err(AssignmentError::InvalidInput {
+                        details: "edges need a signed cost type, i32, i64 or f64, for their duals; not u64".to_string(),
+                });
+        }
+        let edges_in = ass_pblm_input.edges.as_deref().unwrap_or_default();
+        let edges = edges_in.iter().map(|(row, col, cost)| Ok((*row, *col, get_cost(cost)?))).collect::<Result<Vec<_>, AssignmentError>>()?;
+
+        let nrows = ass_pblm_input.nrows.unwrap_or_else(|| edges.iter().map(|e| e.0 + 1).max().unwrap_or(0));
+        let ncols = ass_pblm_input.ncols.unwrap_or_else(|| edges.iter().map(|e| e.1 + 1).max().unwrap_or(0));
+        SparseCostMatrix::from_edges(nrows, ncols, &edges)
+}
+
+/// `supply`, `demand` and the unit costs `c` of a transportation problem.
+pub type ParsedTransportation<N> = (Vec<N>, Vec<N>, DMatrix<N>);
+
+pub fn parse_transportation<N>(input: Json<InputTransportation>) -> Result<ParsedTransportation<N>, TransportationError>
+where
+        N: Cost + DeserializeOwned,
+{
+        let supply = get_cost_vector(&input.supply)?;
+        let demand = get_cost_vector(&input.demand)?;
+
+        let rows = input.c.len();
+        let cols = check_cost_rows(&input.c)?;
+        let c = input.c.iter().flatten().map(get_cost).collect::<Result<Vec<N>, _>>()?;
+
+        Ok((supply, demand, DMatrix::from_row_slice(rows, cols, &c[..])))
+}
+
+/// Costs `c`, `sizes` and `capacities` of a generalized assignment problem.
+pub type ParsedGap<N> = (DMatrix<Option<N>>, DMatrix<N>, Vec<N>);
+
+pub fn parse_gap<N>(input: Json<InputGap>) -> Result<ParsedGap<N>, GapError>
+where
+        N: Cost + DeserializeOwned,
+{
+        let c = get_cost_matrix(&input.c)?;
+        let capacities = get_cost_vector(&input.capacities)?;
+
+        let sizes = get_dense_matrix(&input.sizes)?;
+
+        Ok((c, sizes, capacities))
+}
+
+pub fn parse_qap<N>(input: Json<InputQap>) -> Result<QapMatrices<N>, QapError>
+where
+        N: Cost + DeserializeOwned,
+{
+        if let Some(text) = &input.qaplib {
+                if !input.flow.is_empty() || !input.distance.is_empty() {
+                        return Err(QapError::InvalidInput {
+                                details: "give either qaplib or flow and distance, not both".to_string(),
+                        });
+                }
+                return parse_qaplib(text);
+        }
+
+        Ok((get_dense_matrix(&input.flow)?, get_dense_matrix(&input.distance)?))
+}
+
+pub fn parse_birkhoff<N>(input: Json<InputBirkhoff>) -> Result<DMatrix<N>, BirkhoffError>
+where
+        N: Cost + DeserializeOwned,
+{
+        let cols = check_cost_rows(&input.x)?;
+        let entries = input.x.iter().flatten().map(|x_ij| serde_json::from_value(x_ij.clone())).collect::<Result<Vec<N>, _>>()?;
+
+        Ok(DMatrix::from_row_slice(input.x.len(), cols, &entries[..]))
+}
+
+// The options of a single optimum that `ass_pblm_input` sets, by name.
+fn single_solve_options(ass_pblm_input: &InputAssPblm) -> Vec<&'static str> {
+        let options = [
+                ("u", ass_pblm_input.u.is_some()),
+                ("v", ass_pblm_input.v.is_some()),
+                ("repair_duals", ass_pblm_input.repair_duals),
+                ("trace", ass_pblm_input.trace),
+                ("row_names", !ass_pblm_input.row_names.is_empty()),
+                ("col_names", !ass_pblm_input.col_names.is_empty()),
+        ];
+        options.into_iter().filter(|&(_, set)| set).map(|(name, _)| name).collect()
+}
+
+// Rejects the `options` set along with `with`, which ignores them.
+fn reject_options(options: Vec<&str>, with: &str) -> Result<(), AssignmentError> {
+        if options.is_empty() {
+                return Ok(());
+        }
+        Err(AssignmentError::InvalidInput {
+                details: format!("{} cannot be combined with {}", options.join(", "), with),
+        })
+}
+
+// JSON numbers are kept as `Number` until the requested cost type is known, so
+// that e.g. a negative entry is rejected for `u64` and a fraction for `i64`.
+fn get_cost<N: Cost + DeserializeOwned>(n: &Number) -> Result<N, AssignmentError> {
+        Ok(serde_json::from_value(Value::Number(n.clone()))?)
+}
+
+fn get_cost_vector<N: Cost + DeserializeOwned>(invec: &[Number]) -> Result<Vec<N>, AssignmentError> {
+        invec.iter().map(get_cost).collect()
+}
+
+fn get_cost_matrix<N: Cost + DeserializeOwned>(c_inmatrix: &[Vec<Option<Number>>]) -> Result<DMatrix<Option<N>>, AssignmentError> {
         let mut c = Vec::new();
-        let rows;
-        let cols;
 
-        rows = c_inmatrix.len();
+        let rows = c_inmatrix.len();
+        let cols = check_cost_rows(c_inmatrix)?;
 
-        for mut row in c_inmatrix {
-                c.append(&mut row);
+        for row in c_inmatrix {
+                for entry in row {
+                        c.push(entry.as_ref().map(get_cost).transpose()?);
+                }
         }
 
-        // The number of items divided by the number of rows equals the
-        // number of columns.
-        cols = c.len() / rows;
-        DMatrix::from_row_slice(rows, cols, &c[..])
+        Ok(DMatrix::from_row_slice(rows, cols, &c[..]))
+}
+
+// A matrix without forbidden cells.
+fn get_dense_matrix<N: Cost + DeserializeOwned>(rows: &[Vec<Number>]) -> Result<DMatrix<N>, AssignmentError> {
+        let cols = check_cost_rows(rows)?;
+        let entries = rows.iter().flatten().map(get_cost).collect::<Result<Vec<N>, _>>()?;
+
+        Ok(DMatrix::from_row_slice(rows.len(), cols, &entries[..]))
 }
@@ -1,4 +1,4 @@
-pub use inputs::parse;
-pub use inputs::InputAssPblm;
+pub use inputs::{parse, parse_birkhoff, parse_gap, parse_k_best, parse_qap, parse_sparse, parse_transportation};
+pub use inputs::{CostType, EntryType, InputAssPblm, InputBirkhoff, InputGap, InputQap, InputTransportation};
 
 pub mod inputs;
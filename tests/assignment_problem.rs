@@ -0,0 +1,748 @@
+use maths::comb_optimization::assignment_problem::inputs::check_cost_rows;
+use maths::comb_optimization::{count_optimal_assignments, dual_violations, new_solver, solve_bottleneck, solve_sparse, verify_assignment, AssignmentError, AssignmentSolution, AssignmentSolver, AuctionDirection, AuctionSolver, Bid, CertificateViolation, Change, Cost, HallViolator, HungarianStep, KBestAssignments, Objective, OptimalSolution, optimal_assignments, ShortestPathSolver, Side, SolverKind, SparseCostMatrix};
+use nalgebra::DMatrix;
+use serde_json::Value;
+
+const C: [i32; 25] = [13, 12, 13, 12, 11, 12, 11, 15, 15, 16, 10, 13, 18, 16, 13, 13, 15, 22, 16, 17, 12, 15, 19, 16, 18];
+
+// ∑u + ∑v of the final duals.
+fn dual_sum<N: Cost + Into<f64>>(soln: &AssignmentSolution<N>) -> f64 {
+        soln.u.iter().chain(soln.v.iter()).map(|&x| x.into()).sum()
+}
+
+fn solve<N: Cost>(u: Vec<N>, v: Vec<N>, c: Vec<N>) -> AssignmentSolution<N> {
+        let mut ass_pblm_soln = OptimalSolution::new();
+        ass_pblm_soln.init(u, v, DMatrix::from_row_slice(5, 5, &c));
+        ass_pblm_soln.find_optimum_cost().unwrap()
+}
+
+#[test]
+fn test_generic_cost_types() {
+        let soln = solve(vec![], vec![], C.iter().map(|&x| x as i64 * 1_000_000_000).collect());
+        assert_eq!(soln.cost, 65_000_000_000);
+        assert_eq!(soln.u.iter().chain(soln.v.iter()).sum::<i64>(), soln.cost);
+
+        // Unsigned duals must stay non-negative, so start from duals that do.
+        let soln = solve(vec![7, 8, 6, 9, 8], vec![4, 3, 6, 5, 4], C.iter().map(|&x| x as u64).collect());
+        assert_eq!(soln.cost, 65);
+        assert_eq!(soln.u.iter().chain(soln.v.iter()).sum::<u64>(), 65);
+
+        let soln = solve(vec![], vec![], C.iter().map(|&x| x as f64 / 10.0).collect());
+        assert!((soln.cost - 6.5).abs() < 1e-9);
+        assert!((dual_sum(&soln) - 6.5).abs() < 1e-9);
+}
+
+#[test]
+fn test_cost_tolerance() {
+        assert!(0.1_f64.checked_add(0.2).unwrap().checked_sub(0.3).unwrap().is_zero());
+        assert!(!1e-6_f64.is_zero());
+        assert_eq!(u64::zero().checked_sub(1), None);
+        assert_eq!(f64::MAX.checked_add(f64::MAX), None);
+}
+
+#[test]
+fn test_maximization() {
+        let mut ass_pblm_soln = OptimalSolution::new();
+        ass_pblm_soln.set_objective(Objective::Maximize);
+        ass_pblm_soln.init(vec![], vec![], DMatrix::from_row_slice(5, 5, &C));
+        let soln = ass_pblm_soln.find_optimum_cost().unwrap();
+        assert_eq!(soln.cost, 82);
+        assert_eq!(dual_sum(&soln), 82.0);
+
+        // The trace starts from the original matrix, not from M - c.
+        assert_eq!(soln.states[0].c[(2, 0)], Some(10));
+        for i in 0..5 {
+                for j in 0..5 {
+                        assert!(soln.u[i] + soln.v[j] >= C[i * 5 + j]);
+                }
+        }
+}
+
+#[test]
+fn test_rectangular() {
+        let c = DMatrix::from_row_slice(3, 5, &[9, 2, 7, 8, 4, 6, 4, 3, 7, 6, 5, 8, 1, 8, 3]);
+
+        let mut ass_pblm_soln = OptimalSolution::new();
+        ass_pblm_soln.init(vec![], vec![], c.clone());
+        let soln = ass_pblm_soln.find_optimum_cost().unwrap();
+        assert_eq!(soln.cost, 8);
+        assert_eq!(dual_sum(&soln), 8.0);
+        assert_eq!(soln.assignment, vec![(0, 1), (1, 2), (2, 4)]);
+        assert_eq!(soln.unmatched_rows, Vec::<usize>::new());
+        assert_eq!(soln.unmatched_cols, vec![0, 3]);
+
+        let states: Value = serde_json::to_value(&soln.states).unwrap();
+        // Columns of the matching are numbered after the 3 rows.
+        assert_eq!(states.as_array().unwrap().last().unwrap()["m"], serde_json::json!([[0, 4], [1, 5], [2, 7]]));
+        for state in &soln.states {
+                assert_eq!(state.u.len() % 3, 0);
+                assert_eq!(state.c.shape(), (3, 5));
+        }
+
+        let mut ass_pblm_soln = OptimalSolution::new();
+        ass_pblm_soln.set_objective(Objective::Maximize);
+        ass_pblm_soln.init(vec![], vec![], c.transpose());
+        let soln = ass_pblm_soln.find_optimum_cost().unwrap();
+        assert_eq!(soln.cost, 24);
+        assert_eq!(dual_sum(&soln), 24.0);
+        assert_eq!(soln.assignment, vec![(0, 0), (1, 2), (3, 1)]);
+        assert_eq!(soln.unmatched_rows, vec![2, 4]);
+        assert_eq!(soln.unmatched_cols, Vec::<usize>::new());
+}
+
+#[test]
+fn test_every_blue_vertex_is_scanned() {
+        // Used to loop forever: the alternating tree stopped scanning a blue vertex
+        // after its first red neighbour, so ϵ was taken over a zero entry.
+        let c = vec![0, 4, 9, 1, 2, 7, 6, 3, 7, 6, 4, 7, 8, 0, 6, 8, 1, 4, 8, 0, 3, 4, 7, 8, 5, 3, 2, 6, 8, 5, 5, 2, 8, 8, 6, 5];
+        let mut ass_pblm_soln = OptimalSolution::new();
+        ass_pblm_soln.init(vec![], vec![], DMatrix::from_row_slice(6, 6, &c));
+        let soln = ass_pblm_soln.find_optimum_cost().unwrap();
+        assert_eq!(soln.cost, 14);
+        assert_eq!(dual_sum(&soln), 14.0);
+}
+
+#[test]
+fn test_hungarian_trace() {
+        let c = vec![0, 4, 9, 1, 2, 7, 6, 3, 7, 6, 4, 7, 8, 0, 6, 8, 1, 4, 8, 0, 3, 4, 7, 8, 5, 3, 2, 6, 8, 5, 5, 2, 8, 8, 6, 5];
+        let mut ass_pblm_soln = OptimalSolution::new();
+        ass_pblm_soln.init(vec![], vec![], DMatrix::from_row_slice(6, 6, &c));
+        let soln = ass_pblm_soln.find_optimum_cost().unwrap();
+
+        // Every state that chose ϵ carries the matching run on its equality graph; the
+        // input and the optimal state do not.
+        let (last, traced) = soln.states.split_last().unwrap();
+        assert!(soln.states[0].hungarian.is_none() && last.hungarian.is_none());
+        assert!(traced.len() > 1);
+        for state in &traced[1..] {
+                let steps = state.hungarian.as_ref().unwrap();
+                assert!(matches!(steps.first(), Some(HungarianStep::Start { .. })));
+                let matching = steps.iter().rev().find_map(|step| match step {
+                        HungarianStep::Start { matching } | HungarianStep::Augmented { matching } => Some(matching),
+                        _ => None,
+                });
+                // König: the cover is as large as the maximum matching.
+                match steps.last() {
+                        Some(HungarianStep::Cover { cover }) => assert_eq!(cover.len(), matching.unwrap().len()),
+                        step => panic!("expected a cover, found {:?}", step),
+                }
+        }
+
+        // The steps of a rectangular problem name only its 3 rows and 5 columns.
+        let c = DMatrix::from_row_slice(3, 5, &[9, 2, 7, 8, 4, 6, 4, 3, 7, 6, 5, 8, 1, 8, 3]);
+        let mut ass_pblm_soln = OptimalSolution::new();
+        ass_pblm_soln.init(vec![], vec![], c);
+        let soln = ass_pblm_soln.find_optimum_cost().unwrap();
+        for step in soln.states.iter().flat_map(|state| state.hungarian.iter().flatten()) {
+                if let HungarianStep::Cover { cover } = step {
+                        assert!(cover.iter().all(|vertex| vertex.node.index() < 8));
+                }
+        }
+        let states: Value = serde_json::to_value(&soln.states).unwrap();
+        assert_eq!(states[1]["hungarian"][0]["step"], "start");
+        assert!(states[0].get("hungarian").is_none());
+}
+
+#[test]
+fn test_row_and_column_names() {
+        let c = DMatrix::from_row_slice(3, 5, &[9, 2, 7, 8, 4, 6, 4, 3, 7, 6, 5, 8, 1, 8, 3]);
+        let names = |names: &[&str]| names.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+        let workers = names(&["ann", "ben", "cat"]);
+        let jobs = names(&["dig", "fix", "mow", "paint", "weld"]);
+
+        for kind in [SolverKind::Hungarian, SolverKind::ShortestPath, SolverKind::Auction] {
+                let mut solver = new_solver::<i32>(kind);
+                solver.set_names(workers.clone(), jobs.clone());
+                solver.init(vec![], vec![], c.clone());
+                let soln = solver.find_optimum_cost().unwrap();
+                assert_eq!(soln.named_assignment, Some(vec![("ann".to_string(), "fix".to_string()), ("ben".to_string(), "mow".to_string()), ("cat".to_string(), "weld".to_string())]));
+        }
+
+        // The equality graphs and the trace show the names; unnamed columns are t1, t2, ...
+        let mut ass_pblm_soln = OptimalSolution::new();
+        ass_pblm_soln.set_names(workers.clone(), Vec::new());
+        ass_pblm_soln.init(vec![], vec![], c.clone());
+        let soln = ass_pblm_soln.find_optimum_cost().unwrap();
+        let g = soln.states.last().unwrap().g.as_ref().unwrap();
+        assert_eq!(g.node_weights().cloned().collect::<Vec<_>>(), names(&["ann", "ben", "cat", "t1", "t2", "t3", "t4", "t5"]));
+        assert_eq!(soln.named_assignment.unwrap()[0], ("ann".to_string(), "t2".to_string()));
+        for step in soln.states.iter().flat_map(|state| state.hungarian.iter().flatten()) {
+                if let HungarianStep::ExposedVertex { vertex } = step {
+                        assert!(workers.contains(&vertex.label));
+                }
+        }
+
+        let mut ass_pblm_soln = OptimalSolution::new();
+        ass_pblm_soln.init(vec![], vec![], c.clone());
+        assert_eq!(ass_pblm_soln.find_optimum_cost().unwrap().named_assignment, None);
+
+        let mut ass_pblm_soln = OptimalSolution::new();
+        ass_pblm_soln.set_names(workers, names(&["dig"]));
+        ass_pblm_soln.init(vec![], vec![], c);
+        assert_eq!(ass_pblm_soln.find_optimum_cost().unwrap_err(), AssignmentError::NameLengthMismatch { side: Side::Columns, expected: 5, found: 1 });
+}
+
+#[test]
+fn test_forbidden_cells() {
+        let x = None;
+        #[rustfmt::skip]
+        let c = DMatrix::from_row_slice(3, 3, &[
+                Some(1), Some(2), x,
+                x, Some(5), Some(1),
+                Some(3), x, Some(9),
+        ]);
+        // Without the forbidden cells (0, 2) and (2, 1) the optimum would be 1 + 5 + 0.
+        let mut ass_pblm_soln = OptimalSolution::new();
+        ass_pblm_soln.init_with_forbidden(vec![], vec![], c);
+        let soln = ass_pblm_soln.find_optimum_cost().unwrap();
+        assert_eq!(soln.cost, 6);
+        assert_eq!(dual_sum(&soln), 6.0);
+        let states: Value = serde_json::to_value(&soln.states).unwrap();
+        // `c` is serialized column by column, (0, 2) is its 7th entry.
+        assert_eq!(states[0]["c"][0][6], Value::Null);
+        assert_eq!(soln.assignment, vec![(0, 1), (1, 2), (2, 0)]);
+
+        // Rows 0 and 2 may only take column 0.
+        #[rustfmt::skip]
+        let c = DMatrix::from_row_slice(3, 3, &[
+                Some(1), x, x,
+                Some(4), Some(5), Some(1),
+                Some(3), x, x,
+        ]);
+        let mut ass_pblm_soln = OptimalSolution::new();
+        ass_pblm_soln.init_with_forbidden(vec![], vec![], c);
+        let err = ass_pblm_soln.find_optimum_cost().unwrap_err();
+        assert_eq!(
+                err,
+                AssignmentError::from(HallViolator {
+                        side: Side::Rows,
+                        members: vec![0, 2],
+                        neighbours: vec![0],
+                })
+        );
+
+        // More rows than columns: columns 1 and 2 may only be taken by row 3.
+        #[rustfmt::skip]
+        let c = DMatrix::from_row_slice(4, 3, &[
+                Some(1), x, x,
+                Some(2), x, x,
+                Some(3), x, x,
+                Some(4), Some(5), Some(6),
+        ]);
+        let mut ass_pblm_soln = OptimalSolution::new();
+        ass_pblm_soln.init_with_forbidden(vec![], vec![], c);
+        let err = ass_pblm_soln.find_optimum_cost().unwrap_err();
+        assert_eq!(
+                err,
+                AssignmentError::from(HallViolator {
+                        side: Side::Columns,
+                        members: vec![1, 2],
+                        neighbours: vec![3],
+                })
+        );
+}
+
+#[test]
+fn test_solution_fields() {
+        let soln = solve(vec![7, 8, 6, 9, 8], vec![4, 3, 6, 5, 4], C.to_vec());
+        assert_eq!(soln.cost, 65);
+        assert_eq!(soln.assignment.iter().map(|&(i, j)| C[i * 5 + j]).sum::<i32>(), 65);
+        assert_eq!(soln.iterations + 1, soln.states.len());
+        for &(i, j) in &soln.assignment {
+                assert_eq!(soln.u[i] + soln.v[j], C[i * 5 + j]);
+        }
+}
+
+#[test]
+fn test_errors() {
+        let solve_with = |u: Vec<u64>, v: Vec<u64>, c: DMatrix<u64>| {
+                let mut ass_pblm_soln = OptimalSolution::new();
+                ass_pblm_soln.init(u, v, c);
+                ass_pblm_soln.find_optimum_cost().unwrap_err()
+        };
+        let c = DMatrix::from_row_slice(5, 5, &C.map(|x| x as u64));
+
+        assert_eq!(solve_with(vec![], vec![], DMatrix::from_row_slice(0, 0, &[])), AssignmentError::EmptyMatrix);
+        assert_eq!(
+                solve_with(vec![7, 8, 6], vec![], c.clone()),
+                AssignmentError::DualLengthMismatch {
+                        side: Side::Rows,
+                        expected: 5,
+                        found: 3,
+                }
+        );
+        // u_1 + v_1 = 12 > c_1,1 = 11
+        assert_eq!(solve_with(vec![7, 8, 6, 9, 8], vec![4, 4, 6, 5, 4], c.clone()), AssignmentError::InfeasibleDuals { cells: vec![(1, 1)] });
+        // Without supplied duals the unsigned duals have to become negative.
+        assert!(matches!(solve_with(vec![], vec![], c), AssignmentError::Overflow { .. }));
+
+        assert!(matches!(OptimalSolution::<i32>::new().find_optimum_cost(), Err(AssignmentError::EmptyMatrix)));
+
+        assert_eq!(check_cost_rows(&[vec![1, 2], vec![3, 4]]), Ok(2));
+        assert_eq!(check_cost_rows::<i32>(&[]), Err(AssignmentError::EmptyMatrix));
+        assert_eq!(
+                check_cost_rows(&[vec![1, 2], vec![3]]),
+                Err(AssignmentError::RaggedRows {
+                        row: 1,
+                        expected: 2,
+                        found: 1,
+                })
+        );
+}
+
+#[test]
+fn test_dual_repair() {
+        let c = DMatrix::from_row_slice(5, 5, &C);
+        let (u, v) = (vec![7, 8, 6, 9, 8], vec![4, 4, 6, 5, 5]);
+        // u_0 + v_4 = 12 > 11 and u_1 + v_1 = 12 > 11.
+        assert_eq!(dual_violations(&u, &v, &c.map(Some), Objective::Minimize), Ok(vec![(0, 4), (1, 1)]));
+        assert_eq!(dual_violations(&u, &[], &c.map(Some), Objective::Minimize), Ok(vec![]));
+
+        let mut ass_pblm_soln = OptimalSolution::new();
+        ass_pblm_soln.init(u.clone(), v.clone(), c.clone());
+        assert_eq!(ass_pblm_soln.find_optimum_cost().unwrap_err(), AssignmentError::InfeasibleDuals { cells: vec![(0, 4), (1, 1)] });
+
+        let mut ass_pblm_soln = OptimalSolution::new();
+        ass_pblm_soln.set_repair_duals(true);
+        ass_pblm_soln.init(u.clone(), v.clone(), c);
+        let soln = ass_pblm_soln.find_optimum_cost().unwrap();
+        assert_eq!(soln.cost, 65);
+        assert_eq!(dual_sum(&soln), 65.0);
+        // The supplied duals stay in the trace, followed by the repaired ones.
+        assert_eq!(soln.states[0].u, u);
+        assert_eq!(soln.states[0].repaired_cells, None);
+        assert_eq!(soln.states[1].repaired_cells, Some(vec![(0, 4), (1, 1)]));
+        assert_eq!(soln.states[1].u, vec![11, 11, 10, 13, 12]);
+
+        // For a maximisation problem the duals have to cover c from above.
+        let mut ass_pblm_soln = OptimalSolution::new();
+        ass_pblm_soln.set_objective(Objective::Maximize);
+        ass_pblm_soln.set_repair_duals(true);
+        // Row 1 has its maximum 16 in column 4, above u_1 + v_4 = 15.
+        ass_pblm_soln.init(vec![13, 16, 18, 22, 19], vec![0, 0, 0, 0, -1], DMatrix::from_row_slice(5, 5, &C));
+        let soln = ass_pblm_soln.find_optimum_cost().unwrap();
+        assert_eq!(soln.states[1].repaired_cells, Some(vec![(1, 4)]));
+        assert_eq!(soln.cost, 82);
+        assert_eq!(dual_sum(&soln), 82.0);
+}
+
+#[test]
+fn test_shortest_path_solver() {
+        let mut solver = ShortestPathSolver::new();
+        solver.init(vec![], vec![], DMatrix::from_row_slice(5, 5, &C));
+        let soln = solver.find_optimum_cost().unwrap();
+        assert_eq!(soln.cost, 65);
+        assert_eq!(dual_sum(&soln), 65.0);
+        assert_eq!(soln.iterations, 5);
+        for &(i, j) in &soln.assignment {
+                assert_eq!(soln.u[i] + soln.v[j], C[i * 5 + j]);
+        }
+
+        // Both backends agree on rectangular, maximisation and forbidden problems.
+        let x = None;
+        #[rustfmt::skip]
+        let c = DMatrix::from_row_slice(4, 3, &[
+                Some(7), Some(2), x,
+                Some(3), x, Some(8),
+                Some(4), Some(6), Some(1),
+                x, Some(5), Some(9),
+        ]);
+        for objective in [Objective::Minimize, Objective::Maximize] {
+                let solutions: Vec<_> = [SolverKind::Hungarian, SolverKind::ShortestPath]
+                        .into_iter()
+                        .map(|kind| {
+                                let mut solver = new_solver::<i32>(kind);
+                                solver.set_objective(objective);
+                                solver.init_with_forbidden(vec![], vec![], c.clone());
+                                solver.find_optimum_cost().unwrap()
+                        })
+                        .collect();
+                assert_eq!(solutions[0].cost, solutions[1].cost);
+                assert_eq!(dual_sum(&solutions[1]), solutions[1].cost as f64);
+                assert_eq!(solutions[1].unmatched_rows.len(), 1);
+        }
+
+        #[rustfmt::skip]
+        let c = DMatrix::from_row_slice(3, 3, &[
+                Some(1), x, x,
+                Some(4), Some(5), Some(1),
+                Some(3), x, x,
+        ]);
+        let mut solver = new_solver::<i32>(SolverKind::ShortestPath);
+        solver.init_with_forbidden(vec![], vec![], c);
+        assert_eq!(
+                solver.find_optimum_cost().unwrap_err(),
+                AssignmentError::from(HallViolator {
+                        side: Side::Rows,
+                        members: vec![0, 2],
+                        neighbours: vec![0],
+                })
+        );
+}
+
+#[test]
+fn test_auction_solver() {
+        for direction in [AuctionDirection::Forward, AuctionDirection::Reverse, AuctionDirection::ForwardReverse] {
+                let mut solver = AuctionSolver::new();
+                solver.set_direction(direction);
+                solver.init(vec![], vec![], DMatrix::from_row_slice(5, 5, &C));
+                let soln = solver.find_optimum_cost().unwrap();
+                assert_eq!(soln.cost, 65);
+                assert_eq!(dual_sum(&soln), 65.0);
+                assert_eq!(soln.states.len(), 2);
+        }
+
+        // Every bid is recorded on request, with ϵ falling from phase to phase.
+        let mut solver = AuctionSolver::new();
+        solver.set_direction(AuctionDirection::ForwardReverse);
+        solver.set_trace(true);
+        solver.init(vec![], vec![], DMatrix::from_row_slice(5, 5, &C));
+        let soln = solver.find_optimum_cost().unwrap();
+        let bids: Vec<&Bid<i32>> = soln.states.iter().filter_map(|state| state.bid.as_ref()).collect();
+        assert_eq!(bids.len(), soln.states.len() - 2);
+        assert_eq!(bids[0].side, Side::Rows);
+        assert!(bids.iter().any(|bid| bid.side == Side::Columns));
+        assert!(bids.windows(2).all(|pair| pair[0].epsilon >= pair[1].epsilon));
+        assert_eq!(bids.last().unwrap().epsilon, 1);
+        assert!(bids.iter().all(|bid| bid.increment >= bid.epsilon));
+        let states: Value = serde_json::to_value(&soln.states).unwrap();
+        assert_eq!(states[1]["bid"]["side"], "rows");
+        assert!(states[0].get("bid").is_none());
+
+        // The same answers as the other backends.
+        let x = None;
+        #[rustfmt::skip]
+        let c = DMatrix::from_row_slice(4, 3, &[
+                Some(7), Some(2), x,
+                Some(3), x, Some(8),
+                Some(4), Some(6), Some(1),
+                x, Some(5), Some(9),
+        ]);
+        for objective in [Objective::Minimize, Objective::Maximize] {
+                let solutions: Vec<_> = [SolverKind::Hungarian, SolverKind::Auction]
+                        .into_iter()
+                        .map(|kind| {
+                                let mut solver = new_solver::<i32>(kind);
+                                solver.set_objective(objective);
+                                solver.init_with_forbidden(vec![], vec![], c.clone());
+                                solver.find_optimum_cost().unwrap()
+                        })
+                        .collect();
+                assert_eq!(solutions[0].cost, solutions[1].cost);
+                assert_eq!(solutions[0].ranging, solutions[1].ranging);
+                assert_eq!(dual_sum(&solutions[1]), solutions[1].cost as f64);
+        }
+
+        let c = DMatrix::from_row_slice(2, 3, &[0.5, 0.25, 0.125, 0.75, 0.375, 0.5]);
+        let mut solver = new_solver::<f64>(SolverKind::Auction);
+        solver.init(vec![], vec![], c);
+        assert_eq!(solver.find_optimum_cost().unwrap().cost, 0.5);
+
+        // Near ties of large magnitude, where a bid of 1e-9 would move no price.
+        for magnitude in [1e13, 1e15] {
+                let c = DMatrix::from_row_slice(3, 3, &[magnitude, magnitude, magnitude, magnitude, magnitude, magnitude, magnitude + 1.0, magnitude, magnitude]);
+                let mut shortest_path = ShortestPathSolver::new();
+                shortest_path.init(vec![], vec![], c.clone());
+                let mut solver = AuctionSolver::new();
+                solver.init(vec![], vec![], c);
+                assert_eq!(solver.find_optimum_cost().unwrap().cost, shortest_path.find_optimum_cost().unwrap().cost);
+        }
+
+        #[rustfmt::skip]
+        let c = DMatrix::from_row_slice(3, 3, &[
+                Some(1), x, x,
+                Some(4), Some(5), Some(1),
+                Some(3), x, x,
+        ]);
+        let mut solver = new_solver::<i32>(SolverKind::Auction);
+        solver.init_with_forbidden(vec![], vec![], c);
+        assert_eq!(
+                solver.find_optimum_cost().unwrap_err(),
+                AssignmentError::from(HallViolator {
+                        side: Side::Rows,
+                        members: vec![0, 2],
+                        neighbours: vec![0],
+                })
+        );
+}
+
+#[test]
+fn test_warm_start() {
+        let mut c = DMatrix::from_row_slice(5, 5, &C);
+        let mut solver = ShortestPathSolver::new();
+        solver.init(vec![], vec![], c.clone());
+        let previous = solver.find_optimum_cost().unwrap();
+        assert_eq!(previous.assignment, vec![(0, 2), (1, 1), (2, 4), (3, 3), (4, 0)]);
+
+        // Make the assigned cell (0, 2) expensive: only row 0 has to be assigned again.
+        c[(0, 2)] = 30;
+        let mut solver = ShortestPathSolver::new();
+        solver.init(vec![], vec![], c.clone());
+        solver.warm_start(&previous, &[Change::Cell(0, 2)]);
+        let soln = solver.find_optimum_cost().unwrap();
+        assert_eq!(soln.iterations, 1);
+
+        let mut cold = ShortestPathSolver::new();
+        cold.init(vec![], vec![], c.clone());
+        assert_eq!(soln.cost, cold.find_optimum_cost().unwrap().cost);
+        assert_eq!(dual_sum(&soln), soln.cost as f64);
+
+        // A cheaper column; the state after the warm start keeps the untouched pairs.
+        for i in 0..5 {
+                c[(i, 3)] -= 5;
+        }
+        let previous = soln;
+        let mut solver = ShortestPathSolver::new();
+        solver.init(vec![], vec![], c.clone());
+        solver.warm_start(&previous, &[Change::Column(3)]);
+        let soln = solver.find_optimum_cost().unwrap();
+        assert_eq!(soln.states[1].m.as_ref().unwrap().len(), 4);
+        assert_eq!(soln.iterations, 1);
+
+        // The other backends solve from scratch.
+        let mut solver = new_solver(SolverKind::Hungarian);
+        solver.init(vec![], vec![], c.clone());
+        solver.warm_start(&previous, &[Change::Column(3)]);
+        assert_eq!(solver.find_optimum_cost().unwrap().cost, soln.cost);
+
+        let mut cold = ShortestPathSolver::new();
+        cold.init(vec![], vec![], c);
+        assert_eq!(soln.cost, cold.find_optimum_cost().unwrap().cost);
+}
+
+#[test]
+fn test_k_best() {
+        let c = DMatrix::from_row_slice(5, 5, &C).map(Some);
+        let costs: Vec<i32> = KBestAssignments::new(c.clone(), Objective::Minimize, SolverKind::ShortestPath).take(4).map(|soln| soln.unwrap().cost).collect();
+        assert_eq!(costs[0], 65);
+        assert!(costs.windows(2).all(|w| w[0] <= w[1]));
+
+        // All 5! assignments, each exactly once.
+        let solutions: Vec<_> = KBestAssignments::new(c.clone(), Objective::Minimize, SolverKind::Hungarian).map(Result::unwrap).collect();
+        assert_eq!(solutions.len(), 120);
+        let mut assignments: Vec<_> = solutions.iter().map(|soln| soln.assignment.clone()).collect();
+        assignments.sort();
+        assignments.dedup();
+        assert_eq!(assignments.len(), 120);
+        assert_eq!(solutions.last().unwrap().cost, 82);
+
+        let profits: Vec<i32> = KBestAssignments::new(c, Objective::Maximize, SolverKind::ShortestPath).take(3).map(|soln| soln.unwrap().cost).collect();
+        assert_eq!(profits[0], 82);
+        assert!(profits.windows(2).all(|w| w[0] >= w[1]));
+
+        // A forbidden cell leaves the two assignments of a 2 x 2 problem to one.
+        let c = DMatrix::from_row_slice(2, 2, &[Some(1), None, Some(3), Some(4)]);
+        let solutions: Vec<_> = KBestAssignments::new(c, Objective::Minimize, SolverKind::ShortestPath).map(Result::unwrap).collect();
+        assert_eq!(solutions.len(), 1);
+        assert_eq!(solutions[0].assignment, vec![(0, 0), (1, 1)]);
+}
+
+#[test]
+fn test_bottleneck() {
+        let c = DMatrix::from_row_slice(5, 5, &C).map(Some);
+        let soln = solve_bottleneck(&c, Objective::Minimize).unwrap();
+        assert_eq!(soln.bottleneck, 15);
+        assert_eq!(soln.assignment.len(), 5);
+        assert!(soln.assignment.iter().all(|&(i, j)| C[5 * i + j] <= 15));
+        // Binary search over the 10 distinct entries, starting with the largest.
+        assert_eq!(soln.thresholds[0].threshold, 22);
+        assert!(soln.thresholds.len() <= 5);
+        assert!(soln.thresholds.iter().all(|step| step.perfect == (step.threshold >= 15)));
+
+        let soln = solve_bottleneck(&c, Objective::Maximize).unwrap();
+        assert_eq!(soln.bottleneck, 13);
+        assert!(soln.assignment.iter().all(|&(i, j)| C[5 * i + j] >= 13));
+
+        // 3 x 2: one row is left over.
+        let c = DMatrix::from_row_slice(3, 2, &[Some(1.0), Some(9.0), Some(2.0), Some(3.0), Some(8.0), Some(1.5)]);
+        let soln = solve_bottleneck(&c, Objective::Minimize).unwrap();
+        assert_eq!(soln.bottleneck, 1.5);
+        assert_eq!(soln.assignment, vec![(0, 0), (2, 1)]);
+        assert_eq!(soln.unmatched_rows, vec![1]);
+        assert!(soln.unmatched_cols.is_empty());
+
+        // Rows 0 and 1 can only go to column 0.
+        let c = DMatrix::from_row_slice(3, 3, &[Some(1), None, None, Some(2), None, None, Some(3), Some(4), Some(5)]);
+        let hall_violator = HallViolator {
+                side: Side::Rows,
+                members: vec![0, 1],
+                neighbours: vec![0],
+        };
+        assert_eq!(solve_bottleneck(&c, Objective::Minimize).unwrap_err(), AssignmentError::NoPerfectMatching { hall_violator });
+        assert_eq!(solve_bottleneck(&DMatrix::<Option<i32>>::from_element(2, 0, None), Objective::Minimize).unwrap_err(), AssignmentError::EmptyMatrix);
+}
+
+#[test]
+fn test_cost_ranging() {
+        // Assigned: (0, 0) at 1 and (1, 1) at 4, the alternative costs 2 + 5 = 7.
+        let mut ass_pblm_soln = OptimalSolution::new();
+        ass_pblm_soln.init(vec![], vec![], DMatrix::from_row_slice(2, 2, &[1, 2, 5, 4]));
+        let soln = ass_pblm_soln.find_optimum_cost().unwrap();
+        assert_eq!(soln.ranging, DMatrix::from_row_slice(2, 2, &[Some(2), Some(2), Some(2), Some(2)]));
+
+        // The same with profits: the assigned cells may fall and the others have to rise.
+        let mut ass_pblm_soln = ShortestPathSolver::new();
+        ass_pblm_soln.set_objective(Objective::Maximize);
+        ass_pblm_soln.init(vec![], vec![], DMatrix::from_row_slice(2, 3, &[1, 2, 7, 5, 4, 3]));
+        let soln = ass_pblm_soln.find_optimum_cost().unwrap();
+        assert_eq!(soln.assignment, vec![(0, 2), (1, 0)]);
+        // (1, 1) enters once it rises by 1, (1, 0) leaves once it falls by 1.
+        assert_eq!(soln.ranging, DMatrix::from_row_slice(2, 3, &[Some(7), Some(5), Some(5), Some(1), Some(1), Some(7)]));
+
+        // An entry that is the only option never changes the assignment.
+        let c = DMatrix::from_row_slice(2, 2, &[Some(3), None, Some(1), Some(2)]);
+        let mut ass_pblm_soln = new_solver::<i32>(SolverKind::ShortestPath);
+        ass_pblm_soln.init_with_forbidden(vec![], vec![], c);
+        let soln = ass_pblm_soln.find_optimum_cost().unwrap();
+        assert_eq!(soln.ranging, DMatrix::from_row_slice(2, 2, &[None, None, None, None]));
+
+        // Every cell of the 5 x 5 example: the cost of the best assignment that
+        // changes the cell's status is cost + ranging.
+        let soln = solve(vec![], vec![], C.to_vec());
+        for (i, j) in (0..5).flat_map(|i| (0..5).map(move |j| (i, j))) {
+                let assigned = soln.assignment.contains(&(i, j));
+                let mut c = DMatrix::from_row_slice(5, 5, &C).map(Some);
+                if assigned {
+                        c[(i, j)] = None;
+                } else {
+                        let keep = c[(i, j)];
+                        c.row_mut(i).fill(None);
+                        c.column_mut(j).fill(None);
+                        c[(i, j)] = keep;
+                }
+                let mut ass_pblm_soln = ShortestPathSolver::new();
+                ass_pblm_soln.init_with_forbidden(vec![], vec![], c);
+                let other = ass_pblm_soln.find_optimum_cost().unwrap();
+                assert_eq!(Some(other.cost - soln.cost), soln.ranging[(i, j)]);
+        }
+}
+
+#[test]
+fn test_optimal_assignments() {
+        // Equal costs: all 5! assignments are optimal.
+        let soln = solve(vec![], vec![], vec![1; 25]);
+        let all = optimal_assignments(&soln, None);
+        assert_eq!(all.len(), 120);
+        assert!(all.contains(&vec![(0, 4), (1, 3), (2, 2), (3, 1), (4, 0)]));
+        assert_eq!(optimal_assignments(&soln, Some(4)).len(), 4);
+        assert_eq!(count_optimal_assignments(&soln, None), 120);
+        assert_eq!(count_optimal_assignments(&soln, Some(2)), 2);
+
+        // A unique optimum.
+        let mut ass_pblm_soln = OptimalSolution::new();
+        ass_pblm_soln.init(vec![], vec![], DMatrix::from_row_slice(2, 2, &[1, 2, 5, 4]));
+        let soln = ass_pblm_soln.find_optimum_cost().unwrap();
+        assert_eq!(optimal_assignments(&soln, None), vec![soln.assignment.clone()]);
+
+        // Rectangular profits: row 0 takes column 0 or 1, row 1 takes column 2.
+        let mut ass_pblm_soln = ShortestPathSolver::new();
+        ass_pblm_soln.set_objective(Objective::Maximize);
+        ass_pblm_soln.init(vec![], vec![], DMatrix::from_row_slice(2, 3, &[5, 5, 1, 1, 1, 6]));
+        let soln = ass_pblm_soln.find_optimum_cost().unwrap();
+        let mut all = optimal_assignments(&soln, None);
+        all.sort();
+        assert_eq!(all, vec![vec![(0, 0), (1, 2)], vec![(0, 1), (1, 2)]]);
+}
+
+#[test]
+fn test_verify_assignment() {
+        // The solvers' own certificates hold.
+        let soln = solve(vec![], vec![], C.to_vec());
+        let c = DMatrix::from_row_slice(5, 5, &C).map(Some);
+        let report = verify_assignment(&c, &soln.assignment, &soln.u, &soln.v, Objective::Minimize).unwrap();
+        assert!(report.optimal);
+        assert_eq!((report.cost, report.dual_objective), (soln.cost, soln.cost));
+
+        let c = DMatrix::from_row_slice(3, 5, &[9, 2, 7, 8, 4, 6, 4, 3, 7, 6, 5, 8, 1, 8, 3]).map(Some);
+        let mut ass_pblm_soln = ShortestPathSolver::new();
+        ass_pblm_soln.set_objective(Objective::Maximize);
+        ass_pblm_soln.init_with_forbidden(vec![], vec![], c.transpose());
+        let soln = ass_pblm_soln.find_optimum_cost().unwrap();
+        assert!(verify_assignment(&c.transpose(), &soln.assignment, &soln.u, &soln.v, Objective::Maximize).unwrap().optimal);
+
+        // Feasible duals, but the other assignment costs 7 > ∑u + ∑v = 5.
+        let c = DMatrix::from_row_slice(2, 2, &[Some(1), Some(2), Some(5), Some(4)]);
+        let report = verify_assignment(&c, &[(0, 1), (1, 0)], &[1, 4], &[0, 0], Objective::Minimize).unwrap();
+        assert!(!report.optimal);
+        assert!(report.assignment.is_empty() && report.dual_feasibility.is_empty());
+        assert_eq!(
+                report.complementary_slackness,
+                vec![CertificateViolation::NotTight { row: 0, col: 1, cost: 2, dual_sum: 1 }, CertificateViolation::NotTight { row: 1, col: 0, cost: 5, dual_sum: 4 }]
+        );
+        assert_eq!((report.cost, report.dual_objective, report.cost_equality), (7, 5, false));
+
+        // Infeasible duals and a broken assignment.
+        let c = DMatrix::from_row_slice(2, 2, &[Some(1), None, Some(5), Some(4)]);
+        let report = verify_assignment(&c, &[(0, 1), (1, 1), (2, 0)], &[2, 4], &[0, 0], Objective::Minimize).unwrap();
+        assert_eq!(
+                report.assignment,
+                vec![
+                        CertificateViolation::ForbiddenCell { row: 0, col: 1 },
+                        CertificateViolation::CellOutOfRange { row: 2, col: 0 },
+                        CertificateViolation::NotAssigned { side: Side::Columns, index: 0 },
+                        CertificateViolation::AssignedTwice { side: Side::Columns, index: 1 },
+                ]
+        );
+        assert_eq!(report.dual_feasibility, vec![CertificateViolation::InfeasibleCell { row: 0, col: 0, cost: 1, dual_sum: 2 }]);
+
+        // A rectangular problem: an unassigned column may not carry a dual.
+        let c = DMatrix::from_row_slice(1, 2, &[Some(5), Some(1)]);
+        let report = verify_assignment(&c, &[(0, 0)], &[0], &[5, 0], Objective::Minimize).unwrap();
+        assert_eq!(report.dual_feasibility, vec![CertificateViolation::InfeasibleSign { side: Side::Columns, index: 0, dual: 5 }]);
+        assert!(report.cost_equality && !report.optimal);
+        let report = verify_assignment(&c, &[(0, 1)], &[1], &[-1, 0], Objective::Minimize).unwrap();
+        assert_eq!(report.complementary_slackness, vec![CertificateViolation::SlackDual { side: Side::Columns, index: 0, dual: -1 }]);
+
+        assert_eq!(
+                verify_assignment(&c, &[(0, 1)], &[1], &[0], Objective::Minimize).unwrap_err(),
+                AssignmentError::DualLengthMismatch { side: Side::Columns, expected: 2, found: 1 }
+        );
+}
+
+#[test]
+fn test_sparse() {
+        // The 5 x 5 example as an edge list gives the dense optimum.
+        let edges: Vec<(usize, usize, i32)> = (0..25).map(|k| (k / 5, k % 5, C[k])).collect();
+        let c = SparseCostMatrix::from_edges(5, 5, &edges).unwrap();
+        assert_eq!(c.len(), 25);
+        assert_eq!(c.get(2, 0), Some(10));
+        let soln = solve_sparse(&c, Objective::Minimize).unwrap();
+        assert_eq!(soln.cost, 65);
+        assert_eq!(soln.u.iter().sum::<i32>() + soln.v.iter().sum::<i32>(), 65);
+        assert_eq!(solve_sparse(&c, Objective::Maximize).unwrap().cost, 82);
+
+        // 3 x 4 with two allowed cells per row: column 3 stays free.
+        let c = SparseCostMatrix::from_edges(3, 4, &[(0, 0, 4.0), (0, 1, 1.0), (1, 1, 2.0), (1, 2, 6.0), (2, 0, 3.0), (2, 2, 5.0)]).unwrap();
+        let soln = solve_sparse(&c, Objective::Minimize).unwrap();
+        assert_eq!(soln.assignment, vec![(0, 1), (1, 2), (2, 0)]);
+        assert_eq!(soln.cost, 10.0);
+        assert_eq!(soln.unmatched_cols, vec![3]);
+
+        // The transposed problem leaves row 3 unassigned.
+        let soln = solve_sparse(&c.transpose(), Objective::Minimize).unwrap();
+        assert_eq!(soln.assignment, vec![(0, 2), (1, 0), (2, 1)]);
+        assert_eq!(soln.unmatched_rows, vec![3]);
+
+        // Row 1 takes column 0 from row 0, which lowers v_0 below 0: a signed
+        // type is needed.
+        let edges = [(0, 0, 1), (0, 1, 5), (1, 0, 1), (1, 1, 9)];
+        let soln = solve_sparse(&SparseCostMatrix::<i64>::from_edges(2, 2, &edges).unwrap(), Objective::Minimize).unwrap();
+        assert_eq!(soln.assignment, vec![(0, 1), (1, 0)]);
+        assert_eq!((soln.u, soln.v), (vec![5, 5], vec![-4, 0]));
+        let unsigned: Vec<(usize, usize, u64)> = edges.iter().map(|&(i, j, c)| (i, j, c as u64)).collect();
+        let c = SparseCostMatrix::from_edges(2, 2, &unsigned).unwrap();
+        assert_eq!(solve_sparse(&c, Objective::Minimize).unwrap_err(), AssignmentError::Overflow { operation: "v_j - d" });
+
+        // Rows 0 and 1 share column 0 only.
+        let c = SparseCostMatrix::from_edges(3, 3, &[(0, 0, 1), (1, 0, 2), (2, 1, 3)]).unwrap();
+        let hall_violator = HallViolator {
+                side: Side::Rows,
+                members: vec![0, 1],
+                neighbours: vec![0],
+        };
+        assert_eq!(solve_sparse(&c, Objective::Minimize).unwrap_err(), AssignmentError::NoPerfectMatching { hall_violator });
+
+        assert_eq!(SparseCostMatrix::from_edges(2, 2, &[(0, 2, 1)]).unwrap_err(), AssignmentError::EdgeOutOfRange { row: 0, col: 2, nrows: 2, ncols: 2 });
+        assert_eq!(SparseCostMatrix::from_edges(2, 2, &[(1, 1, 1), (1, 1, 2)]).unwrap_err(), AssignmentError::DuplicateEdge { row: 1, col: 1 });
+        assert_eq!(SparseCostMatrix::<i32>::from_edges(0, 2, &[]).unwrap_err(), AssignmentError::EmptyMatrix);
+}
@@ -0,0 +1,29 @@
+use maths::comb_optimization::{birkhoff_decomposition, BirkhoffError, Side};
+use maths::number_theory::Rational;
+use nalgebra::DMatrix;
+
+#[test]
+fn test_birkhoff_decomposition() {
+        // 1/2 on the diagonal and 1/4 elsewhere: 1/2 of the identity and 1/4 of each 3-cycle.
+        let quarter = |k: i64| Rational::new(k, 4).unwrap();
+        let x = DMatrix::from_row_slice(3, 3, &[2, 1, 1, 1, 2, 1, 1, 1, 2]).map(quarter);
+        let decomposition = birkhoff_decomposition(&x).unwrap();
+        assert_eq!(decomposition.line_sum, Rational::from_integer(1));
+        assert_eq!(decomposition.terms.len(), 3);
+        assert_eq!(decomposition.terms[0].coefficient, quarter(2));
+        assert_eq!(decomposition.terms[0].permutation, vec![0, 1, 2]);
+        let mut cycles: Vec<Vec<usize>> = decomposition.terms[1..].iter().map(|term| term.permutation.clone()).collect();
+        cycles.sort();
+        assert_eq!(cycles, vec![vec![1, 2, 0], vec![2, 0, 1]]);
+        assert!(decomposition.terms[1..].iter().all(|term| term.coefficient == quarter(1)));
+
+        // Integer matrices with equal line sums decompose with integer coefficients.
+        let x = DMatrix::from_row_slice(2, 2, &[3, 1, 1, 3]);
+        let decomposition = birkhoff_decomposition(&x).unwrap();
+        assert_eq!(decomposition.line_sum, 4);
+        assert_eq!(decomposition.terms.iter().map(|term| (term.coefficient, term.permutation.clone())).collect::<Vec<_>>(), vec![(3, vec![0, 1]), (1, vec![1, 0])]);
+
+        assert_eq!(birkhoff_decomposition(&DMatrix::from_element(2, 3, 1.0)).unwrap_err(), BirkhoffError::NotSquare { nrows: 2, ncols: 3 });
+        assert_eq!(birkhoff_decomposition(&DMatrix::from_row_slice(2, 2, &[2, -1, -1, 2])).unwrap_err(), BirkhoffError::NegativeEntry { row: 1, col: 0 });
+        assert_eq!(birkhoff_decomposition(&DMatrix::from_row_slice(2, 2, &[0.5, 0.5, 0.25, 0.75])).unwrap_err(), BirkhoffError::UnequalLineSums { side: Side::Columns, index: 0 });
+}
@@ -0,0 +1,97 @@
+use maths::comb_optimization::{solve_gap, GapError, GapMethod, Objective};
+use nalgebra::DMatrix;
+
+// 3 agents with capacities 11, 13 and 10 share 6 jobs; the optimal cost is 23,
+// one more than with the capacities ignored.
+const C: [i32; 18] = [9, 2, 7, 8, 3, 12, 6, 4, 3, 7, 9, 5, 5, 8, 1, 8, 6, 4];
+const SIZES: [i32; 18] = [4, 5, 3, 4, 2, 6, 3, 6, 4, 2, 5, 3, 5, 3, 6, 4, 4, 2];
+const CAPACITIES: [i32; 3] = [11, 13, 10];
+
+fn costs() -> DMatrix<Option<i32>> {
+        DMatrix::from_row_slice(3, 6, &C).map(Some)
+}
+
+#[test]
+fn test_exact() {
+        let sizes = DMatrix::from_row_slice(3, 6, &SIZES);
+        let soln = solve_gap(&costs(), &sizes, &CAPACITIES, Objective::Minimize, GapMethod::Exact).unwrap();
+        assert!(soln.optimal);
+        assert_eq!(soln.cost, 23);
+        assert_eq!(soln.assignment, vec![(0, 1), (0, 4), (1, 0), (1, 3), (2, 2), (2, 5)]);
+        assert_eq!(soln.loads, vec![7, 5, 8]);
+        assert!(soln.bound <= soln.cost as f64 + 1e-9);
+        assert!(soln.heuristic_cost.unwrap() >= soln.cost);
+
+        // The heuristics alone never beat the exact cost.
+        let heuristic = solve_gap(&costs(), &sizes, &CAPACITIES, Objective::Minimize, GapMethod::Heuristic).unwrap();
+        assert_eq!(heuristic.nodes, 0);
+        assert_eq!(Some(heuristic.cost), soln.heuristic_cost);
+        assert!(heuristic.cost >= soln.cost);
+}
+
+#[test]
+fn test_capacities_bind() {
+        // Both jobs are cheapest on agent 0, which only has room for one of them.
+        let c = DMatrix::from_row_slice(2, 2, &[1.0, 1.5, 4.0, 6.0]).map(Some);
+        let sizes = DMatrix::from_row_slice(2, 2, &[2.0, 2.0, 1.0, 1.0]);
+        let soln = solve_gap(&c, &sizes, &[3.0, 3.0], Objective::Minimize, GapMethod::Exact).unwrap();
+        assert_eq!(soln.assignment, vec![(0, 1), (1, 0)]);
+        assert_eq!(soln.cost, 5.5);
+        assert_eq!(soln.loads, vec![2.0, 1.0]);
+
+        let soln = solve_gap(&c, &sizes, &[3.0, 3.0], Objective::Maximize, GapMethod::Exact).unwrap();
+        assert_eq!(soln.assignment, vec![(1, 0), (1, 1)]);
+        assert_eq!(soln.cost, 10.0);
+        assert!(soln.bound >= 10.0 - 1e-9);
+}
+
+#[test]
+fn test_gap_errors() {
+        let sizes = DMatrix::from_row_slice(3, 6, &SIZES);
+        assert_eq!(solve_gap(&costs(), &sizes, &CAPACITIES[..2], Objective::Minimize, GapMethod::Exact).unwrap_err(), GapError::LengthMismatch { expected: 3, found: 2 });
+        let short = DMatrix::from_row_slice(3, 5, &SIZES[..15]);
+        assert_eq!(solve_gap(&costs(), &short, &CAPACITIES, Objective::Minimize, GapMethod::Exact).unwrap_err(), GapError::ShapeMismatch { expected: (3, 6), found: (3, 5) });
+        assert_eq!(solve_gap(&costs(), &sizes, &[11, -1, 10], Objective::Minimize, GapMethod::Exact).unwrap_err(), GapError::NegativeCapacity { row: 1 });
+
+        // Job 2 is forbidden for agent 0 and too large for the others.
+        let mut c = costs();
+        c[(0, 2)] = None;
+        assert_eq!(solve_gap(&c, &sizes, &[11, 3, 5], Objective::Minimize, GapMethod::Exact).unwrap_err(), GapError::JobDoesNotFit { col: 2 });
+
+        // Every job fits on its own, but the total size of 18 exceeds the capacity of 12.
+        let c = DMatrix::from_element(2, 3, Some(1u64));
+        let sizes = DMatrix::from_element(2, 3, 6u64);
+        assert_eq!(solve_gap(&c, &sizes, &[6, 6], Objective::Minimize, GapMethod::Exact).unwrap_err(), GapError::Infeasible);
+        assert_eq!(solve_gap(&c, &sizes, &[6, 6], Objective::Minimize, GapMethod::Heuristic).unwrap_err(), GapError::NoSolutionFound);
+}
+
+#[test]
+fn test_auto_and_large_costs() {
+        // Auto solves the small example exactly, like the exact method.
+        let sizes = DMatrix::from_row_slice(3, 6, &SIZES);
+        let auto = solve_gap(&costs(), &sizes, &CAPACITIES, Objective::Minimize, GapMethod::default()).unwrap();
+        assert_eq!(GapMethod::default(), GapMethod::Auto);
+        assert!(auto.optimal && auto.nodes > 0);
+        assert_eq!(auto.cost, 23);
+
+        // 25 jobs are too many for the branch-and-bound of Auto.
+        let c = DMatrix::from_fn(2, 25, |i, j| Some(((i + 3 * j) % 7) as i64));
+        let sizes = DMatrix::from_fn(2, 25, |i, j| ((2 * i + j) % 5 + 1) as i64);
+        let soln = solve_gap(&c, &sizes, &[40, 40], Objective::Minimize, GapMethod::Auto).unwrap();
+        assert_eq!(soln.nodes, 0);
+
+        // Costs near 2^62 differ by less than f64 can tell; the search compares
+        // them exactly, the heuristic's rounded bound proves nothing.
+        let big = 1i64 << 62;
+        let c = DMatrix::from_row_slice(2, 2, &[big + 3, big + 1, big + 2, big + 5]).map(Some);
+        let sizes = DMatrix::from_element(2, 2, 1i64);
+        let soln = solve_gap(&c, &sizes, &[1, 1], Objective::Minimize, GapMethod::Exact);
+        assert_eq!(soln.unwrap_err(), GapError::Overflow { operation: "assignment cost" });
+        let c = c.map(|c_ij| c_ij.map(|c_ij| c_ij / 2));
+        let soln = solve_gap(&c, &sizes, &[1, 1], Objective::Minimize, GapMethod::Exact).unwrap();
+        assert_eq!(soln.assignment, vec![(0, 1), (1, 0)]);
+        assert_eq!(soln.cost, big + 1);
+        assert!(soln.optimal);
+        let heuristic = solve_gap(&c, &sizes, &[1, 1], Objective::Minimize, GapMethod::Heuristic).unwrap();
+        assert!(!heuristic.optimal);
+}
@@ -0,0 +1,72 @@
+use maths::comb_optimization::{weighted_bipartite_matching, GraphMatchingError, Objective};
+use maths::graph_theory::{bipartite_undirected, is_bipartite_undirected};
+use petgraph::graph::{Graph, NodeIndex};
+use petgraph::Undirected;
+
+// Workers w1..w3 and jobs j1..j3, w3 qualified only for j3 and j2 without a
+// positive weight.
+fn example() -> Graph<&'static str, i64, Undirected> {
+        let mut graph = Graph::new_undirected();
+        let w: Vec<NodeIndex> = ["w1", "w2", "w3"].into_iter().map(|name| graph.add_node(name)).collect();
+        let j: Vec<NodeIndex> = ["j1", "j2", "j3"].into_iter().map(|name| graph.add_node(name)).collect();
+        for (a, b, weight) in [(0, 0, 4), (0, 2, 6), (1, 0, 5), (1, 1, -2), (2, 2, 3)] {
+                graph.add_edge(w[a], j[b], weight);
+        }
+        graph
+}
+
+#[test]
+fn test_weighted_bipartite_matching() {
+        let graph = example();
+        let n = NodeIndex::new;
+
+        let best = weighted_bipartite_matching(&graph, Objective::Maximize, false).unwrap();
+        assert_eq!(best.edges, vec![(n(0), n(5), 6), (n(1), n(3), 5)]);
+        assert_eq!(best.total_weight, 11);
+
+        // w3 is matched to j3 only at the price of w1's best edge.
+        let complete = weighted_bipartite_matching(&graph, Objective::Maximize, true).unwrap();
+        assert_eq!(complete.edges, vec![(n(0), n(3), 4), (n(1), n(4), -2), (n(2), n(5), 3)]);
+        assert_eq!(complete.total_weight, 5);
+
+        let cheapest = weighted_bipartite_matching(&graph, Objective::Minimize, false).unwrap();
+        assert_eq!(cheapest.edges, vec![(n(1), n(4), -2)]);
+        assert_eq!(cheapest.total_weight, -2);
+
+        let cheapest_complete = weighted_bipartite_matching(&graph, Objective::Minimize, true).unwrap();
+        assert_eq!(cheapest_complete.total_weight, 5);
+
+        // Parallel edges count with the better weight for the objective.
+        let mut parallel = example();
+        parallel.add_edge(n(2), n(5), 9);
+        assert_eq!(weighted_bipartite_matching(&parallel, Objective::Maximize, false).unwrap().total_weight, 14);
+        assert_eq!(weighted_bipartite_matching(&parallel, Objective::Minimize, true).unwrap().total_weight, 5);
+
+        let mut triangle = example();
+        triangle.add_edge(n(0), n(1), 1);
+        assert_eq!(weighted_bipartite_matching(&triangle, Objective::Maximize, false), Err(GraphMatchingError::NotBipartite));
+}
+
+#[test]
+fn test_bipartite_sides() {
+        // The path a - b - d - c, listed out of order, and the isolated e.
+        let mut graph: Graph<&str, f64, Undirected> = Graph::new_undirected();
+        let (a, b, c, d, e) = (graph.add_node("a"), graph.add_node("b"), graph.add_node("c"), graph.add_node("d"), graph.add_node("e"));
+        graph.add_edge(b, a, 1.5);
+        graph.add_edge(c, d, 2.0);
+        graph.add_edge(b, d, 0.5);
+
+        let sides = bipartite_undirected(&graph).unwrap();
+        assert_eq!(sides.nodes_u, vec![a, d, e]);
+        assert_eq!(sides.nodes_v, vec![b, c]);
+        assert!(is_bipartite_undirected(&graph));
+
+        // Edges are matched whichever end was added first.
+        let matching = weighted_bipartite_matching(&graph, Objective::Maximize, false).unwrap();
+        assert_eq!(matching.edges, vec![(a, b, 1.5), (d, c, 2.0)]);
+        assert_eq!(matching.total_weight, 3.5);
+
+        graph.add_edge(a, a, 1.0);
+        assert!(bipartite_undirected(&graph).is_none());
+        assert!(!is_bipartite_undirected(&graph));
+}
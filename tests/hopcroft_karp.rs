@@ -0,0 +1,51 @@
+use maths::graph_theory::{bipartite_undirected, hopcroft_karp, BipartiteGraph};
+use petgraph::algo::maximum_matching;
+use petgraph::graph::{Graph, NodeIndex};
+use petgraph::Undirected;
+
+#[test]
+fn test_hopcroft_karp_phases() {
+        let mut graph: Graph<String, String, Undirected> = Graph::new_undirected();
+        let u: Vec<NodeIndex> = (1..=3).map(|i| graph.add_node(format!("u{}", i))).collect();
+        let v: Vec<NodeIndex> = (1..=3).map(|j| graph.add_node(format!("v{}", j))).collect();
+        for (i, j) in [(0, 0), (0, 1), (1, 0), (2, 1), (2, 2)] {
+                graph.add_edge(u[i], v[j], format!("u{}-v{}", i + 1, j + 1));
+        }
+        let sides = BipartiteGraph {
+                nodes_u: u.clone(),
+                nodes_v: v.clone(),
+        };
+        let result = hopcroft_karp(&graph, &sides);
+        assert_eq!(result.matching, vec![(u[0], v[1]), (u[1], v[0]), (u[2], v[2])]);
+
+        // Phase 1 matches u1-v1 and u3-v2 directly; u2 then needs the path u2 v1 u1 v2 u3 v3.
+        assert_eq!(result.phases.len(), 3);
+        assert_eq!(result.phases[0].layers, vec![u.clone(), v.clone()]);
+        assert_eq!(result.phases[0].augmenting_paths, vec![vec![u[0], v[0]], vec![u[2], v[1]]]);
+        assert_eq!(result.phases[1].layers, vec![vec![u[1]], vec![v[0]], vec![u[0]], vec![v[1]], vec![u[2]], vec![v[2]]]);
+        assert_eq!(result.phases[1].augmenting_paths, vec![vec![u[1], v[0], u[0], v[1], u[2], v[2]]]);
+        // No free vertex of U is left, so the last phase finds nothing.
+        assert_eq!(result.phases[2].layers, vec![Vec::<NodeIndex>::new()]);
+        assert!(result.phases[2].augmenting_paths.is_empty());
+}
+
+#[test]
+fn test_hopcroft_karp_maximum() {
+        // A graph without a perfect matching, with sides found by `bipartite_undirected`:
+        // s1, s2 and s3 all have only t1 and t2 as partners.
+        let mut graph: Graph<&str, (), Undirected> = Graph::new_undirected();
+        let s: Vec<NodeIndex> = ["s1", "s2", "s3", "s4"].iter().map(|name| graph.add_node(*name)).collect();
+        let t: Vec<NodeIndex> = ["t1", "t2", "t3", "t4"].iter().map(|name| graph.add_node(*name)).collect();
+        for (i, j) in [(0, 0), (0, 1), (1, 0), (1, 1), (2, 1), (3, 2), (3, 3)] {
+                graph.add_edge(s[i], t[j], ());
+        }
+        let sides = bipartite_undirected(&graph).unwrap();
+        let result = hopcroft_karp(&graph, &sides);
+        assert_eq!(result.matching.len(), 3);
+        assert_eq!(result.matching.len(), maximum_matching(&graph).edges().count());
+        for &(a, b) in &result.matching {
+                assert!(graph.contains_edge(a, b));
+        }
+        let total: usize = result.phases.iter().map(|phase| phase.augmenting_paths.len()).sum();
+        assert_eq!(total, 3);
+}
@@ -1,8 +1,9 @@
 pub use maths::comb_optimization::hungarian::hungarian_maximum_matching;
+use maths::comb_optimization::hungarian::{Color, HungarianStep, StepVertex};
 pub use maths::graph_theory::bipartite::bipartite_undirected;
+use maths::graph_theory::bipartite::BipartiteGraph;
 use petgraph::graph::{Graph, NodeIndex};
 use petgraph::Undirected;
-use std::collections::HashMap;
 use std::collections::HashSet;
 
 #[test]
@@ -38,17 +39,11 @@ fn test_hungarian_maximum_matching() {
                 input_max_matching.insert((b, g));
                 input_max_matching.insert((c, h));
                 input_max_matching.insert((e, j));
-                let mut node_index_weight_map: HashMap<usize, String> = HashMap::new();
-
-                for node in graph.node_indices() {
-                        let weight = String::from(graph.node_weight(node).unwrap());
-                        node_index_weight_map.insert(node.index(), weight.clone());
-                }
 
                 let g_vertex_u_v_sets = bipartite_undirected(&graph).unwrap();
                 let hungarian_output = hungarian_maximum_matching(
                         &graph,
-                        node_index_weight_map,
+                        |node| graph[node].clone(),
                         input_max_matching,
                         &g_vertex_u_v_sets,
                 )
@@ -90,17 +85,11 @@ fn test_hungarian_maximum_matching() {
                 let mut input_max_matching: HashSet<(NodeIndex, NodeIndex)> = HashSet::new();
                 input_max_matching.insert((b, h));
                 input_max_matching.insert((e, i));
-                let mut node_index_weight_map: HashMap<usize, String> = HashMap::new();
-
-                for node in graph.node_indices() {
-                        let weight = String::from(graph.node_weight(node).unwrap());
-                        node_index_weight_map.insert(node.index(), weight.clone());
-                }
 
                 let g_vertex_u_v_sets = bipartite_undirected(&graph).unwrap();
                 let hungarian_output = hungarian_maximum_matching(
                         &graph,
-                        node_index_weight_map,
+                        |node| graph[node].clone(),
                         input_max_matching,
                         &g_vertex_u_v_sets,
                 )
@@ -134,17 +123,11 @@ fn test_hungarian_maximum_matching() {
                 let mut input_max_matching: HashSet<(NodeIndex, NodeIndex)> = HashSet::new();
                 input_max_matching.insert((a, f));
                 input_max_matching.insert((d, e));
-                let mut node_index_weight_map: HashMap<usize, String> = HashMap::new();
-
-                for node in graph.node_indices() {
-                        let weight = String::from(graph.node_weight(node).unwrap());
-                        node_index_weight_map.insert(node.index(), weight.clone());
-                }
 
                 let g_vertex_u_v_sets = bipartite_undirected(&graph).unwrap();
                 let hungarian_output = hungarian_maximum_matching(
                         &graph,
-                        node_index_weight_map,
+                        |node| graph[node].clone(),
                         input_max_matching,
                         &g_vertex_u_v_sets,
                 )
@@ -155,5 +138,56 @@ fn test_hungarian_maximum_matching() {
                 println!("{:?}", min_cover);
                 assert_eq!(min_cover, min_cover_test);
                 println!("{:?}", max_matching);
+
+                // s2 is matched to t3 by the path s2-t3; the tree from s3 then reaches only t2, matched to s1.
+                let v = |node: NodeIndex| StepVertex {
+                        node,
+                        label: graph[node].clone(),
+                };
+                let expected_steps = vec![
+                        HungarianStep::Start { matching: vec![(v(a), v(f)), (v(d), v(e))] },
+                        HungarianStep::ExposedVertex { vertex: v(b) },
+                        HungarianStep::Colored { vertex: v(b), color: Color::Blue },
+                        HungarianStep::Colored { vertex: v(g), color: Color::Red },
+                        HungarianStep::TreeGrown { parent: v(b), child: v(g), matched: false },
+                        HungarianStep::AugmentingPath { path: vec![v(b), v(g)] },
+                        HungarianStep::Augmented { matching: vec![(v(a), v(f)), (v(b), v(g)), (v(d), v(e))] },
+                        HungarianStep::ExposedVertex { vertex: v(c) },
+                        HungarianStep::Colored { vertex: v(c), color: Color::Blue },
+                        HungarianStep::Colored { vertex: v(f), color: Color::Red },
+                        HungarianStep::TreeGrown { parent: v(c), child: v(f), matched: false },
+                        HungarianStep::Colored { vertex: v(a), color: Color::Blue },
+                        HungarianStep::TreeGrown { parent: v(f), child: v(a), matched: true },
+                        HungarianStep::Cover { cover: vec![v(b), v(d), v(f)] },
+                ];
+                assert_eq!(hungarian_output.2, expected_steps);
         }
 }
+
+#[test]
+fn test_hungarian_node_labels() {
+        // Node weights of any type, here (name, id), are labelled by a closure.
+        let mut graph: Graph<(&str, u32), u32, Undirected> = Graph::new_undirected();
+        let alice = graph.add_node(("alice", 7));
+        let bob = graph.add_node(("bob", 9));
+        let weld = graph.add_node(("weld", 1));
+        let paint = graph.add_node(("paint", 2));
+        graph.add_edge(alice, weld, 3);
+        graph.add_edge(alice, paint, 5);
+        graph.add_edge(bob, weld, 4);
+
+        let sides = BipartiteGraph {
+                nodes_u: vec![alice, bob],
+                nodes_v: vec![weld, paint],
+        };
+        let (min_cover, max_matching, steps) = hungarian_maximum_matching(&graph, |node| format!("{} #{}", graph[node].0, graph[node].1), HashSet::new(), &sides).unwrap();
+        assert_eq!(min_cover, vec![alice, bob]);
+        assert_eq!(max_matching, HashSet::from([(alice, paint), (bob, weld)]));
+
+        // bob reaches paint through weld and alice.
+        let path = steps.iter().rev().find_map(|step| match step {
+                HungarianStep::AugmentingPath { path } => Some(path.iter().map(|vertex| vertex.label.as_str()).collect::<Vec<_>>()),
+                _ => None,
+        });
+        assert_eq!(path, Some(vec!["bob #9", "weld #1", "alice #7", "paint #2"]));
+}
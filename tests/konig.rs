@@ -0,0 +1,50 @@
+use maths::graph_theory::{konig_cover, verify_konig, BipartiteGraph, KonigViolation};
+use petgraph::graph::{Graph, NodeIndex};
+use petgraph::Undirected;
+
+// The first graph of tests/hungarian.rs: s1..s5 and t1..t5 with a maximum matching of 4.
+fn example() -> (Graph<String, (), Undirected>, BipartiteGraph<NodeIndex>) {
+        let mut graph: Graph<String, (), Undirected> = Graph::new_undirected();
+        let s: Vec<NodeIndex> = (1..=5).map(|i| graph.add_node(format!("s{}", i))).collect();
+        let t: Vec<NodeIndex> = (1..=5).map(|j| graph.add_node(format!("t{}", j))).collect();
+        for (i, j) in [(1, 2), (1, 3), (2, 1), (2, 2), (2, 4), (2, 5), (3, 2), (3, 3), (4, 2), (4, 3), (5, 4), (5, 5)] {
+                graph.add_edge(s[i - 1], t[j - 1], ());
+        }
+        (graph, BipartiteGraph { nodes_u: s, nodes_v: t })
+}
+
+#[test]
+fn test_konig_cover() {
+        let (graph, sides) = example();
+        let cover = konig_cover(&graph, &sides);
+        assert_eq!(cover.matching.len(), 4);
+        // s1, s3 and s4 have only t2 and t3 as partners, so t2 and t3 are in every minimum cover.
+        assert_eq!(cover.cover_u, vec![NodeIndex::new(1), NodeIndex::new(4)]);
+        assert_eq!(cover.cover_v, vec![NodeIndex::new(6), NodeIndex::new(7)]);
+        assert_eq!(cover.independent_u, vec![NodeIndex::new(0), NodeIndex::new(2), NodeIndex::new(3)]);
+        assert_eq!(cover.independent_v, vec![NodeIndex::new(5), NodeIndex::new(8), NodeIndex::new(9)]);
+        assert!(verify_konig(&graph, &sides, &cover).is_empty());
+}
+
+#[test]
+fn test_verify_konig() {
+        let (graph, sides) = example();
+        let cover = konig_cover(&graph, &sides);
+
+        // Dropping t2 from the cover uncovers its edges and breaks |M| = |K|.
+        let mut smaller = cover.clone();
+        smaller.cover_v.retain(|&v| v != NodeIndex::new(6));
+        smaller.independent_v.push(NodeIndex::new(6));
+        let violations = verify_konig(&graph, &sides, &smaller);
+        assert!(violations.contains(&KonigViolation::UncoveredEdge { u: NodeIndex::new(0), v: NodeIndex::new(6) }));
+        assert!(violations.contains(&KonigViolation::SizeMismatch { matching: 4, cover: 3 }));
+
+        let mut bad_matching = cover.clone();
+        bad_matching.matching.push((NodeIndex::new(0), NodeIndex::new(5)));
+        let violations = verify_konig(&graph, &sides, &bad_matching);
+        assert!(violations.contains(&KonigViolation::NotAnEdge { u: NodeIndex::new(0), v: NodeIndex::new(5) }));
+
+        let mut overlapping = cover;
+        overlapping.independent_u.push(NodeIndex::new(1));
+        assert_eq!(verify_konig(&graph, &sides, &overlapping), vec![KonigViolation::NotComplementary { vertex: NodeIndex::new(1) }]);
+}
@@ -0,0 +1,80 @@
+use maths::comb_optimization::{gilmore_lawler_bound, parse_qaplib, read_qaplib, solve_qap, QapError, QapMethod};
+use nalgebra::DMatrix;
+
+// 5 facilities on 5 locations; the optimal cost is 50, reached by two
+// placements, as locations 1 and 2 are interchangeable.
+const QAPLIB: &str = "5
+
+ 0 5 2 4 1
+ 5 0 3 0 2
+ 2 3 0 0 0
+ 4 0 0 0 5
+ 1 2 0 5 0
+
+ 0 1 1 2 3
+ 1 0 2 1 2
+ 1 2 0 1 2
+ 2 1 1 0 1
+ 3 2 2 1 0
+";
+
+fn cost(flow: &DMatrix<i64>, distance: &DMatrix<i64>, p: &[usize]) -> i64 {
+        (0..p.len()).flat_map(|i| (0..p.len()).map(move |j| (i, j))).map(|(i, j)| flow[(i, j)] * distance[(p[i], p[j])]).sum()
+}
+
+#[test]
+fn test_exact() {
+        let (flow, distance) = parse_qaplib::<i64>(QAPLIB).unwrap();
+        let soln = solve_qap(&flow, &distance, QapMethod::Exact).unwrap();
+        assert!(soln.optimal);
+        assert_eq!(soln.cost, 50);
+        assert!([vec![1, 3, 4, 0, 2], vec![2, 3, 4, 0, 1]].contains(&soln.assignment));
+        assert_eq!(cost(&flow, &distance, &soln.assignment), 50);
+        assert!(soln.heuristic_cost >= 50);
+        assert!(soln.bound <= 50);
+        assert_eq!(gilmore_lawler_bound(&flow, &distance).unwrap(), soln.bound);
+
+        // Small problems are solved exactly by default.
+        assert_eq!(solve_qap(&flow, &distance, QapMethod::Auto).unwrap().nodes, soln.nodes);
+}
+
+#[test]
+fn test_local_search() {
+        // 14 locations on a 2 x 7 grid, beyond the exact default.
+        let n = 14;
+        let flow = DMatrix::from_fn(n, n, |i, j| ((i * 7 + j * 3) % 5) as u64);
+        let distance = DMatrix::from_fn(n, n, |k, m| ((k % 7).abs_diff(m % 7) + (k / 7).abs_diff(m / 7)) as u64);
+        let soln = solve_qap(&flow, &distance, QapMethod::Auto).unwrap();
+        assert_eq!(soln.nodes, 0);
+        assert_eq!(soln.cost, soln.heuristic_cost);
+        assert!(soln.bound <= soln.cost);
+        let mut locations = soln.assignment.clone();
+        locations.sort_unstable();
+        assert_eq!(locations, (0..n).collect::<Vec<_>>());
+        let total: u64 = (0..n).flat_map(|i| (0..n).map(move |j| (i, j))).map(|(i, j)| flow[(i, j)] * distance[(soln.assignment[i], soln.assignment[j])]).sum();
+        assert_eq!(soln.cost, total);
+}
+
+#[test]
+fn test_read_qaplib() {
+        let path = std::env::temp_dir().join("maths_qap_test.dat");
+        std::fs::write(&path, QAPLIB).unwrap();
+        let (flow, distance) = read_qaplib::<f64>(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(flow[(3, 4)], 5.0);
+        assert_eq!(distance[(4, 0)], 3.0);
+        assert_eq!(solve_qap(&flow, &distance, QapMethod::Exact).unwrap().cost, 50.0);
+
+        assert_eq!(parse_qaplib::<i64>("").unwrap_err(), QapError::EmptyMatrix);
+        assert!(matches!(parse_qaplib::<i64>("2\n1 2 3 4\n5 6 7").unwrap_err(), QapError::InvalidInput { .. }));
+        assert!(matches!(parse_qaplib::<u64>("1\n-1 2").unwrap_err(), QapError::InvalidInput { .. }));
+}
+
+#[test]
+fn test_qap_errors() {
+        let square = DMatrix::from_element(3, 3, 1);
+        let wide = DMatrix::from_element(3, 4, 1);
+        assert_eq!(solve_qap(&wide, &square, QapMethod::Exact).unwrap_err(), QapError::NotSquare { matrix: "flow", nrows: 3, ncols: 4 });
+        let small = DMatrix::from_element(2, 2, 1);
+        assert_eq!(solve_qap(&square, &small, QapMethod::Exact).unwrap_err(), QapError::SizeMismatch { flow: 3, distance: 2 });
+}
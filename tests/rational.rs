@@ -0,0 +1,44 @@
+use maths::comb_optimization::{new_solver, SolverKind};
+use maths::number_theory::Rational;
+use nalgebra::DMatrix;
+
+#[test]
+fn test_rational_arithmetic() {
+        let third = Rational::new(1, 3).unwrap();
+        let half = "0.5".parse::<Rational>().unwrap();
+        assert_eq!(third.checked_add(half), Rational::new(5, 6));
+        assert_eq!(third.checked_sub(half), Rational::new(-1, 6));
+        assert_eq!(third.checked_div(half), Rational::new(2, 3));
+        assert_eq!(third.checked_div(Rational::from_integer(0)), None);
+        assert_eq!(Rational::from_integer(i64::MAX).checked_add(Rational::from_integer(1)), None);
+        assert!(third < half);
+
+        // Lowest terms with a positive denominator.
+        let x = Rational::new(6, -4).unwrap();
+        assert_eq!((x.numer(), x.denom()), (-3, 2));
+        assert_eq!(x.to_string(), "-3/2");
+        assert_eq!(Rational::new(1, 0), None);
+}
+
+#[test]
+fn test_rational_serde() {
+        assert_eq!(serde_json::from_str::<Rational>("\"7/21\"").unwrap(), Rational::new(1, 3).unwrap());
+        assert_eq!(serde_json::from_str::<Rational>("0.1").unwrap(), Rational::new(1, 10).unwrap());
+        assert_eq!(serde_json::from_str::<Rational>("-4").unwrap(), Rational::from_integer(-4));
+        assert_eq!(serde_json::to_string(&Rational::new(3, 9).unwrap()).unwrap(), "\"1/3\"");
+        assert!(serde_json::from_str::<Rational>("\"1/0\"").is_err());
+        assert!("1.2.3".parse::<Rational>().is_err());
+}
+
+#[test]
+fn test_rational_costs() {
+        // Thirds have no exact floating point value; the optimum is exactly 2.
+        let c = DMatrix::from_row_slice(3, 3, &[1, 5, 3, 2, 4, 6, 3, 1, 2]).map(|k| Rational::new(k, 3).unwrap());
+        for kind in [SolverKind::Hungarian, SolverKind::ShortestPath, SolverKind::Auction] {
+                let mut solver = new_solver::<Rational>(kind);
+                solver.init(Vec::new(), Vec::new(), c.clone());
+                let soln = solver.find_optimum_cost().unwrap();
+                assert_eq!(soln.cost, Rational::from_integer(2));
+                assert_eq!(soln.assignment, vec![(0, 2), (1, 0), (2, 1)]);
+        }
+}
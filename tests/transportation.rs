@@ -0,0 +1,141 @@
+use maths::comb_optimization::{solve_transportation, InitialMethod, Side, TransportationError};
+use nalgebra::DMatrix;
+
+// Supplies 7, 9, 18 and demands 5, 8, 7, 14; the optimal cost is 743.
+const C: [i32; 12] = [19, 30, 50, 10, 70, 30, 40, 60, 40, 8, 70, 20];
+const SUPPLY: [i32; 3] = [7, 9, 18];
+const DEMAND: [i32; 4] = [5, 8, 7, 14];
+
+#[test]
+fn test_initial_methods() {
+        let c = DMatrix::from_row_slice(3, 4, &C);
+        // Cost of the initial solution of each method: 1015, 814 and 779.
+        for (method, initial_cost) in [(InitialMethod::NorthwestCorner, 1015), (InitialMethod::LeastCost, 814), (InitialMethod::Vogel, 779)] {
+                let soln = solve_transportation(&SUPPLY, &DEMAND, &c, method).unwrap();
+                let x = &soln.states[0].x;
+                assert_eq!(x.component_mul(&c).sum(), initial_cost);
+                assert_eq!(soln.cost, 743);
+                assert_eq!(soln.shipments, vec![(0, 0, 5), (0, 3, 2), (1, 1, 2), (1, 2, 7), (2, 1, 6), (2, 3, 12)]);
+                assert_eq!(soln.dummy, None);
+
+                // Every state has m + n - 1 basic cells, every one but the last a pivot.
+                assert!(soln.states.iter().all(|state| state.basis.len() == 6));
+                assert_eq!(soln.states.len(), soln.iterations + 1);
+                assert!(soln.states.last().unwrap().entering.is_none());
+                for state in &soln.states[..soln.iterations] {
+                        let (i, j) = state.entering.unwrap();
+                        assert!(state.c[(i, j)] < 0);
+                        let cycle = state.cycle.as_ref().unwrap();
+                        assert_eq!(cycle[0], (i, j));
+                        assert!(cycle.len() % 2 == 0 && cycle.len() >= 4);
+                        assert!(cycle.contains(&state.leaving.unwrap()));
+                }
+        }
+}
+
+#[test]
+fn test_potentials() {
+        let c = DMatrix::from_row_slice(3, 4, &C);
+        let soln = solve_transportation(&SUPPLY, &DEMAND, &c, InitialMethod::Vogel).unwrap();
+        for i in 0..3 {
+                for j in 0..4 {
+                        assert!(soln.u[i] + soln.v[j] <= c[(i, j)]);
+                }
+        }
+        for &(i, j, _) in &soln.shipments {
+                assert_eq!(soln.u[i] + soln.v[j], c[(i, j)]);
+        }
+        let dual: i32 = SUPPLY.iter().zip(&soln.u).map(|(s, u)| s * u).sum::<i32>() + DEMAND.iter().zip(&soln.v).map(|(d, v)| d * v).sum::<i32>();
+        assert_eq!(dual, 743);
+}
+
+#[test]
+fn test_unbalanced() {
+        // 5 units of supply are left over.
+        let c = DMatrix::from_row_slice(2, 2, &[4.0, 6.0, 5.0, 3.0]);
+        let soln = solve_transportation(&[10.0, 10.0], &[8.0, 7.0], &c, InitialMethod::LeastCost).unwrap();
+        assert_eq!(soln.dummy, Some(Side::Columns));
+        assert_eq!(soln.shipments, vec![(0, 0, 8.0), (1, 1, 7.0)]);
+        assert_eq!(soln.cost, 53.0);
+        assert_eq!(soln.unused_supply, vec![2.0, 3.0]);
+        assert_eq!(soln.unmet_demand, vec![0.0, 0.0]);
+        assert_eq!(soln.states[0].x.ncols(), 3);
+        // Unused supply is worth nothing: u_i <= 0.
+        assert!(soln.u.iter().all(|&u_i| u_i <= 0.0));
+
+        // 4 units of demand cannot be met.
+        let soln = solve_transportation(&[5, 6], &[7, 8], &DMatrix::from_row_slice(2, 2, &[1, 2, 3, 1]), InitialMethod::NorthwestCorner).unwrap();
+        assert_eq!(soln.dummy, Some(Side::Rows));
+        assert_eq!(soln.unmet_demand.iter().sum::<i32>(), 4);
+        assert_eq!(soln.cost, 11);
+}
+
+#[test]
+fn test_degenerate() {
+        // Row 0 and column 0 run out at the same time, so a zero shipment is basic.
+        let c = DMatrix::from_row_slice(2, 2, &[1, 5, 5, 1]);
+        let soln = solve_transportation(&[3, 4], &[3, 4], &c, InitialMethod::NorthwestCorner).unwrap();
+        let first = &soln.states[0];
+        assert_eq!(first.basis, vec![(0, 0), (1, 0), (1, 1)]);
+        assert_eq!(first.x[(1, 0)], 0);
+        assert_eq!(soln.cost, 7);
+        assert_eq!(soln.iterations, 0);
+
+        // Two losing cells reach zero in one pivot; the first by row and column
+        // leaves, the other stays basic.
+        let c = DMatrix::from_row_slice(2, 2, &[5, 1, 1, 5]);
+        let soln = solve_transportation(&[3, 3], &[3, 3], &c, InitialMethod::NorthwestCorner).unwrap();
+        assert_eq!(soln.cost, 6);
+        let pivot = &soln.states[0];
+        assert_eq!(pivot.cycle, Some(vec![(0, 1), (1, 1), (1, 0), (0, 0)]));
+        assert_eq!((pivot.theta, pivot.leaving), (Some(3), Some((0, 0))));
+        let last = soln.states.last().unwrap();
+        assert_eq!(last.basis.len(), 3);
+        assert!(last.basis.contains(&(1, 1)));
+        assert_eq!(last.x[(1, 1)], 0);
+}
+
+#[test]
+fn test_bland() {
+        // An assignment problem as a transportation problem: every loop has zero
+        // shipments to lose, and three of the four pivots move θ = 0.
+        let c = DMatrix::from_row_slice(3, 3, &[5, 9, 4, 5, 5, 8, 3, 9, 5]);
+        let soln = solve_transportation(&[1, 1, 1], &[1, 1, 1], &c, InitialMethod::NorthwestCorner).unwrap();
+        assert_eq!(soln.cost, 12);
+        assert_eq!(soln.shipments, vec![(0, 2, 1), (1, 1, 1), (2, 0, 1)]);
+        let thetas: Vec<Option<i32>> = soln.states.iter().map(|state| state.theta).collect();
+        assert_eq!(thetas, vec![Some(0), Some(0), Some(1), Some(0), None]);
+
+        // (1, 0) and (2, 1) both drop to zero in the first pivot; (1, 0) comes first.
+        let first = &soln.states[0];
+        assert_eq!(first.cycle, Some(vec![(2, 0), (1, 0), (1, 1), (2, 1)]));
+        assert_eq!(first.leaving, Some((1, 0)));
+
+        let mut bases = Vec::new();
+        for state in &soln.states {
+                // The first negative reduced cost enters, so no cell before it is negative.
+                if let Some((i, j)) = state.entering {
+                        assert!((0..3).flat_map(|k| (0..3).map(move |l| (k, l))).take_while(|&cell| cell != (i, j)).all(|cell| state.c[cell] >= 0));
+                        assert!(state.c[(i, j)] < 0);
+                }
+                // No basis comes back, degenerate pivots or not.
+                let mut basis = state.basis.clone();
+                basis.sort_unstable();
+                assert!(!bases.contains(&basis));
+                bases.push(basis);
+        }
+}
+
+#[test]
+fn test_errors() {
+        let c = DMatrix::from_row_slice(2, 2, &[1, 2, 3, 4]);
+        assert_eq!(solve_transportation(&[1], &[1, 0], &c, InitialMethod::Vogel).unwrap_err(), TransportationError::LengthMismatch { side: Side::Rows, expected: 2, found: 1 });
+        assert_eq!(solve_transportation(&[1, 1], &[1, -2], &c, InitialMethod::Vogel).unwrap_err(), TransportationError::NegativeQuantity { side: Side::Columns, index: 1 });
+        assert_eq!(solve_transportation::<i32>(&[], &[], &DMatrix::from_row_slice(0, 0, &[]), InitialMethod::Vogel).unwrap_err(), TransportationError::EmptyMatrix);
+        assert_eq!(solve_transportation(&[i32::MAX, 1], &[1, 1], &c, InitialMethod::Vogel).unwrap_err(), TransportationError::Overflow { operation: "total supply" });
+
+        // Potentials and reduced costs can be negative, so u64 is rejected up front.
+        let unsigned = DMatrix::from_row_slice(3, 4, &C.map(|c_ij| c_ij as u64));
+        let error = solve_transportation(&SUPPLY.map(|s| s as u64), &DEMAND.map(|d| d as u64), &unsigned, InitialMethod::NorthwestCorner).unwrap_err();
+        assert!(matches!(error, TransportationError::InvalidInput { .. }));
+}